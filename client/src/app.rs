@@ -1,8 +1,6 @@
 use crate::prelude::*;
 
-use crate::core::health::{
-    spawn_health_thread, update_health, update_window_title, HealthChannel, HealthState,
-};
+use crate::core::health::{update_health, update_window_title, HealthState};
 use crate::gameplay::{
     evidence::EvidenceTuning,
     ghost::GhostState,
@@ -10,10 +8,10 @@ use crate::gameplay::{
     map::{HouseLayout, HouseLayoutSelection},
     GameplayPlugin,
 };
+use crate::net::{NetworkConfig, NetworkPlugin};
 use crate::ui::UiPlugin;
 
 pub fn run() {
-    let rx = spawn_health_thread();
     let initial_house = HouseLayout::two_room();
 
     App::new()
@@ -40,27 +38,22 @@ pub fn run() {
             position: initial_house.random_ghost_spawn(),
         })
         .insert_resource(HouseLayoutSelection::default())
-        .insert_resource(EquipmentState {
-            active: crate::core::Equipment::Emf,
-            emf_level: 0,
-            emf_dwell: 0.0,
-            emf_smoothed: 0.0,
-            emf_evidence_latch: 0.0,
-            spiritbox_message: "Silence...".to_string(),
-            spiritbox_cooldown: 0.0,
-        })
+        .insert_resource(EquipmentState::default())
         .insert_resource(GhostTypeState {
             selected: GhostType::Spirit,
             active: GhostType::Spirit,
         })
         .insert_resource(crate::core::SessionState { started: false })
         .insert_resource(crate::core::ResolutionState::default())
+        .insert_resource(crate::core::ProfileState::default())
+        .insert_resource(crate::core::QuickPlayState::default())
+        .insert_resource(crate::core::ChatState::default())
         .insert_resource(EvidenceState::default())
         .insert_resource(EvidenceTuning::default())
         .insert_resource(HealthState {
             status: "loading".to_string(),
         })
-        .insert_resource(HealthChannel { rx })
+        .insert_resource(NetworkConfig::from_env())
         .insert_resource(AmbientLight {
             color: Color::srgb(0.7, 0.75, 0.9),
             brightness: 0.06,
@@ -73,7 +66,7 @@ pub fn run() {
             }),
             ..default()
         }))
-        .add_plugins((GameplayPlugin, UiPlugin))
+        .add_plugins((GameplayPlugin, UiPlugin, NetworkPlugin))
         .add_systems(Update, (update_health, update_window_title))
         .run();
 }
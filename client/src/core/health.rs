@@ -1,37 +1,21 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
-use crossbeam_channel::{self, Receiver};
-use reqwest::blocking::get;
-use shared::prelude::Health;
-use std::thread;
-use std::time::Duration;
+
+use shared::prelude::{UpdateRequired, VersionMismatch, VersionRange};
 
 use crate::core::RoleState;
+use crate::net::{ConnectionState, ConnectionStatus};
 
 #[derive(Resource, Clone)]
 pub struct HealthState {
     pub status: String,
 }
 
-#[derive(Resource)]
-pub struct HealthChannel {
-    pub rx: Receiver<HealthState>,
-}
-
-pub fn spawn_health_thread() -> Receiver<HealthState> {
-    let (tx, rx) = crossbeam_channel::unbounded::<HealthState>();
-    thread::spawn(move || loop {
-        let state = fetch_health();
-        let _ = tx.send(state);
-        thread::sleep(Duration::from_secs(2));
-    });
-    rx
-}
-
-pub fn update_health(mut health: ResMut<HealthState>, channel: Res<HealthChannel>) {
-    while let Ok(new_state) = channel.rx.try_recv() {
-        *health = new_state;
+pub fn update_health(mut health: ResMut<HealthState>, connection: Res<ConnectionState>) {
+    if !connection.is_changed() {
+        return;
     }
+    health.status = connection_label(&connection);
 }
 
 pub fn update_window_title(
@@ -42,31 +26,53 @@ pub fn update_window_title(
     let role_label = match role.current {
         crate::core::Role::Ghost => "Ghost",
         crate::core::Role::Investigator => "Investigator",
+        crate::core::Role::Spectator => "Spectator",
     };
     let mut window = windows.single_mut();
     window.title = format!("Me & My Friends - {} - {}", role_label, health.status);
 }
 
-fn fetch_health() -> HealthState {
-    match get("http://localhost:8000/health") {
-        Ok(resp) => {
-            if !resp.status().is_success() {
-                return HealthState {
-                    status: "error".into(),
-                };
-            }
-
-            match resp.json::<Health>() {
-                Ok(payload) => HealthState {
-                    status: payload.status.clone(),
-                },
-                Err(_) => HealthState {
-                    status: "error".into(),
-                },
-            }
+fn connection_label(connection: &ConnectionState) -> String {
+    match connection.status {
+        ConnectionStatus::Connecting => "connecting".into(),
+        ConnectionStatus::Disconnected => "offline".into(),
+        ConnectionStatus::Incompatible => match &connection.protocol_mismatch {
+            Some(mismatch) => format!(
+                "protocol mismatch: client {} / server {} - update required",
+                version_label(mismatch.client),
+                version_label(mismatch.server)
+            ),
+            None => "protocol mismatch - update required".into(),
+        },
+        ConnectionStatus::Connected if connection.server_shutting_down => {
+            "server shutting down".into()
         }
-        Err(_) => HealthState {
-            status: "error".into(),
+        ConnectionStatus::Connected => match &connection.room {
+            Some(room) => format!("room {}", room.code),
+            None => "online".into(),
         },
     }
 }
+
+pub fn update_notice(mismatch: &VersionMismatch) -> String {
+    match mismatch.update {
+        UpdateRequired::Client => format!(
+            "This game is out of date. The server needs protocol {} but this client speaks {}. Please update the game to play online.",
+            version_label(mismatch.server),
+            version_label(mismatch.client)
+        ),
+        UpdateRequired::Server => format!(
+            "The server is older than this game. It speaks protocol {} but this client needs {}. Online play returns once the server is updated.",
+            version_label(mismatch.server),
+            version_label(mismatch.client)
+        ),
+    }
+}
+
+fn version_label(range: VersionRange) -> String {
+    if range.min == range.max {
+        format!("v{}", range.max)
+    } else {
+        format!("v{}-v{}", range.min, range.max)
+    }
+}
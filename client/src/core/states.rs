@@ -1,23 +1,8 @@
 use bevy::prelude::*;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Role {
-    Ghost,
-    Investigator,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum Equipment {
-    Emf,
-    Spiritbox,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum GhostType {
-    Spirit,
-    Banshee,
-    Onryo,
-}
+use shared::chat::{ChatChannel, ChatMessage, ChatRejection};
+pub use shared::game::{Equipment, GhostType, Role, RoundOutcome};
+use shared::protocol::{PlayerProfile, QueueStatus};
 
 #[derive(Resource)]
 pub struct RoleState {
@@ -37,6 +22,7 @@ pub enum MenuScreen {
     GhostDetails,
     InvestigatorDetails,
     Resolution,
+    Profile,
 }
 
 #[derive(Resource)]
@@ -72,23 +58,60 @@ pub struct SessionState {
     pub started: bool,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum RoundOutcome {
-    SuccessfulExorcism,
-    WrongGhost,
-    FailedExorcism,
-}
-
 #[derive(Resource, Default)]
 pub struct ResolutionState {
     pub outcome: Option<RoundOutcome>,
     pub shown: bool,
 }
 
+#[derive(Resource, Default)]
+pub struct ProfileState {
+    pub profile: Option<PlayerProfile>,
+    pub requested: bool,
+}
+
+// `preference` of None lets matchmaking pick whichever role is missing.
+#[derive(Resource, Default)]
+pub struct QuickPlayState {
+    pub preference: Option<Role>,
+    pub requested: bool,
+    pub cancel_requested: bool,
+    pub searching: bool,
+    pub matched: bool,
+    pub status: Option<QueueStatus>,
+    pub countdown_ms: Option<u64>,
+}
+
+pub const CHAT_HISTORY: usize = 40;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChatEntry {
+    Message(ChatMessage),
+    Rejected(ChatRejection),
+}
+
+// `outbox` is drained by the network layer; the HUD only queues drafts.
+#[derive(Resource, Default)]
+pub struct ChatState {
+    pub open: bool,
+    pub draft: String,
+    pub log: Vec<ChatEntry>,
+    pub outbox: Vec<(ChatChannel, String)>,
+}
+
+impl ChatState {
+    pub fn push(&mut self, entry: ChatEntry) {
+        self.log.push(entry);
+        let overflow = self.log.len().saturating_sub(CHAT_HISTORY);
+        self.log.drain(..overflow);
+    }
+}
+
 pub const DEFAULT_GHOST_YAW: f32 = 0.0;
 pub const DEFAULT_GHOST_PITCH: f32 = 0.12;
 pub const DEFAULT_INVESTIGATOR_YAW: f32 = 0.0;
 pub const DEFAULT_INVESTIGATOR_PITCH: f32 = 0.2;
+pub const DEFAULT_SPECTATOR_PITCH: f32 = -0.35;
 
 pub fn set_default_camera(role: Role, control: &mut CameraControl, role_yaw: &mut RoleYaw) {
     match role {
@@ -102,5 +125,9 @@ pub fn set_default_camera(role: Role, control: &mut CameraControl, role_yaw: &mu
             control.pitch = DEFAULT_INVESTIGATOR_PITCH;
             role_yaw.investigator = DEFAULT_INVESTIGATOR_YAW;
         }
+        Role::Spectator => {
+            control.yaw = DEFAULT_INVESTIGATOR_YAW;
+            control.pitch = DEFAULT_SPECTATOR_PITCH;
+        }
     }
 }
@@ -5,25 +5,17 @@ use crate::gameplay::ghost::GhostState;
 use crate::gameplay::map::systems::default_house_layout;
 use crate::gameplay::map::systems::{room_id, room_id_in_house};
 use crate::gameplay::map::HouseLayout;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::net::prediction::PredictionState;
+use crate::net::systems::PendingClaims;
+use shared::exorcism::random_banshee_order;
+use shared::prelude::ClientMessage;
 
 pub mod rules;
 pub mod tables;
 
+pub use shared::game::ExorcismState;
 use tables::ExorcismTables;
 
-static BANSHEE_SEQUENCE_COUNTER: AtomicU64 = AtomicU64::new(0);
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum ExorcismState {
-    Inactive,
-    Stage(u8),
-    Progress(f32),
-    Failed,
-    Complete,
-}
-
 #[derive(Resource)]
 pub struct ExorcismStatus {
     pub state: ExorcismState,
@@ -42,7 +34,7 @@ pub struct InvestigationState {
 #[derive(Resource)]
 pub struct PuzzleSpawned(pub bool);
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub(crate) struct RoomLights {
     rooms: Vec<(u8, bool)>,
 }
@@ -72,6 +64,7 @@ pub struct SpiritMarker;
 #[derive(Component)]
 struct BansheeAnchor {
     color: BansheeNodeColor,
+    node: u8,
 }
 
 #[derive(Component)]
@@ -150,17 +143,27 @@ impl Default for BansheeSequence {
 
 impl BansheeSequence {
     pub fn for_anchor_count(count: usize) -> Self {
-        let anchor_colors = BansheeNodeColor::palette(count);
-        let mut order = anchor_colors.clone();
-        shuffle_colors(&mut order, banshee_random_seed(count as u64));
-        Self {
-            anchor_colors,
-            order,
-        }
+        Self::from_nodes(count, &random_banshee_order(count, count as u8))
+    }
+
+    pub fn from_nodes(count: usize, nodes: &[u8]) -> Self {
+        let mut sequence = Self {
+            anchor_colors: BansheeNodeColor::palette(count),
+            order: Vec::new(),
+        };
+        sequence.order = nodes
+            .iter()
+            .map(|node| sequence.color_for_index(usize::from(*node)))
+            .collect();
+        sequence
     }
 
-    fn reset_for_anchor_count(&mut self, count: usize) {
-        *self = Self::for_anchor_count(count);
+    fn reset_for_anchor_count(&mut self, count: usize, server_order: &[u8]) {
+        *self = if server_order.is_empty() {
+            Self::for_anchor_count(count)
+        } else {
+            Self::from_nodes(count, server_order)
+        };
     }
 
     pub fn sequence_len(&self) -> u8 {
@@ -193,6 +196,11 @@ impl BansheeSequence {
     }
 }
 
+/// Banshee node order the server sent with `RoundStarted`. Empty offline,
+/// in which case the client shuffles its own.
+#[derive(Resource, Default)]
+pub struct BansheeOrder(pub Vec<u8>);
+
 #[derive(Resource)]
 struct OnryoPuzzle {
     stage: u8,
@@ -211,6 +219,7 @@ impl Plugin for ExorcismPlugin {
             .insert_resource(RoomLights { rooms: Vec::new() })
             .insert_resource(SpiritPuzzle { progress: 0.0 })
             .insert_resource(BansheeSequence::default())
+            .init_resource::<BansheeOrder>()
             .insert_resource(BansheePuzzle {
                 stage: 0,
                 time_since_trigger: 0.0,
@@ -233,6 +242,7 @@ impl Plugin for ExorcismPlugin {
             .add_systems(
                 Update,
                 (
+                    apply_banshee_order,
                     maybe_reset_puzzle,
                     spawn_puzzle_entities,
                     ghost_toggle_lights,
@@ -296,6 +306,19 @@ fn onryo_ritual_positions(house_layout: Option<&HouseLayout>) -> Vec<Vec3> {
         .unwrap_or_else(|| default_house_layout().exorcism.onryo_ritual_positions)
 }
 
+fn apply_banshee_order(
+    order: Res<BansheeOrder>,
+    mut banshee_sequence: ResMut<BansheeSequence>,
+    house_layout: Option<Res<HouseLayout>>,
+) {
+    if order.is_changed() && !order.0.is_empty() {
+        banshee_sequence.reset_for_anchor_count(
+            banshee_anchor_positions(house_layout.as_deref()).len(),
+            &order.0,
+        );
+    }
+}
+
 fn maybe_reset_puzzle(
     spawned: Res<PuzzleSpawned>,
     tables: Res<ExorcismTables>,
@@ -303,6 +326,7 @@ fn maybe_reset_puzzle(
     mut spirit: ResMut<SpiritPuzzle>,
     mut banshee: ResMut<BansheePuzzle>,
     mut banshee_sequence: ResMut<BansheeSequence>,
+    banshee_order: Res<BansheeOrder>,
     mut onryo: ResMut<OnryoPuzzle>,
     mut lights: ResMut<RoomLights>,
     mut anchors: Query<Entity, With<PuzzleEntity>>,
@@ -336,8 +360,10 @@ fn maybe_reset_puzzle(
     banshee.stage = 0;
     banshee.time_since_trigger = 0.0;
     banshee.failed_timer = 0.0;
-    banshee_sequence
-        .reset_for_anchor_count(banshee_anchor_positions(house_layout.as_deref()).len());
+    banshee_sequence.reset_for_anchor_count(
+        banshee_anchor_positions(house_layout.as_deref()).len(),
+        &banshee_order.0,
+    );
     onryo.stage = 0;
     onryo.stacks = 0.0;
     onryo.carrying = None;
@@ -433,7 +459,10 @@ fn spawn_puzzle_entities(
                         transform: Transform::from_translation(pos),
                         ..default()
                     },
-                    BansheeAnchor { color },
+                    BansheeAnchor {
+                        color,
+                        node: index as u8,
+                    },
                     PuzzleEntity,
                 ));
                 commands.spawn((
@@ -517,6 +546,7 @@ fn update_spirit_puzzle(
     camera: Query<&Transform, With<Camera>>,
     mut spirit: ResMut<SpiritPuzzle>,
     mut status: ResMut<ExorcismStatus>,
+    prediction: Option<Res<PredictionState>>,
 ) {
     if menu.open || journal.open || role.current != Role::Investigator {
         return;
@@ -576,11 +606,7 @@ fn update_spirit_puzzle(
     status.stacks = 0.0;
     status.max_stacks = required_count as f32;
     if spirit.progress >= 1.0 {
-        if ghost_type.active == GhostType::Spirit {
-            status.state = ExorcismState::Complete;
-        } else {
-            status.state = ExorcismState::Failed;
-        }
+        status.state = finished(&ghost_type, prediction.as_deref(), GhostType::Spirit);
     } else {
         status.state = ExorcismState::Progress(spirit.progress);
     }
@@ -597,10 +623,12 @@ fn update_banshee_puzzle(
     banshee_sequence: Res<BansheeSequence>,
     journal: Res<JournalState>,
     house_layout: Option<Res<HouseLayout>>,
-    player: Query<&Transform, With<crate::gameplay::investigator::Player>>,
+    player: Query<&Transform, With<crate::gameplay::investigator::ActiveInvestigator>>,
     anchors: Query<(&BansheeAnchor, &Transform)>,
     mut banshee: ResMut<BansheePuzzle>,
     mut status: ResMut<ExorcismStatus>,
+    claims: Option<ResMut<PendingClaims>>,
+    prediction: Option<Res<PredictionState>>,
 ) {
     if menu.open || journal.open || role.current != Role::Investigator {
         return;
@@ -648,19 +676,22 @@ fn update_banshee_puzzle(
     let Ok(player_transform) = player.get_single() else {
         return;
     };
-    let mut closest: Option<(BansheeNodeColor, f32, Vec3)> = None;
+    let mut closest: Option<(BansheeNodeColor, f32, u8)> = None;
     for (anchor, transform) in anchors.iter() {
         let distance = player_transform.translation.distance(transform.translation);
         if distance <= tables.banshee.interact_distance {
             if closest.is_none() || distance < closest.unwrap().1 {
-                closest = Some((anchor.color, distance, transform.translation));
+                closest = Some((anchor.color, distance, anchor.node));
             }
         }
     }
 
-    let Some((color, _, _pos)) = closest else {
+    let Some((color, _, node)) = closest else {
         return;
     };
+    if let Some(mut claims) = claims {
+        claims.push(ClientMessage::PressBansheeNode { node });
+    }
 
     let expected = banshee.stage;
     let order_ok = banshee_sequence.expected_color(expected) == Some(color);
@@ -679,11 +710,7 @@ fn update_banshee_puzzle(
             status.state = ExorcismState::Failed;
         }
         ExorcismState::Complete => {
-            if ghost_type.active == GhostType::Banshee {
-                status.state = ExorcismState::Complete;
-            } else {
-                status.state = ExorcismState::Failed;
-            }
+            status.state = finished(&ghost_type, prediction.as_deref(), GhostType::Banshee);
             banshee.stage = sequence_len;
         }
         ExorcismState::Stage(next) => {
@@ -708,7 +735,7 @@ fn update_onryo_puzzle(
     player: Query<
         &Transform,
         (
-            With<crate::gameplay::investigator::Player>,
+            With<crate::gameplay::investigator::ActiveInvestigator>,
             Without<OnryoCursed>,
             Without<OnryoRitual>,
         ),
@@ -716,19 +743,21 @@ fn update_onryo_puzzle(
     mut cursed: Query<
         (Entity, &mut Transform, &mut OnryoCursed),
         (
-            Without<crate::gameplay::investigator::Player>,
+            Without<crate::gameplay::investigator::ActiveInvestigator>,
             Without<OnryoRitual>,
         ),
     >,
     rituals: Query<
         (&OnryoRitual, &Transform),
         (
-            Without<crate::gameplay::investigator::Player>,
+            Without<crate::gameplay::investigator::ActiveInvestigator>,
             Without<OnryoCursed>,
         ),
     >,
     mut onryo: ResMut<OnryoPuzzle>,
     mut status: ResMut<ExorcismStatus>,
+    mut claims: Option<ResMut<PendingClaims>>,
+    prediction: Option<Res<PredictionState>>,
 ) {
     if menu.open || journal.open || role.current != Role::Investigator {
         return;
@@ -786,6 +815,9 @@ fn update_onryo_puzzle(
             if distance <= tables.onryo.interact_distance {
                 onryo.carrying = Some(entity);
                 onryo.stacks += tables.onryo.stack_penalty_wrong;
+                if let Some(claims) = claims.as_mut() {
+                    claims.push(ClientMessage::Interact);
+                }
                 break;
             }
         }
@@ -808,6 +840,9 @@ fn update_onryo_puzzle(
     let Some(carried_entity) = onryo.carrying.take() else {
         return;
     };
+    if let Some(claims) = claims.as_mut() {
+        claims.push(ClientMessage::Interact);
+    }
 
     if spot_index == onryo.stage {
         if let Ok((_, mut transform, mut cursed_obj)) = cursed.get_mut(carried_entity) {
@@ -821,37 +856,26 @@ fn update_onryo_puzzle(
             .map(|layout| layout.exorcism.onryo_ritual_positions.len() as u8)
             .unwrap_or(tables.onryo.default_ritual_count);
         if onryo.stage >= sequence_len {
-            if ghost_type.active == GhostType::Onryo {
-                status.state = ExorcismState::Complete;
-            } else {
-                status.state = ExorcismState::Failed;
-            }
+            status.state = finished(&ghost_type, prediction.as_deref(), GhostType::Onryo);
         }
     } else {
         onryo.stacks += tables.onryo.stack_penalty_wrong;
     }
 }
 
-fn banshee_random_seed(salt: u64) -> u64 {
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_nanos() as u64)
-        .unwrap_or(0);
-    let counter = BANSHEE_SEQUENCE_COUNTER.fetch_add(1, Ordering::Relaxed);
-    let mut seed = nanos ^ counter.rotate_left(13) ^ salt.wrapping_mul(0x9E37_79B9_7F4A_7C15);
-    if seed == 0 {
-        seed = 0x94D0_49BB_1331_11EB;
-    }
-    seed
-}
-
-fn shuffle_colors(colors: &mut [BansheeNodeColor], mut seed: u64) {
-    for index in (1..colors.len()).rev() {
-        seed = seed
-            .wrapping_mul(6_364_136_223_846_793_005)
-            .wrapping_add(1_442_695_040_888_963_407);
-        let swap_index = (seed % (index as u64 + 1)) as usize;
-        colors.swap(index, swap_index);
+// Offline a finished puzzle fails when the guess was wrong. Online the
+// client cannot know: it claims the exorcism and the server's verdict ends
+// the round.
+fn finished(
+    ghost_type: &GhostTypeState,
+    prediction: Option<&PredictionState>,
+    puzzle: GhostType,
+) -> ExorcismState {
+    let online = prediction.is_some_and(PredictionState::is_active);
+    if online || ghost_type.active == puzzle {
+        ExorcismState::Complete
+    } else {
+        ExorcismState::Failed
     }
 }
 
@@ -890,7 +914,7 @@ mod tests {
                 max_z: 10.0,
             },
             obstacles: Vec::new(),
-            rooms: vec![crate::gameplay::map::components::RoomZone {
+            rooms: vec![shared::map::RoomZone {
                 id: 0,
                 name: "Only Room",
                 bounds: crate::gameplay::map::components::Bounds {
@@ -901,7 +925,7 @@ mod tests {
                 },
             }],
             walls: Vec::new(),
-            exorcism: crate::gameplay::map::components::ExorcismLayout {
+            exorcism: shared::map::ExorcismLayout {
                 spirit_anchors: vec![Vec3::new(0.0, 0.7, 0.0)],
                 banshee_anchors: vec![
                     Vec3::new(-4.0, 0.5, 0.0),
@@ -920,6 +944,7 @@ mod tests {
             Transform::from_xyz(-4.0, 0.9, 0.0),
             GlobalTransform::default(),
             crate::gameplay::investigator::Player,
+            crate::gameplay::investigator::ActiveInvestigator,
         ));
 
         {
@@ -1,53 +1 @@
-use super::ExorcismState;
-
-pub fn spirit_progress(
-    mut progress: f32,
-    target_progress: f32,
-    dt: f32,
-    rate_up: f32,
-    rate_down: f32,
-) -> f32 {
-    let target_progress = target_progress.clamp(0.0, 1.0);
-    if progress < target_progress {
-        progress = (progress + rate_up * dt).min(target_progress);
-    } else if progress > target_progress {
-        progress = (progress - rate_down * dt).max(target_progress);
-    }
-    progress.clamp(0.0, 1.0)
-}
-
-pub fn banshee_advance(
-    stage: u8,
-    sequence_len: u8,
-    timing_ok: bool,
-    order_ok: bool,
-) -> ExorcismState {
-    if !order_ok || !timing_ok {
-        return ExorcismState::Failed;
-    }
-
-    let next = stage + 1;
-    if next >= sequence_len {
-        ExorcismState::Complete
-    } else {
-        ExorcismState::Stage(next)
-    }
-}
-
-pub fn onryo_stack_tick(
-    stacks: f32,
-    dt: f32,
-    carrying: bool,
-    max_stacks: f32,
-    stack_rate: f32,
-) -> (f32, bool) {
-    if !carrying {
-        return (stacks, false);
-    }
-    let new_stacks = stacks + dt * stack_rate;
-    (new_stacks, new_stacks >= max_stacks)
-}
-
-#[cfg(test)]
-#[path = "rules_tests.rs"]
-mod rules_tests;
+pub use shared::exorcism::{banshee_advance, onryo_stack_tick, spirit_progress};
@@ -1,3 +1,5 @@
+use shared::hunt::{HuntPhase, HuntTuning};
+
 use crate::prelude::*;
 
 #[derive(Resource)]
@@ -7,3 +9,27 @@ pub struct GhostState {
 
 #[derive(Component)]
 pub struct GhostMarker;
+
+// Offline the client runs the hunt clock itself; online the server's `Hunt`
+// messages overwrite it and the local clock only smooths the countdown.
+#[derive(Resource)]
+pub struct GhostHunt {
+    pub phase: HuntPhase,
+    pub tuning: HuntTuning,
+}
+
+impl GhostHunt {
+    pub fn restart(&mut self) {
+        self.phase = HuntPhase::new(&self.tuning);
+    }
+}
+
+impl Default for GhostHunt {
+    fn default() -> Self {
+        let tuning = HuntTuning::default();
+        Self {
+            phase: HuntPhase::new(&tuning),
+            tuning,
+        }
+    }
+}
@@ -3,15 +3,21 @@ use bevy::prelude::*;
 pub mod components;
 pub mod systems;
 
-pub use components::{GhostMarker, GhostState};
+pub use components::{GhostHunt, GhostMarker, GhostState};
 
 pub struct GhostPlugin;
 
 impl Plugin for GhostPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.init_resource::<GhostHunt>().add_systems(
             Update,
-            (systems::ghost_movement_system, systems::sync_ghost_marker),
+            (
+                systems::ghost_movement_system,
+                systems::ghost_hunt_system,
+                systems::advance_hunt,
+                systems::sync_ghost_marker,
+            )
+                .chain(),
         );
     }
 }
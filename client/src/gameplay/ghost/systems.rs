@@ -1,9 +1,13 @@
 use crate::prelude::*;
 
+use shared::prelude::ClientMessage;
+
 use crate::core::{MenuState, RoleState};
-use crate::gameplay::ghost::{GhostMarker, GhostState};
+use crate::gameplay::ghost::{GhostHunt, GhostMarker, GhostState};
+use crate::gameplay::keyboard_move_input;
 use crate::gameplay::map::components::CollisionWorld;
-use crate::gameplay::map::systems::move_with_collisions;
+use crate::net::prediction::{predict_movement, PredictionState};
+use crate::net::systems::PendingClaims;
 
 pub fn ghost_movement_system(
     time: Res<Time>,
@@ -13,45 +17,21 @@ pub fn ghost_movement_system(
     mut ghost: ResMut<GhostState>,
     control: Res<CameraControl>,
     world: Res<CollisionWorld>,
+    mut prediction: Option<ResMut<PredictionState>>,
     mut camera_query: Query<&mut Transform, (With<Camera>, Without<GhostMarker>)>,
 ) {
     if menu.open || role.current != Role::Ghost {
         return;
     }
 
-    let delta = time.delta_seconds();
-    let speed = 5.2;
-    let sprint = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
-    let speed = if sprint { speed * 1.6 } else { speed };
-
-    let forward = Vec3::new(control.yaw.sin(), 0.0, control.yaw.cos());
-    let right = Vec3::new(-forward.z, 0.0, forward.x);
-
-    let mut movement = Vec3::ZERO;
-    if keys.pressed(KeyCode::KeyW) || keys.pressed(KeyCode::ArrowUp) {
-        movement += forward;
-    }
-    if keys.pressed(KeyCode::KeyS) || keys.pressed(KeyCode::ArrowDown) {
-        movement -= forward;
-    }
-    if keys.pressed(KeyCode::KeyA) || keys.pressed(KeyCode::ArrowLeft) {
-        movement -= right;
-    }
-    if keys.pressed(KeyCode::KeyD) || keys.pressed(KeyCode::ArrowRight) {
-        movement += right;
-    }
-
-    if movement.length_squared() > 0.0 {
-        movement = movement.normalize() * speed * delta;
-    }
-
-    move_with_collisions(
+    let input = keyboard_move_input(&keys, &control);
+    predict_movement(
         &mut ghost.position,
-        movement,
-        0.35,
-        world.bounds,
-        &world.obstacles,
-        true,
+        input,
+        Role::Ghost,
+        time.delta_seconds(),
+        &world,
+        prediction.as_deref_mut(),
     );
 
     let forward3d = Vec3::new(
@@ -66,14 +46,58 @@ pub fn ghost_movement_system(
     }
 }
 
+// Online the server decides whether the hunt starts; offline the local
+// clock does.
+pub fn ghost_hunt_system(
+    keys: Res<ButtonInput<KeyCode>>,
+    role: Res<RoleState>,
+    menu: Res<MenuState>,
+    mut hunt: ResMut<GhostHunt>,
+    prediction: Option<Res<PredictionState>>,
+    claims: Option<ResMut<PendingClaims>>,
+) {
+    if menu.open || role.current != Role::Ghost || !keys.just_pressed(KeyCode::KeyH) {
+        return;
+    }
+    if !hunt.phase.is_ready() {
+        return;
+    }
+    match claims {
+        Some(mut claims) if prediction.is_some_and(|prediction| prediction.is_active()) => {
+            claims.push(ClientMessage::StartHunt);
+        }
+        _ => {
+            let hunt = &mut *hunt;
+            hunt.phase.trigger(&hunt.tuning);
+        }
+    }
+}
+
+pub fn advance_hunt(time: Res<Time>, menu: Res<MenuState>, mut hunt: ResMut<GhostHunt>) {
+    if menu.open {
+        return;
+    }
+    let hunt = &mut *hunt;
+    hunt.phase.advance(time.delta_seconds(), &hunt.tuning);
+}
+
+// Investigators only see the ghost while it hunts.
 pub fn sync_ghost_marker(
     ghost: Res<GhostState>,
-    mut markers: Query<&mut Transform, With<GhostMarker>>,
+    role: Res<RoleState>,
+    hunt: Option<Res<GhostHunt>>,
+    mut markers: Query<(&mut Transform, &mut Visibility), With<GhostMarker>>,
 ) {
-    let Ok(mut marker) = markers.get_single_mut() else {
+    let Ok((mut marker, mut visibility)) = markers.get_single_mut() else {
         return;
     };
     marker.translation = Vec3::new(ghost.position.x, 1.2, ghost.position.z);
+    let hunting = hunt.is_some_and(|hunt| hunt.phase.is_hunting());
+    *visibility = if role.current != Role::Investigator || hunting {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
 }
 
 #[cfg(test)]
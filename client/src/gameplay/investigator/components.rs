@@ -1,4 +1,13 @@
 use crate::prelude::*;
 
+// Every investigator body on this client. Online there is one; an offline
+// investigation can field up to four.
 #[derive(Component)]
 pub struct Player;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TeamSlot(pub usize);
+
+// The body that takes input and owns the camera.
+#[derive(Component)]
+pub struct ActiveInvestigator;
@@ -4,16 +4,19 @@ pub mod components;
 pub mod systems;
 pub mod tools;
 
-pub use components::Player;
+pub use components::{ActiveInvestigator, Player, TeamSlot};
 
 pub struct InvestigatorPlugin;
 
 impl Plugin for InvestigatorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<systems::InvestigatorCameraState>()
+            .init_resource::<systems::InvestigatorTeam>()
             .add_systems(
                 Update,
                 (
+                    systems::arrange_investigator_team,
+                    systems::cycle_active_investigator,
                     systems::investigator_movement_system,
                     tools::handle_equipment_input,
                     tools::update_emf_reading,
@@ -1,11 +1,16 @@
 use crate::prelude::*;
 
+use shared::prelude::MAX_INVESTIGATORS;
+
 use crate::core::{JournalState, MenuState, RoleState};
-use crate::gameplay::investigator::Player;
+use crate::gameplay::investigator::tools::EquipmentState;
+use crate::gameplay::investigator::{ActiveInvestigator, Player, TeamSlot};
+use crate::gameplay::keyboard_move_input;
 use crate::gameplay::map::components::CollisionWorld;
 use crate::gameplay::map::systems::{
-    avoid_camera_obstacles, clamp_camera_distance, move_with_collisions, shortest_angle,
+    avoid_camera_obstacles, clamp_camera_distance, shortest_angle,
 };
+use crate::net::prediction::{predict_movement, PredictionState};
 
 const INVESTIGATOR_CAMERA_RADIUS: f32 = 4.8;
 const INVESTIGATOR_CAMERA_MIN_DISTANCE: f32 = 1.2;
@@ -24,6 +29,146 @@ impl Default for InvestigatorCameraState {
     }
 }
 
+#[derive(Resource)]
+pub struct InvestigatorTeam {
+    pub size: usize,
+    starts: Option<Vec<Vec3>>,
+}
+
+impl Default for InvestigatorTeam {
+    fn default() -> Self {
+        Self {
+            size: 1,
+            starts: None,
+        }
+    }
+}
+
+impl InvestigatorTeam {
+    pub fn cycle_size(&mut self) {
+        self.size = self.size % MAX_INVESTIGATORS + 1;
+    }
+
+    // One body per start; the next `arrange_investigator_team` run spawns or
+    // removes bodies to match and hands control to slot 0.
+    pub fn arrange(&mut self, starts: Vec<Vec3>) {
+        self.starts = Some(starts);
+    }
+}
+
+pub(crate) fn spawn_investigator(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    slot: usize,
+    position: Vec3,
+) -> Entity {
+    let mut body = commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(0.7, 1.8, 0.7)),
+            material: materials.add(StandardMaterial {
+                base_color: Color::srgb(0.6, 0.65, 0.75),
+                metallic: 0.0,
+                perceptual_roughness: 0.95,
+                reflectance: 0.02,
+                ..default()
+            }),
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        Player,
+        TeamSlot(slot),
+    ));
+    if slot == 0 {
+        body.insert(ActiveInvestigator);
+    } else {
+        body.insert(EquipmentState::default());
+    }
+    body.id()
+}
+
+pub fn arrange_investigator_team(
+    mut commands: Commands,
+    mut team: ResMut<InvestigatorTeam>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bodies: Query<(Entity, &mut Transform, Option<&TeamSlot>), With<Player>>,
+) {
+    let Some(starts) = team.starts.take() else {
+        return;
+    };
+    let mut placed = vec![false; starts.len()];
+    for (entity, mut transform, slot) in &mut bodies {
+        let slot = slot.map_or(0, |slot| slot.0);
+        let Some(start) = starts.get(slot).filter(|_| !placed[slot]) else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        placed[slot] = true;
+        transform.translation = *start;
+        transform.rotation = Quat::IDENTITY;
+        let mut body = commands.entity(entity);
+        if slot == 0 {
+            body.insert(ActiveInvestigator).remove::<EquipmentState>();
+        } else {
+            body.remove::<ActiveInvestigator>()
+                .insert(EquipmentState::default());
+        }
+    }
+    for (slot, start) in starts.iter().enumerate() {
+        if !placed[slot] {
+            spawn_investigator(&mut commands, &mut meshes, &mut materials, slot, *start);
+        }
+    }
+}
+
+// Tab passes control, the camera and the kit in hand to the next
+// investigator; the one left behind keeps its own kit.
+pub fn cycle_active_investigator(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    role: Res<RoleState>,
+    menu: Res<MenuState>,
+    journal: Res<JournalState>,
+    mut equipment: ResMut<EquipmentState>,
+    bodies: Query<
+        (
+            Entity,
+            &TeamSlot,
+            Has<ActiveInvestigator>,
+            Option<&EquipmentState>,
+        ),
+        With<Player>,
+    >,
+) {
+    if menu.open
+        || journal.open
+        || role.current != Role::Investigator
+        || !keys.just_pressed(KeyCode::Tab)
+    {
+        return;
+    }
+    let mut team: Vec<_> = bodies.iter().collect();
+    if team.len() < 2 {
+        return;
+    }
+    team.sort_by_key(|(_, slot, _, _)| slot.0);
+    let Some(current) = team.iter().position(|(_, _, active, _)| *active) else {
+        return;
+    };
+    let (from, ..) = team[current];
+    let (to, _, _, kit) = team[(current + 1) % team.len()];
+    let kit = kit.cloned().unwrap_or_default();
+    commands
+        .entity(from)
+        .remove::<ActiveInvestigator>()
+        .insert(std::mem::replace(&mut *equipment, kit));
+    commands
+        .entity(to)
+        .remove::<EquipmentState>()
+        .insert(ActiveInvestigator);
+}
+
 pub fn investigator_movement_system(
     time: Res<Time>,
     keys: Res<ButtonInput<KeyCode>>,
@@ -33,47 +178,25 @@ pub fn investigator_movement_system(
     control: Res<CameraControl>,
     world: Res<CollisionWorld>,
     camera_state: Option<ResMut<InvestigatorCameraState>>,
-    mut player_query: Query<&mut Transform, With<Player>>,
-    mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    mut prediction: Option<ResMut<PredictionState>>,
+    mut player_query: Query<&mut Transform, With<ActiveInvestigator>>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<ActiveInvestigator>)>,
 ) {
     if menu.open || journal.open || role.current != Role::Investigator {
         return;
     }
 
     let delta = time.delta_seconds();
-    let speed = 3.6;
-    let sprint = keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight);
-    let speed = if sprint { speed * 1.6 } else { speed };
-
-    let forward = Vec3::new(control.yaw.sin(), 0.0, control.yaw.cos());
-    let right = Vec3::new(-forward.z, 0.0, forward.x);
-
-    let mut movement = Vec3::ZERO;
-    if keys.pressed(KeyCode::KeyW) || keys.pressed(KeyCode::ArrowUp) {
-        movement += forward;
-    }
-    if keys.pressed(KeyCode::KeyS) || keys.pressed(KeyCode::ArrowDown) {
-        movement -= forward;
-    }
-    if keys.pressed(KeyCode::KeyA) || keys.pressed(KeyCode::ArrowLeft) {
-        movement -= right;
-    }
-    if keys.pressed(KeyCode::KeyD) || keys.pressed(KeyCode::ArrowRight) {
-        movement += right;
-    }
-
-    if movement.length_squared() > 0.0 {
-        movement = movement.normalize() * speed * delta;
-    }
+    let input = keyboard_move_input(&keys, &control);
 
     if let Ok(mut player) = player_query.get_single_mut() {
-        move_with_collisions(
+        predict_movement(
             &mut player.translation,
-            movement,
-            0.35,
-            world.bounds,
-            &world.obstacles,
-            true,
+            input,
+            Role::Investigator,
+            delta,
+            &world,
+            prediction.as_deref_mut(),
         );
 
         let target_yaw = control.yaw + std::f32::consts::PI;
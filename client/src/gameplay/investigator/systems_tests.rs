@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::core::{CameraControl, JournalState, MenuState, Role, RoleState};
 use crate::gameplay::investigator::systems::investigator_movement_system;
-use crate::gameplay::investigator::Player;
+use crate::gameplay::investigator::{ActiveInvestigator, Player};
 use crate::gameplay::map::components::{Bounds, CollisionWorld};
 
 #[test]
@@ -35,7 +35,12 @@ fn investigator_faces_forward_with_pi_offset() {
 
     let player = app
         .world_mut()
-        .spawn((Transform::default(), GlobalTransform::default(), Player))
+        .spawn((
+            Transform::default(),
+            GlobalTransform::default(),
+            Player,
+            ActiveInvestigator,
+        ))
         .id();
 
     app.world_mut().spawn(Camera3dBundle::default());
@@ -6,21 +6,44 @@ use crate::gameplay::evidence::{
     spiritbox_reply, EvidenceTuning,
 };
 use crate::gameplay::ghost::GhostState;
-use crate::gameplay::investigator::Player;
+use crate::gameplay::investigator::ActiveInvestigator;
 use crate::gameplay::map::systems::{room_id, room_id_in_house};
 use crate::gameplay::map::HouseLayout;
+use crate::net::prediction::PredictionState;
+use crate::net::systems::PendingClaims;
+use shared::prelude::ClientMessage;
 
-#[derive(Resource)]
+// The resource is the kit in the active investigator's hands; teammates
+// keep theirs as a component until control passes to them.
+#[derive(Resource, Component, Clone)]
 pub struct EquipmentState {
     pub active: Equipment,
     pub emf_level: u8,
     pub emf_dwell: f32,
     pub emf_smoothed: f32,
     pub emf_evidence_latch: f32,
+    // Online the meter follows the server's reading: only the server knows
+    // the ghost type.
+    pub emf_reading: Option<u8>,
     pub spiritbox_message: String,
     pub spiritbox_cooldown: f32,
 }
 
+impl Default for EquipmentState {
+    fn default() -> Self {
+        Self {
+            active: Equipment::Emf,
+            emf_level: 0,
+            emf_dwell: 0.0,
+            emf_smoothed: 0.0,
+            emf_evidence_latch: 0.0,
+            emf_reading: None,
+            spiritbox_message: "Silence...".to_string(),
+            spiritbox_cooldown: 0.0,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct EvidenceState {
     pub emf_five: bool,
@@ -43,6 +66,9 @@ pub fn handle_equipment_input(
     if keys.just_pressed(KeyCode::Digit2) || keys.just_pressed(KeyCode::Numpad2) {
         equipment.active = Equipment::Spiritbox;
     }
+    if keys.just_pressed(KeyCode::Digit3) || keys.just_pressed(KeyCode::Numpad3) {
+        equipment.active = Equipment::Radio;
+    }
 }
 
 pub fn update_emf_reading(
@@ -57,8 +83,9 @@ pub fn update_emf_reading(
     ghost_type: Res<GhostTypeState>,
     mut evidence: ResMut<EvidenceState>,
     house_layout: Option<Res<HouseLayout>>,
-    player: Query<&Transform, With<Player>>,
+    player: Query<&Transform, With<ActiveInvestigator>>,
     camera: Query<&Transform, With<Camera>>,
+    prediction: Option<Res<PredictionState>>,
 ) {
     if menu.open || journal.open || role.current != Role::Investigator {
         equipment.emf_level = 0;
@@ -74,28 +101,34 @@ pub fn update_emf_reading(
         return;
     };
 
-    let to_ghost = ghost.position - player_transform.translation;
-    let distance = to_ghost.length();
-    let facing = facing_ghost(
-        player_transform.translation,
-        ghost.position,
-        view_forward(&control, camera.get_single().ok()),
-        tuning.emf_facing_dot,
-    );
+    let online = prediction.is_some_and(|prediction| prediction.is_active());
+    let (base_level, candidate_five, facing) = if online {
+        let level = equipment.emf_reading.unwrap_or(0);
+        (level.min(4), level == 5, true)
+    } else {
+        let distance = (ghost.position - player_transform.translation).length();
+        let facing = facing_ghost(
+            player_transform.translation,
+            ghost.position,
+            view_forward(&control, camera.get_single().ok()),
+            tuning.emf_facing_dot,
+        );
 
-    let player_room = house_layout
-        .as_ref()
-        .and_then(|layout| room_id_in_house(layout, player_transform.translation))
-        .unwrap_or_else(|| room_id(player_transform.translation));
-    let ghost_room = house_layout
-        .as_ref()
-        .and_then(|layout| room_id_in_house(layout, ghost.position))
-        .unwrap_or_else(|| room_id(ghost.position));
-    let same_room = player_room == ghost_room;
-    let base_level = emf_level(ghost_type.active, distance, same_room, &tuning);
-    let overlaps = distance <= overlap_distance(&tuning);
-    let candidate_five =
-        overlaps && emf_five_candidate(ghost_type.active, distance, &tuning) && facing;
+        let player_room = house_layout
+            .as_ref()
+            .and_then(|layout| room_id_in_house(layout, player_transform.translation))
+            .unwrap_or_else(|| room_id(player_transform.translation));
+        let ghost_room = house_layout
+            .as_ref()
+            .and_then(|layout| room_id_in_house(layout, ghost.position))
+            .unwrap_or_else(|| room_id(ghost.position));
+        let same_room = player_room == ghost_room;
+        let base_level = emf_level(ghost_type.active, distance, same_room, &tuning);
+        let overlaps = distance <= overlap_distance(&tuning);
+        let candidate_five =
+            overlaps && emf_five_candidate(ghost_type.active, distance, &tuning) && facing;
+        (base_level, candidate_five, facing)
+    };
 
     let dt = time.delta_seconds();
     let dwell_lock = tuning.emf_dwell_lock;
@@ -155,8 +188,9 @@ pub fn handle_spiritbox(
     ghost_type: Res<GhostTypeState>,
     mut evidence: ResMut<EvidenceState>,
     house_layout: Option<Res<HouseLayout>>,
-    player: Query<&Transform, With<Player>>,
+    player: Query<&Transform, With<ActiveInvestigator>>,
     camera: Query<&Transform, With<Camera>>,
+    online: (Option<Res<PredictionState>>, Option<ResMut<PendingClaims>>),
 ) {
     if equipment.spiritbox_cooldown > 0.0 {
         equipment.spiritbox_cooldown =
@@ -179,6 +213,16 @@ pub fn handle_spiritbox(
         return;
     }
 
+    // Online the server answers; `sync_tool_readings` shows the reply.
+    if let (Some(prediction), Some(mut claims)) = online {
+        if prediction.is_active() {
+            claims.push(ClientMessage::UseEquipment);
+            equipment.spiritbox_message = "...".into();
+            equipment.spiritbox_cooldown = tuning.spiritbox_cooldown_miss;
+            return;
+        }
+    }
+
     let Ok(player_transform) = player.get_single() else {
         equipment.spiritbox_message = "Only static...".into();
         equipment.spiritbox_cooldown = tuning.spiritbox_cooldown_miss;
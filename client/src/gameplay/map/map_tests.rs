@@ -162,6 +162,33 @@ fn random_round_starts_keep_investigator_and_ghost_apart() {
     assert!(separation_sq >= 1.0);
 }
 
+#[test]
+fn team_starts_never_overlap_for_up_to_four_investigators() {
+    for layout in [HouseLayout::two_room(), HouseLayout::three_room()] {
+        for size in 1..=4 {
+            let (team, ghost) = layout.random_team_start_positions(size);
+            assert_eq!(team.len(), size);
+            for (index, start) in team.iter().enumerate() {
+                assert!(layout.bounds.contains_xz(*start));
+                assert!((*start - ghost).length_squared() >= 1.0);
+                for other in &team[index + 1..] {
+                    let delta = *start - *other;
+                    assert!(delta.x * delta.x + delta.z * delta.z >= 1.0);
+                }
+            }
+        }
+    }
+
+    let mut cramped = HouseLayout::two_room();
+    cramped.investigator_spawns.truncate(1);
+    let (team, _) = cramped.random_team_start_positions(4);
+    for (index, start) in team.iter().enumerate() {
+        for other in &team[index + 1..] {
+            assert!(start.distance(*other) >= 1.0);
+        }
+    }
+}
+
 #[test]
 fn house_layout_selection_defaults_to_two_room() {
     let selection = HouseLayoutSelection::default();
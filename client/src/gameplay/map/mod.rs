@@ -22,6 +22,7 @@ impl Plugin for MapPlugin {
                 (
                     systems::sync_layout_walls,
                     systems::sync_room_light_visuals,
+                    systems::flicker_lights_during_hunt,
                     systems::animate_room_light_flicker,
                 )
                     .chain(),
@@ -1,10 +1,12 @@
 use crate::prelude::*;
 
 use crate::gameplay::exorcism::RoomLights;
-use crate::gameplay::ghost::GhostMarker;
-use crate::gameplay::investigator::Player;
+use crate::gameplay::ghost::{GhostHunt, GhostMarker};
+use crate::gameplay::investigator::systems::spawn_investigator;
 use std::path::Path;
 
+pub use shared::map::{collides, room_id_in_house};
+
 use super::components::{Bounds, HouseLayout, Obstacle};
 
 #[derive(Component)]
@@ -290,14 +292,6 @@ fn spawn_curated_set_dressing(
     spawned_any
 }
 
-pub fn room_id_in_house(layout: &HouseLayout, position: Vec3) -> Option<u8> {
-    layout
-        .rooms
-        .iter()
-        .find(|room| room.bounds.contains_xz(position))
-        .map(|room| room.id)
-}
-
 #[allow(dead_code)]
 pub fn investigator_spawn_position() -> Vec3 {
     default_house_layout().investigator_spawn
@@ -323,10 +317,15 @@ pub fn random_ghost_spawn_position() -> Vec3 {
     default_house_layout().random_ghost_spawn()
 }
 
+#[cfg(test)]
 pub fn random_round_start_positions() -> (Vec3, Vec3) {
     default_house_layout().random_start_positions()
 }
 
+pub fn random_team_start_positions(investigators: usize) -> (Vec<Vec3>, Vec3) {
+    default_house_layout().random_team_start_positions(investigators)
+}
+
 pub(crate) fn setup_scene(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -361,23 +360,13 @@ pub(crate) fn setup_scene(
     }
     spawn_room_lights(&mut commands, &house, room_lights.as_deref());
 
-    let player_mesh = meshes.add(Cuboid::new(0.7, 1.8, 0.7));
-    let player_material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.6, 0.65, 0.75),
-        metallic: 0.0,
-        perceptual_roughness: 0.95,
-        reflectance: 0.02,
-        ..default()
-    });
-    commands.spawn((
-        PbrBundle {
-            mesh: player_mesh,
-            material: player_material,
-            transform: Transform::from_translation(house.initial_investigator_spawn()),
-            ..default()
-        },
-        Player,
-    ));
+    spawn_investigator(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        0,
+        house.initial_investigator_spawn(),
+    );
 
     let ghost_mesh = meshes.add(Sphere::new(0.18).mesh().uv(16, 12));
     let ghost_material = materials.add(StandardMaterial {
@@ -687,6 +676,35 @@ pub(crate) fn sync_room_light_visuals(
     }
 }
 
+// While the ghost hunts every room light keeps flickering around its
+// current level; `animate_room_light_flicker` settles it once the hunt ends.
+pub(crate) fn flicker_lights_during_hunt(
+    hunt: Option<Res<GhostHunt>>,
+    lights: Option<Res<RoomLights>>,
+    mut room_lights: Query<&mut RoomLightVisual>,
+) {
+    let (Some(hunt), Some(lights)) = (hunt, lights) else {
+        return;
+    };
+    if !hunt.phase.is_hunting() {
+        return;
+    }
+    for mut visual in room_lights.iter_mut() {
+        if visual.flicker_active {
+            continue;
+        }
+        let target = if lights.is_enabled(visual.room_id) {
+            visual.on_intensity
+        } else {
+            visual.off_intensity
+        };
+        visual.flicker_active = true;
+        visual.flicker_elapsed = 0.0;
+        visual.flicker_from = target;
+        visual.flicker_to = target;
+    }
+}
+
 fn light_flicker_multiplier(elapsed: f32, phase: f32, turning_on: bool) -> f32 {
     let burst = (elapsed * (43.0 + phase * 0.25)).sin() * 0.5 + 0.5;
     let chatter = (elapsed * (76.0 + phase * 0.45)).cos() * 0.5 + 0.5;
@@ -750,56 +768,6 @@ pub(crate) fn animate_room_light_flicker(
     }
 }
 
-pub fn clamp_to_bounds(pos: &mut Vec3, bounds: Bounds, radius: f32) {
-    pos.x = pos.x.clamp(bounds.min_x + radius, bounds.max_x - radius);
-    pos.z = pos.z.clamp(bounds.min_z + radius, bounds.max_z - radius);
-}
-
-pub fn collides(pos: Vec3, radius: f32, obstacles: &[Obstacle]) -> bool {
-    obstacles.iter().any(|obs| {
-        let hit_x = pos.x + radius > obs.min_x && pos.x - radius < obs.max_x;
-        let hit_z = pos.z + radius > obs.min_z && pos.z - radius < obs.max_z;
-        hit_x && hit_z
-    })
-}
-
-pub fn move_with_collisions(
-    pos: &mut Vec3,
-    movement: Vec3,
-    radius: f32,
-    bounds: Bounds,
-    obstacles: &[Obstacle],
-    block_interior: bool,
-) {
-    let mut next = *pos + movement;
-    clamp_to_bounds(&mut next, bounds, radius);
-
-    if !block_interior {
-        *pos = next;
-        return;
-    }
-
-    if !collides(next, radius, obstacles) {
-        *pos = next;
-        return;
-    }
-
-    let mut try_x = *pos;
-    try_x.x = next.x;
-    clamp_to_bounds(&mut try_x, bounds, radius);
-    if !collides(try_x, radius, obstacles) {
-        *pos = try_x;
-        return;
-    }
-
-    let mut try_z = *pos;
-    try_z.z = next.z;
-    clamp_to_bounds(&mut try_z, bounds, radius);
-    if !collides(try_z, radius, obstacles) {
-        *pos = try_z;
-    }
-}
-
 pub fn clamp_camera_distance(base: Vec3, dir: Vec3, desired: f32, bounds: Bounds) -> f32 {
     let margin = 0.6;
     let mut max_t = desired;
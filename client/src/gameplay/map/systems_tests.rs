@@ -1,4 +1,5 @@
 use super::*;
+use shared::hunt::HuntPhase;
 
 #[test]
 fn choose_shell_scene_prefers_three_room_variant_when_available() {
@@ -175,3 +176,65 @@ fn rendered_walls_overlap_floor_and_roof_to_avoid_visible_seams() {
         }
     }
 }
+
+#[test]
+fn hunts_keep_room_lights_flickering_until_they_end() {
+    let mut app = App::new();
+    app.init_resource::<RoomLights>()
+        .init_resource::<GhostHunt>()
+        .insert_resource(Time::<()>::default())
+        .add_systems(
+            Update,
+            (flicker_lights_during_hunt, animate_room_light_flicker).chain(),
+        );
+    let light = app
+        .world_mut()
+        .spawn((
+            RoomLightVisual {
+                room_id: 0,
+                on_intensity: 800.0,
+                off_intensity: 0.0,
+                last_enabled: true,
+                flicker_active: false,
+                flicker_elapsed: 0.0,
+                flicker_duration: 0.42,
+                flicker_from: 800.0,
+                flicker_to: 800.0,
+                flicker_phase: 1.7,
+            },
+            PointLight {
+                intensity: 800.0,
+                ..default()
+            },
+        ))
+        .id();
+    let step = |app: &mut App, seconds: f32| {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(std::time::Duration::from_secs_f32(seconds));
+        app.update();
+    };
+
+    app.world_mut().resource_mut::<GhostHunt>().phase = HuntPhase::Hunting(5.0);
+    let mut intensities = Vec::new();
+    for _ in 0..12 {
+        step(&mut app, 0.1);
+        intensities.push(app.world().get::<PointLight>(light).unwrap().intensity);
+    }
+    // The flicker outlasts a single 0.42s burst.
+    assert!(intensities[..4].iter().any(|intensity| *intensity < 800.0));
+    assert!(intensities[8..].iter().any(|intensity| *intensity < 800.0));
+
+    app.world_mut().resource_mut::<GhostHunt>().phase = HuntPhase::Cooldown(30.0);
+    step(&mut app, 0.5);
+    assert!(
+        !app.world()
+            .get::<RoomLightVisual>(light)
+            .unwrap()
+            .flicker_active
+    );
+    assert_eq!(
+        app.world().get::<PointLight>(light).unwrap().intensity,
+        800.0
+    );
+}
@@ -1,5 +1,7 @@
 use crate::prelude::*;
 
+use shared::prelude::MoveInput;
+
 use crate::core::{JournalState, MenuState, RoleState, RoleYaw};
 
 pub mod evidence;
@@ -7,6 +9,7 @@ pub mod exorcism;
 pub mod ghost;
 pub mod investigator;
 pub mod map;
+pub mod spectator;
 
 pub struct GameplayPlugin;
 
@@ -17,12 +20,35 @@ impl Plugin for GameplayPlugin {
             ghost::GhostPlugin,
             investigator::InvestigatorPlugin,
             exorcism::ExorcismPlugin,
+            spectator::SpectatorPlugin,
         ))
         .init_resource::<evidence::EvidenceTuning>()
         .add_systems(Update, (handle_role_toggle, update_mouse_look));
     }
 }
 
+pub fn keyboard_move_input(keys: &ButtonInput<KeyCode>, control: &CameraControl) -> MoveInput {
+    let mut input = MoveInput {
+        yaw: control.yaw,
+        pitch: control.pitch,
+        sprint: keys.pressed(KeyCode::ShiftLeft) || keys.pressed(KeyCode::ShiftRight),
+        ..default()
+    };
+    if keys.pressed(KeyCode::KeyW) || keys.pressed(KeyCode::ArrowUp) {
+        input.forward += 1.0;
+    }
+    if keys.pressed(KeyCode::KeyS) || keys.pressed(KeyCode::ArrowDown) {
+        input.forward -= 1.0;
+    }
+    if keys.pressed(KeyCode::KeyA) || keys.pressed(KeyCode::ArrowLeft) {
+        input.strafe -= 1.0;
+    }
+    if keys.pressed(KeyCode::KeyD) || keys.pressed(KeyCode::ArrowRight) {
+        input.strafe += 1.0;
+    }
+    input
+}
+
 fn handle_role_toggle(
     keys: Res<ButtonInput<KeyCode>>,
     menu: Res<MenuState>,
@@ -38,6 +64,7 @@ fn handle_role_toggle(
         role.current = match role.current {
             Role::Ghost => Role::Investigator,
             Role::Investigator => Role::Ghost,
+            Role::Spectator => return,
         };
         set_default_camera(role.current, &mut control, &mut role_yaw);
     }
@@ -64,11 +91,11 @@ fn update_mouse_look(
         control.pitch -= event.delta.y * sensitivity;
     }
 
-    if role.current == Role::Ghost {
-        control.pitch = control.pitch.clamp(-0.6, 0.6);
-    } else {
-        control.pitch = control.pitch.clamp(-0.25, 0.4);
-    }
+    control.pitch = match role.current {
+        Role::Ghost => control.pitch.clamp(-0.6, 0.6),
+        Role::Investigator => control.pitch.clamp(-0.25, 0.4),
+        Role::Spectator => control.pitch.clamp(-1.4, 1.4),
+    };
 
     if control.yaw > std::f32::consts::PI {
         control.yaw -= std::f32::consts::TAU;
@@ -80,5 +107,6 @@ fn update_mouse_look(
     match role.current {
         Role::Ghost => role_yaw.ghost = control.yaw,
         Role::Investigator => role_yaw.investigator = control.yaw,
+        Role::Spectator => {}
     }
 }
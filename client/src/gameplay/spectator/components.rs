@@ -0,0 +1,30 @@
+use shared::prelude::PlayerId;
+
+use crate::prelude::*;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpectatorFollow {
+    #[default]
+    Free,
+    Player(PlayerId),
+}
+
+#[derive(Resource)]
+pub struct SpectatorState {
+    pub position: Vec3,
+    pub follow: SpectatorFollow,
+    pub ghost_type: Option<GhostType>,
+    // Set when the ghost caught this player and they watch the rest of the round.
+    pub caught: bool,
+}
+
+impl Default for SpectatorState {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 3.0, 0.0),
+            follow: SpectatorFollow::Free,
+            ghost_type: None,
+            caught: false,
+        }
+    }
+}
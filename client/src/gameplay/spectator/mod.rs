@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod systems;
+
+pub use components::{SpectatorFollow, SpectatorState};
+
+pub struct SpectatorPlugin;
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpectatorState>().add_systems(
+            Update,
+            (
+                systems::track_spectated_round,
+                systems::cycle_follow_target,
+                systems::spectator_camera_system,
+            )
+                .chain(),
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use shared::movement::{movement_delta, SPECTATOR_SPEED};
+use shared::prelude::{PlayerId, ServerMessage};
+
+use crate::prelude::*;
+
+use crate::gameplay::ghost::GhostState;
+use crate::gameplay::investigator::ActiveInvestigator;
+use crate::gameplay::keyboard_move_input;
+use crate::gameplay::map::components::CollisionWorld;
+use crate::gameplay::map::systems::{avoid_camera_obstacles, clamp_camera_distance};
+use crate::gameplay::spectator::{SpectatorFollow, SpectatorState};
+use crate::net::interpolation::{RemotePlayer, SnapshotBuffer};
+use crate::net::{ConnectionState, ServerEvent};
+
+const SPECTATOR_EYE_HEIGHT: f32 = 2.4;
+const SPECTATOR_MIN_HEIGHT: f32 = 0.5;
+const SPECTATOR_MAX_HEIGHT: f32 = 8.0;
+const FOLLOW_DISTANCE: f32 = 4.8;
+
+pub fn track_spectated_round(
+    connection: Res<ConnectionState>,
+    mut server_events: EventReader<ServerEvent>,
+    mut role: ResMut<RoleState>,
+    mut menu: ResMut<MenuState>,
+    mut journal: ResMut<JournalState>,
+    mut control: ResMut<CameraControl>,
+    mut role_yaw: ResMut<RoleYaw>,
+    mut spectator: ResMut<SpectatorState>,
+    investigators: Query<&Transform, With<ActiveInvestigator>>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::RoundStarted { spawn, .. }
+                if connection.own_role() == Some(Role::Spectator) =>
+            {
+                role.current = Role::Spectator;
+                menu.selected_role = Role::Spectator;
+                menu.open = false;
+                journal.open = false;
+                set_default_camera(Role::Spectator, &mut control, &mut role_yaw);
+                *spectator = SpectatorState {
+                    position: Vec3::new(spawn.x, SPECTATOR_EYE_HEIGHT, spawn.z),
+                    ..default()
+                };
+            }
+            // A caught investigator keeps watching from where they fell.
+            ServerMessage::Eliminated { player } if connection.player_id == Some(*player) => {
+                let fell_at = investigators
+                    .get_single()
+                    .map_or(spectator.position, |body| body.translation);
+                role.current = Role::Spectator;
+                journal.open = false;
+                set_default_camera(Role::Spectator, &mut control, &mut role_yaw);
+                *spectator = SpectatorState {
+                    position: Vec3::new(fell_at.x, SPECTATOR_EYE_HEIGHT, fell_at.z),
+                    caught: true,
+                    ..default()
+                };
+            }
+            ServerMessage::Snapshot(snapshot) if snapshot.ghost_type.is_some() => {
+                spectator.ghost_type = snapshot.ghost_type;
+            }
+            ServerMessage::RoomUpdate(room) if !room.in_round => {
+                spectator.ghost_type = None;
+                spectator.follow = SpectatorFollow::Free;
+                spectator.caught = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn cycle_follow_target(
+    keys: Res<ButtonInput<KeyCode>>,
+    role: Res<RoleState>,
+    menu: Res<MenuState>,
+    buffer: Res<SnapshotBuffer>,
+    mut spectator: ResMut<SpectatorState>,
+) {
+    if menu.open || role.current != Role::Spectator {
+        return;
+    }
+    if keys.just_pressed(KeyCode::KeyF) {
+        spectator.follow = SpectatorFollow::Free;
+        return;
+    }
+    let forward = keys.just_pressed(KeyCode::Tab) || keys.just_pressed(KeyCode::KeyE);
+    let backward = keys.just_pressed(KeyCode::KeyQ);
+    if !forward && !backward {
+        return;
+    }
+    let targets: Vec<PlayerId> = buffer.tracks().map(|(id, _)| id).collect();
+    spectator.follow = next_follow(spectator.follow, &targets, forward);
+}
+
+// Cycling walks through every tracked player and drops back to the free
+// camera after the last one.
+pub fn next_follow(
+    current: SpectatorFollow,
+    targets: &[PlayerId],
+    forward: bool,
+) -> SpectatorFollow {
+    let index = match current {
+        SpectatorFollow::Player(id) => targets.iter().position(|target| *target == id),
+        SpectatorFollow::Free => None,
+    };
+    let next = match (index, forward) {
+        (None, true) => (!targets.is_empty()).then_some(0),
+        (None, false) => targets.len().checked_sub(1),
+        (Some(index), true) => (index + 1 < targets.len()).then_some(index + 1),
+        (Some(index), false) => index.checked_sub(1),
+    };
+    next.map_or(SpectatorFollow::Free, |index| {
+        SpectatorFollow::Player(targets[index])
+    })
+}
+
+pub fn spectator_camera_system(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    role: Res<RoleState>,
+    menu: Res<MenuState>,
+    control: Res<CameraControl>,
+    world: Res<CollisionWorld>,
+    buffer: Res<SnapshotBuffer>,
+    ghost: Res<GhostState>,
+    remotes: Query<(&RemotePlayer, &Transform), Without<Camera>>,
+    mut spectator: ResMut<SpectatorState>,
+    mut camera_query: Query<&mut Transform, (With<Camera>, Without<RemotePlayer>)>,
+) {
+    if menu.open || role.current != Role::Spectator {
+        return;
+    }
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let look = Vec3::new(
+        control.yaw.sin() * control.pitch.cos(),
+        control.pitch.sin(),
+        control.yaw.cos() * control.pitch.cos(),
+    );
+    let followed = match spectator.follow {
+        SpectatorFollow::Player(id) => followed_position(id, &buffer, &ghost, &remotes),
+        SpectatorFollow::Free => None,
+    };
+
+    if let Some(target) = followed {
+        let base = target + Vec3::new(0.0, 1.6, 0.0);
+        let dir = -look.normalize_or_zero();
+        let mut distance = clamp_camera_distance(base, dir, FOLLOW_DISTANCE, world.bounds);
+        distance = avoid_camera_obstacles(base, dir, distance, 0.35, &world.obstacles);
+        camera.translation = base + dir * distance;
+        camera.look_at(target + Vec3::new(0.0, 1.2, 0.0), Vec3::Y);
+        spectator.position = camera.translation;
+        return;
+    }
+    // The followed player left; stay where the camera is and fly freely.
+    spectator.follow = SpectatorFollow::Free;
+
+    let dt = time.delta_seconds();
+    let input = keyboard_move_input(&keys, &control);
+    let mut position = spectator.position + movement_delta(&input, Role::Spectator, dt);
+    let mut rise = 0.0;
+    if keys.pressed(KeyCode::Space) {
+        rise += 1.0;
+    }
+    if keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::KeyC) {
+        rise -= 1.0;
+    }
+    position.y += rise * SPECTATOR_SPEED * dt;
+
+    let bounds = world.bounds;
+    position.x = position.x.clamp(bounds.min_x, bounds.max_x);
+    position.z = position.z.clamp(bounds.min_z, bounds.max_z);
+    position.y = position.y.clamp(SPECTATOR_MIN_HEIGHT, SPECTATOR_MAX_HEIGHT);
+    spectator.position = position;
+
+    camera.translation = position;
+    camera.look_at(position + look, Vec3::Y);
+}
+
+fn followed_position(
+    id: PlayerId,
+    buffer: &SnapshotBuffer,
+    ghost: &GhostState,
+    remotes: &Query<(&RemotePlayer, &Transform), Without<Camera>>,
+) -> Option<Vec3> {
+    match buffer.track(id)?.role {
+        Role::Ghost => Some(ghost.position),
+        _ => remotes
+            .iter()
+            .find(|(remote, _)| remote.id == id)
+            .map(|(_, transform)| transform.translation),
+    }
+}
+
+#[cfg(test)]
+#[path = "systems_tests.rs"]
+mod systems_tests;
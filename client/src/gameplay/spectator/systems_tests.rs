@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use shared::prelude::{HouseLayoutKind, RoomMember, RoomState, ServerMessage};
+
+use crate::core::{CameraControl, JournalState, MenuState, Role, RoleState, RoleYaw};
+use crate::gameplay::ghost::GhostState;
+use crate::gameplay::map::components::{Bounds, CollisionWorld, Obstacle};
+use crate::gameplay::spectator::systems::{
+    next_follow, spectator_camera_system, track_spectated_round,
+};
+use crate::gameplay::spectator::{SpectatorFollow, SpectatorState};
+use crate::net::interpolation::SnapshotBuffer;
+use crate::net::{ConnectionState, ServerEvent};
+
+fn spectator_app() -> App {
+    let mut app = App::new();
+    app.add_event::<ServerEvent>()
+        .add_systems(
+            Update,
+            (track_spectated_round, spectator_camera_system).chain(),
+        )
+        .insert_resource(MenuState {
+            open: true,
+            selected_role: Role::Ghost,
+        })
+        .insert_resource(RoleState {
+            current: Role::Ghost,
+        })
+        .insert_resource(JournalState { open: false })
+        .insert_resource(RoleYaw {
+            ghost: 0.0,
+            investigator: 0.0,
+        })
+        .insert_resource(CameraControl {
+            yaw: std::f32::consts::FRAC_PI_2,
+            pitch: 0.0,
+        })
+        .insert_resource(CollisionWorld {
+            bounds: Bounds {
+                min_x: -9.4,
+                max_x: 9.4,
+                min_z: -9.4,
+                max_z: 9.4,
+            },
+            obstacles: vec![Obstacle {
+                min_x: 1.8,
+                max_x: 2.2,
+                min_z: -9.4,
+                max_z: 9.4,
+            }],
+        })
+        .insert_resource(ConnectionState {
+            player_id: Some(4),
+            room: Some(RoomState {
+                code: "ABCD".to_string(),
+                name: "Haunted".to_string(),
+                layout: HouseLayoutKind::TwoRoom,
+                members: vec![RoomMember {
+                    id: 4,
+                    display_name: "Stream".to_string(),
+                    role: Some(Role::Spectator),
+                }],
+                in_round: true,
+            }),
+            ..default()
+        })
+        .insert_resource(GhostState {
+            position: Vec3::ZERO,
+        })
+        .init_resource::<SnapshotBuffer>()
+        .init_resource::<SpectatorState>()
+        .insert_resource(Time::<()>::default())
+        .insert_resource(ButtonInput::<KeyCode>::default());
+    app.world_mut().spawn(Camera3dBundle::default());
+    app
+}
+
+#[test]
+fn round_start_enters_spectator_mode() {
+    let mut app = spectator_app();
+    app.world_mut()
+        .send_event(ServerEvent(ServerMessage::RoundStarted {
+            layout: HouseLayoutKind::TwoRoom,
+            spawn: Vec3::new(1.0, 0.9, -3.0),
+            banshee_order: vec![2, 0, 1],
+        }));
+    app.update();
+
+    assert_eq!(app.world().resource::<RoleState>().current, Role::Spectator);
+    assert!(!app.world().resource::<MenuState>().open);
+    let spectator = app.world().resource::<SpectatorState>();
+    assert_eq!(spectator.follow, SpectatorFollow::Free);
+    assert_eq!(spectator.position.x, 1.0);
+    assert_eq!(spectator.position.z, -3.0);
+}
+
+#[test]
+fn free_camera_flies_through_walls_and_climbs() {
+    let mut app = spectator_app();
+    app.world_mut().resource_mut::<MenuState>().open = false;
+    app.world_mut().resource_mut::<RoleState>().current = Role::Spectator;
+    app.world_mut().resource_mut::<SpectatorState>().position = Vec3::new(1.2, 2.0, 0.0);
+    {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.press(KeyCode::KeyW);
+        input.press(KeyCode::Space);
+    }
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(std::time::Duration::from_secs_f32(0.2));
+    app.update();
+
+    let position = app.world().resource::<SpectatorState>().position;
+    assert!(position.x > 2.2);
+    assert!(position.y > 2.0);
+}
+
+#[test]
+fn follow_cycles_through_players_and_back_to_free() {
+    let targets = [2, 5, 9];
+    let mut follow = SpectatorFollow::Free;
+    let mut seen = Vec::new();
+    for _ in 0..4 {
+        follow = next_follow(follow, &targets, true);
+        seen.push(follow);
+    }
+    assert_eq!(
+        seen,
+        vec![
+            SpectatorFollow::Player(2),
+            SpectatorFollow::Player(5),
+            SpectatorFollow::Player(9),
+            SpectatorFollow::Free,
+        ]
+    );
+    assert_eq!(
+        next_follow(SpectatorFollow::Free, &targets, false),
+        SpectatorFollow::Player(9)
+    );
+    assert_eq!(
+        next_follow(SpectatorFollow::Player(2), &targets, false),
+        SpectatorFollow::Free
+    );
+}
+
+#[test]
+fn follow_drops_players_who_left() {
+    assert_eq!(
+        next_follow(SpectatorFollow::Player(4), &[], true),
+        SpectatorFollow::Free
+    );
+    assert_eq!(
+        next_follow(SpectatorFollow::Player(4), &[7], true),
+        SpectatorFollow::Player(7)
+    );
+}
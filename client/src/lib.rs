@@ -1,5 +1,6 @@
 pub mod app;
 pub mod core;
 pub mod gameplay;
+pub mod net;
 pub mod prelude;
 pub mod ui;
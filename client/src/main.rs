@@ -1,6 +1,7 @@
 mod app;
 mod core;
 mod gameplay;
+mod net;
 mod prelude;
 mod ui;
 
@@ -0,0 +1,228 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use crossbeam_channel::{self, Receiver, Sender};
+use shared::codec::{self, Frame};
+use shared::prelude::{
+    ClientMessage, GuestToken, GuestTokenRequest, ServerMessage, WireCodec, PROTOCOL_VERSION,
+};
+use tungstenite::http::StatusCode;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Error, Message, WebSocket};
+
+use crate::net::{ConnectionStatus, NetworkConfig};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const READ_TIMEOUT: Duration = Duration::from_millis(20);
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum SessionEnd {
+    Closed,
+    Incompatible,
+}
+
+pub enum NetworkEvent {
+    Status(ConnectionStatus),
+    Message(ServerMessage),
+}
+
+#[derive(Resource)]
+pub struct NetworkChannels {
+    pub tx: Sender<ClientMessage>,
+    pub rx: Receiver<NetworkEvent>,
+}
+
+pub fn spawn_connection_thread(config: NetworkConfig) -> NetworkChannels {
+    let (outgoing_tx, outgoing_rx) = crossbeam_channel::unbounded::<ClientMessage>();
+    let (events_tx, events_rx) = crossbeam_channel::unbounded::<NetworkEvent>();
+    thread::spawn(move || {
+        let mut guest_token = load_guest_token(&config, unix_ms()).map(|saved| saved.token);
+        let mut resume_token: Option<String> = None;
+        loop {
+            if events_tx
+                .send(NetworkEvent::Status(ConnectionStatus::Connecting))
+                .is_err()
+            {
+                return;
+            }
+            if guest_token.is_none() {
+                guest_token = fetch_guest_token(&config).map(|issued| {
+                    save_guest_token(&config, &issued);
+                    issued.token
+                });
+            }
+            if let Some(token) = guest_token.as_deref() {
+                match tungstenite::connect(config.ws_url(token)) {
+                    Ok((mut socket, _)) => {
+                        let end = run_session(
+                            &mut socket,
+                            &config,
+                            &outgoing_rx,
+                            &events_tx,
+                            &mut resume_token,
+                        );
+                        if let SessionEnd::Incompatible = end {
+                            // Retrying cannot help until one side is updated.
+                            let _ = events_tx
+                                .send(NetworkEvent::Status(ConnectionStatus::Incompatible));
+                            return;
+                        }
+                    }
+                    // Expired, or the server lost its secret: get a fresh one.
+                    Err(Error::Http(response)) if response.status() == StatusCode::UNAUTHORIZED => {
+                        guest_token = None;
+                    }
+                    Err(_) => {}
+                }
+            }
+            if events_tx
+                .send(NetworkEvent::Status(ConnectionStatus::Disconnected))
+                .is_err()
+            {
+                return;
+            }
+            thread::sleep(RECONNECT_DELAY);
+        }
+    });
+    NetworkChannels {
+        tx: outgoing_tx,
+        rx: events_rx,
+    }
+}
+
+fn fetch_guest_token(config: &NetworkConfig) -> Option<GuestToken> {
+    let response = reqwest::blocking::Client::builder()
+        .timeout(AUTH_TIMEOUT)
+        .build()
+        .ok()?
+        .post(config.auth_url())
+        .json(&GuestTokenRequest {
+            display_name: config.display_name.clone(),
+        })
+        .send()
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().ok()
+}
+
+// A token from an earlier run is reused while it is for the same name and
+// has not expired, so the player keeps their account and profile.
+pub(crate) fn load_guest_token(config: &NetworkConfig, now_ms: u64) -> Option<GuestToken> {
+    let text = fs::read_to_string(config.token_path.as_ref()?).ok()?;
+    let saved: GuestToken = serde_json::from_str(&text).ok()?;
+    (saved.display_name == config.display_name.trim() && saved.expires_ms > now_ms).then_some(saved)
+}
+
+pub(crate) fn save_guest_token(config: &NetworkConfig, issued: &GuestToken) {
+    let Some(path) = config.token_path.as_ref() else {
+        return;
+    };
+    let saved = serde_json::to_string_pretty(issued).map(|text| fs::write(path, text));
+    if !matches!(saved, Ok(Ok(()))) {
+        warn!("could not save the guest token to {}", path.display());
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn run_session(
+    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    config: &NetworkConfig,
+    outgoing: &Receiver<ClientMessage>,
+    events: &Sender<NetworkEvent>,
+    resume_token: &mut Option<String>,
+) -> SessionEnd {
+    if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+        let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    }
+    // Anything queued while offline is stale by now.
+    while outgoing.try_recv().is_ok() {}
+    // Until the welcome names a codec both sides speak JSON.
+    let mut wire = WireCodec::Json;
+    let hello = ClientMessage::Hello {
+        version: PROTOCOL_VERSION,
+        resume_token: resume_token.clone(),
+        codecs: config.offered_codecs(),
+    };
+    if !send_client_message(socket, wire, &hello) {
+        return SessionEnd::Closed;
+    }
+
+    loop {
+        while let Ok(message) = outgoing.try_recv() {
+            if !send_client_message(socket, wire, &message) {
+                return SessionEnd::Closed;
+            }
+        }
+        let decoded = match socket.read() {
+            Ok(Message::Text(text)) => codec::decode_text::<ServerMessage>(&text),
+            Ok(Message::Binary(bytes)) => codec::decode_binary::<ServerMessage>(&bytes),
+            Ok(Message::Close(_)) => return SessionEnd::Closed,
+            Ok(_) => None,
+            Err(Error::Io(err))
+                if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                None
+            }
+            Err(_) => return SessionEnd::Closed,
+        };
+        let Some(message) = decoded else {
+            continue;
+        };
+        match &message {
+            ServerMessage::Welcome {
+                resume_token: token,
+                codec,
+                ..
+            } => {
+                *resume_token = Some(token.clone());
+                wire = *codec;
+                let _ = events.send(NetworkEvent::Status(ConnectionStatus::Connected));
+            }
+            ServerMessage::Heartbeat { seq } => {
+                let ack = ClientMessage::HeartbeatAck { seq: *seq };
+                if !send_client_message(socket, wire, &ack) {
+                    return SessionEnd::Closed;
+                }
+                continue;
+            }
+            ServerMessage::ProtocolMismatch(_) => {
+                let _ = events.send(NetworkEvent::Message(message));
+                let _ = socket.close(None);
+                return SessionEnd::Incompatible;
+            }
+            _ => {}
+        }
+        if events.send(NetworkEvent::Message(message)).is_err() {
+            let _ = socket.close(None);
+            return SessionEnd::Closed;
+        }
+    }
+}
+
+fn send_client_message(
+    socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
+    codec: WireCodec,
+    message: &ClientMessage,
+) -> bool {
+    let frame = match codec.encode(message) {
+        Some(Frame::Text(text)) => Message::Text(text),
+        Some(Frame::Binary(bytes)) => Message::Binary(bytes),
+        None => return true,
+    };
+    socket.send(frame).is_ok()
+}
+
+#[cfg(test)]
+#[path = "connection_tests.rs"]
+mod connection_tests;
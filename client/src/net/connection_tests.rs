@@ -0,0 +1,57 @@
+use shared::prelude::GuestToken;
+
+use crate::net::connection::{load_guest_token, save_guest_token};
+use crate::net::NetworkConfig;
+
+fn config_with_token_file(name: &str) -> NetworkConfig {
+    let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    NetworkConfig {
+        display_name: "Ray".to_string(),
+        token_path: Some(path),
+        ..NetworkConfig::default()
+    }
+}
+
+fn issued(display_name: &str, expires_ms: u64) -> GuestToken {
+    GuestToken {
+        token: "payload.signature".to_string(),
+        account_id: "guest-0000000000000001".to_string(),
+        display_name: display_name.to_string(),
+        expires_ms,
+    }
+}
+
+#[test]
+fn saved_tokens_are_reused_by_the_next_run() {
+    let config = config_with_token_file("saved-token");
+    assert_eq!(load_guest_token(&config, 1_000), None);
+
+    save_guest_token(&config, &issued("Ray", 5_000));
+    assert_eq!(load_guest_token(&config, 1_000), Some(issued("Ray", 5_000)));
+    let _ = std::fs::remove_file(config.token_path.unwrap());
+}
+
+#[test]
+fn expired_or_renamed_tokens_are_fetched_again() {
+    let config = config_with_token_file("stale-token");
+    save_guest_token(&config, &issued("Ray", 5_000));
+    assert_eq!(load_guest_token(&config, 5_000), None);
+
+    let renamed = NetworkConfig {
+        display_name: "Egon".to_string(),
+        ..config.clone()
+    };
+    assert_eq!(load_guest_token(&renamed, 1_000), None);
+    let _ = std::fs::remove_file(config.token_path.unwrap());
+}
+
+#[test]
+fn tokens_stay_in_memory_without_a_path() {
+    let config = NetworkConfig {
+        token_path: None,
+        ..NetworkConfig::default()
+    };
+    save_guest_token(&config, &issued("Player", 5_000));
+    assert_eq!(load_guest_token(&config, 1_000), None);
+}
@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::prelude::*;
+use shared::prelude::{MatchSnapshot, PlayerId, Role, ServerMessage};
+
+use crate::core::RoleState;
+use crate::gameplay::ghost::GhostState;
+use crate::gameplay::map::systems::shortest_angle;
+use crate::net::{ConnectionState, ServerEvent};
+
+pub const INTERPOLATION_DELAY: f64 = 0.1;
+pub const MAX_EXTRAPOLATION: f64 = 0.25;
+const MAX_SAMPLES: usize = 32;
+const CLOCK_SMOOTH: f64 = 0.1;
+const CLOCK_RESYNC: f64 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RemoteSample {
+    pub server_time: f64,
+    pub position: Vec3,
+    pub yaw: f32,
+}
+
+pub struct RemoteTrack {
+    pub role: Role,
+    samples: VecDeque<RemoteSample>,
+}
+
+impl RemoteTrack {
+    pub fn sample_at(&self, server_time: f64) -> Option<RemoteSample> {
+        let newest = *self.samples.back()?;
+        if server_time >= newest.server_time {
+            let Some(previous) = self.samples.iter().rev().nth(1).copied() else {
+                return Some(newest);
+            };
+            let ahead = (server_time - newest.server_time).min(MAX_EXTRAPOLATION);
+            let span = newest.server_time - previous.server_time;
+            if span <= f64::EPSILON {
+                return Some(newest);
+            }
+            let velocity = (newest.position - previous.position) / span as f32;
+            return Some(RemoteSample {
+                server_time,
+                position: newest.position + velocity * ahead as f32,
+                yaw: newest.yaw,
+            });
+        }
+
+        let after = self
+            .samples
+            .iter()
+            .position(|sample| sample.server_time >= server_time)?;
+        if after == 0 {
+            return self.samples.front().copied();
+        }
+        let from = self.samples[after - 1];
+        let to = self.samples[after];
+        let t = ((server_time - from.server_time) / (to.server_time - from.server_time)) as f32;
+        Some(RemoteSample {
+            server_time,
+            position: from.position.lerp(to.position, t),
+            yaw: from.yaw + shortest_angle(from.yaw, to.yaw) * t,
+        })
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SnapshotBuffer {
+    tracks: BTreeMap<PlayerId, RemoteTrack>,
+    clock_offset: Option<f64>,
+}
+
+impl SnapshotBuffer {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn push(&mut self, snapshot: &MatchSnapshot, local: Option<PlayerId>, received_at: f64) {
+        let offset = snapshot.server_time - received_at;
+        self.clock_offset = Some(match self.clock_offset {
+            Some(current) if (offset - current).abs() < CLOCK_RESYNC => {
+                current + (offset - current) * CLOCK_SMOOTH
+            }
+            _ => offset,
+        });
+
+        self.tracks.retain(|id, _| {
+            snapshot
+                .players
+                .iter()
+                .any(|player| player.id == *id && Some(*id) != local)
+        });
+        for player in &snapshot.players {
+            if Some(player.id) == local {
+                continue;
+            }
+            let track = self.tracks.entry(player.id).or_insert_with(|| RemoteTrack {
+                role: player.role,
+                samples: VecDeque::new(),
+            });
+            track.role = player.role;
+            if track
+                .samples
+                .back()
+                .is_some_and(|last| last.server_time >= snapshot.server_time)
+            {
+                continue;
+            }
+            track.samples.push_back(RemoteSample {
+                server_time: snapshot.server_time,
+                position: player.position,
+                yaw: player.yaw,
+            });
+            while track.samples.len() > MAX_SAMPLES {
+                track.samples.pop_front();
+            }
+        }
+    }
+
+    pub fn render_time(&self, now: f64) -> Option<f64> {
+        self.clock_offset
+            .map(|offset| now + offset - INTERPOLATION_DELAY)
+    }
+
+    pub fn track(&self, id: PlayerId) -> Option<&RemoteTrack> {
+        self.tracks.get(&id)
+    }
+
+    pub fn tracks(&self) -> impl Iterator<Item = (PlayerId, &RemoteTrack)> {
+        self.tracks.iter().map(|(id, track)| (*id, track))
+    }
+}
+
+#[derive(Component)]
+pub struct RemotePlayer {
+    pub id: PlayerId,
+}
+
+pub fn record_snapshots(
+    time: Res<Time>,
+    connection: Res<ConnectionState>,
+    mut server_events: EventReader<ServerEvent>,
+    mut buffer: ResMut<SnapshotBuffer>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::Snapshot(snapshot) => {
+                buffer.push(snapshot, connection.player_id, time.elapsed_seconds_f64());
+            }
+            ServerMessage::RoundStarted { .. } => buffer.clear(),
+            ServerMessage::RoomUpdate(room) if !room.in_round => buffer.clear(),
+            _ => {}
+        }
+    }
+}
+
+pub fn sync_remote_players(
+    mut commands: Commands,
+    buffer: Res<SnapshotBuffer>,
+    remotes: Query<(Entity, &RemotePlayer)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, remote) in &remotes {
+        let keep = buffer
+            .track(remote.id)
+            .is_some_and(|track| track.role == Role::Investigator);
+        if !keep {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for (id, track) in buffer.tracks() {
+        if track.role != Role::Investigator || remotes.iter().any(|(_, remote)| remote.id == id) {
+            continue;
+        }
+        commands.spawn((
+            PbrBundle {
+                mesh: meshes.add(Cuboid::new(0.7, 1.8, 0.7)),
+                material: materials.add(StandardMaterial {
+                    base_color: Color::srgb(0.7, 0.6, 0.55),
+                    perceptual_roughness: 0.95,
+                    reflectance: 0.02,
+                    ..default()
+                }),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            RemotePlayer { id },
+        ));
+    }
+}
+
+pub fn interpolate_remote_players(
+    time: Res<Time>,
+    role: Res<RoleState>,
+    buffer: Res<SnapshotBuffer>,
+    mut ghost: ResMut<GhostState>,
+    mut remotes: Query<(&RemotePlayer, &mut Transform, &mut Visibility)>,
+) {
+    let Some(render_time) = buffer.render_time(time.elapsed_seconds_f64()) else {
+        return;
+    };
+    for (id, track) in buffer.tracks() {
+        let Some(sample) = track.sample_at(render_time) else {
+            continue;
+        };
+        match track.role {
+            Role::Ghost => {
+                if role.current != Role::Ghost {
+                    ghost.position = sample.position;
+                }
+            }
+            Role::Investigator => {
+                let Some((_, mut transform, mut visibility)) =
+                    remotes.iter_mut().find(|(remote, _, _)| remote.id == id)
+                else {
+                    continue;
+                };
+                transform.translation = sample.position;
+                transform.rotation = Quat::from_rotation_y(sample.yaw + std::f32::consts::PI);
+                *visibility = Visibility::Visible;
+            }
+            Role::Spectator => {}
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "interpolation_tests.rs"]
+mod interpolation_tests;
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use shared::prelude::{MatchSnapshot, PlayerSnapshot, Role};
+
+use crate::net::interpolation::{SnapshotBuffer, INTERPOLATION_DELAY, MAX_EXTRAPOLATION};
+
+fn snapshot(server_time: f64, x: f32) -> MatchSnapshot {
+    MatchSnapshot {
+        tick: (server_time * 20.0) as u64,
+        server_time,
+        players: vec![
+            PlayerSnapshot {
+                id: 1,
+                role: Role::Investigator,
+                position: Vec3::ZERO,
+                yaw: 0.0,
+                last_input_seq: 0,
+            },
+            PlayerSnapshot {
+                id: 2,
+                role: Role::Ghost,
+                position: Vec3::new(x, 1.6, 0.0),
+                yaw: 0.0,
+                last_input_seq: 0,
+            },
+        ],
+        ghost_type: None,
+    }
+}
+
+#[test]
+fn local_player_is_not_buffered() {
+    let mut buffer = SnapshotBuffer::default();
+    buffer.push(&snapshot(1.0, 0.0), Some(1), 5.0);
+    assert!(buffer.track(1).is_none());
+    assert_eq!(buffer.track(2).map(|track| track.role), Some(Role::Ghost));
+}
+
+#[test]
+fn interpolates_between_snapshots() {
+    let mut buffer = SnapshotBuffer::default();
+    buffer.push(&snapshot(1.0, 0.0), Some(1), 5.0);
+    buffer.push(&snapshot(1.05, 1.0), Some(1), 5.05);
+
+    let sample = buffer.track(2).unwrap().sample_at(1.025).unwrap();
+    assert!((sample.position.x - 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn render_time_trails_the_server_clock() {
+    let mut buffer = SnapshotBuffer::default();
+    assert!(buffer.render_time(5.0).is_none());
+    buffer.push(&snapshot(1.0, 0.0), Some(1), 5.0);
+    let render_time = buffer.render_time(5.0).unwrap();
+    assert!((render_time - (1.0 - INTERPOLATION_DELAY)).abs() < 1e-9);
+}
+
+#[test]
+fn extrapolation_is_capped_when_snapshots_stop() {
+    let mut buffer = SnapshotBuffer::default();
+    buffer.push(&snapshot(1.0, 0.0), Some(1), 5.0);
+    buffer.push(&snapshot(1.05, 1.0), Some(1), 5.05);
+
+    let track = buffer.track(2).unwrap();
+    let capped = track.sample_at(1.05 + MAX_EXTRAPOLATION).unwrap();
+    let later = track.sample_at(3.0).unwrap();
+    assert!(capped.position.x > 1.0);
+    assert_eq!(capped.position, later.position);
+}
+
+#[test]
+fn players_missing_from_a_snapshot_are_dropped() {
+    let mut buffer = SnapshotBuffer::default();
+    buffer.push(&snapshot(1.0, 0.0), None, 5.0);
+    let mut next = snapshot(1.05, 1.0);
+    next.players.retain(|player| player.id == 2);
+    buffer.push(&next, None, 5.05);
+
+    assert!(buffer.track(1).is_none());
+    assert!(buffer.track(2).is_some());
+}
@@ -0,0 +1,162 @@
+use crate::prelude::*;
+
+use std::path::PathBuf;
+
+use shared::prelude::{
+    ClientMessage, LobbyRejection, PlayerId, Role, RoomState, ServerMessage, VersionMismatch,
+    WireCodec,
+};
+
+pub mod connection;
+pub mod interpolation;
+pub mod prediction;
+pub mod systems;
+
+pub const DEFAULT_SERVER_ADDR: &str = "127.0.0.1:8000";
+pub const DEFAULT_DISPLAY_NAME: &str = "Player";
+pub const DEFAULT_TOKEN_PATH: &str = "client-token.json";
+
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct NetworkConfig {
+    pub server_addr: String,
+    pub display_name: String,
+    pub room_code: Option<String>,
+    pub codec: WireCodec,
+    // Where the guest token is kept between runs; `None` keeps it in memory.
+    pub token_path: Option<PathBuf>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            server_addr: DEFAULT_SERVER_ADDR.to_string(),
+            display_name: DEFAULT_DISPLAY_NAME.to_string(),
+            room_code: None,
+            codec: WireCodec::Binary,
+            token_path: Some(PathBuf::from(DEFAULT_TOKEN_PATH)),
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            server_addr: std::env::var("SERVER_ADDR").unwrap_or(defaults.server_addr),
+            display_name: std::env::var("PLAYER_NAME").unwrap_or(defaults.display_name),
+            room_code: std::env::var("ROOM_CODE")
+                .ok()
+                .filter(|code| !code.trim().is_empty()),
+            codec: match std::env::var("WIRE_CODEC") {
+                Ok(codec) if codec.trim().eq_ignore_ascii_case("json") => WireCodec::Json,
+                _ => defaults.codec,
+            },
+            token_path: match std::env::var("TOKEN_PATH") {
+                Ok(path) if path.trim().is_empty() => None,
+                Ok(path) => Some(PathBuf::from(path)),
+                Err(_) => defaults.token_path,
+            },
+        }
+    }
+
+    // JSON is always offered as a fallback for servers without binary support.
+    pub fn offered_codecs(&self) -> Vec<WireCodec> {
+        match self.codec {
+            WireCodec::Binary => vec![WireCodec::Binary, WireCodec::Json],
+            WireCodec::Json => vec![WireCodec::Json],
+        }
+    }
+
+    pub fn ws_url(&self, token: &str) -> String {
+        format!("ws://{}/ws?token={}", self.server_addr, token)
+    }
+
+    pub fn auth_url(&self) -> String {
+        format!("http://{}/auth/guest", self.server_addr)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    #[default]
+    Connecting,
+    Connected,
+    Disconnected,
+    Incompatible,
+}
+
+#[derive(Resource, Default)]
+pub struct ConnectionState {
+    pub status: ConnectionStatus,
+    pub player_id: Option<PlayerId>,
+    pub room: Option<RoomState>,
+    pub last_rejection: Option<LobbyRejection>,
+    pub server_shutting_down: bool,
+    pub protocol_mismatch: Option<VersionMismatch>,
+}
+
+impl ConnectionState {
+    pub fn own_role(&self) -> Option<Role> {
+        let id = self.player_id?;
+        self.room
+            .as_ref()?
+            .members
+            .iter()
+            .find(|member| member.id == id)?
+            .role
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct ServerEvent(pub ServerMessage);
+
+#[derive(Event, Clone, Debug)]
+pub struct OutgoingMessage(pub ClientMessage);
+
+pub struct NetworkPlugin;
+
+impl Plugin for NetworkPlugin {
+    fn build(&self, app: &mut App) {
+        let config = app
+            .world()
+            .get_resource::<NetworkConfig>()
+            .cloned()
+            .unwrap_or_else(NetworkConfig::from_env);
+        let channels = connection::spawn_connection_thread(config.clone());
+
+        app.insert_resource(config)
+            .insert_resource(channels)
+            .init_resource::<ConnectionState>()
+            .init_resource::<systems::LobbyChoices>()
+            .init_resource::<systems::PendingClaims>()
+            .init_resource::<prediction::PredictionState>()
+            .init_resource::<interpolation::SnapshotBuffer>()
+            .add_event::<ServerEvent>()
+            .add_event::<OutgoingMessage>()
+            .add_systems(
+                Update,
+                (
+                    systems::receive_network_events,
+                    systems::sync_quick_play,
+                    prediction::handle_round_events,
+                    prediction::smooth_prediction_correction,
+                    interpolation::record_snapshots,
+                    interpolation::sync_remote_players,
+                    interpolation::interpolate_remote_players,
+                    systems::apply_server_verdicts,
+                    systems::sync_team_evidence,
+                    systems::sync_tool_readings,
+                    systems::sync_banshee_order,
+                    systems::sync_hunt,
+                    systems::sync_profile,
+                    systems::sync_chat,
+                    systems::auto_join_room,
+                    systems::sync_lobby_choices,
+                    prediction::send_predicted_inputs,
+                    systems::report_claims,
+                    systems::forward_outgoing_messages,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use shared::movement::{apply_command, InputCommand, MoveInput, MAX_INPUT_DT};
+use shared::prelude::{ClientMessage, MatchSnapshot, Role, ServerMessage};
+
+use crate::core::RoleState;
+use crate::gameplay::ghost::GhostState;
+use crate::gameplay::investigator::systems::InvestigatorTeam;
+use crate::gameplay::investigator::ActiveInvestigator;
+use crate::gameplay::map::components::CollisionWorld;
+use crate::net::{ConnectionState, OutgoingMessage, ServerEvent};
+
+pub const SNAP_DISTANCE: f32 = 2.0;
+const CORRECTION_EPSILON: f32 = 0.01;
+const CORRECTION_SMOOTH: f32 = 12.0;
+
+#[derive(Resource, Default)]
+pub struct PredictionState {
+    active: bool,
+    next_seq: u32,
+    pending: VecDeque<(InputCommand, Role)>,
+    unsent: Vec<InputCommand>,
+    correction: Vec3,
+}
+
+impl PredictionState {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    #[cfg(test)]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn correction(&self) -> Vec3 {
+        self.correction
+    }
+
+    pub fn begin(&mut self) {
+        *self = Self {
+            active: true,
+            ..Self::default()
+        };
+    }
+
+    pub fn stop(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn take_unsent(&mut self) -> Vec<InputCommand> {
+        std::mem::take(&mut self.unsent)
+    }
+
+    fn record(&mut self, input: MoveInput, dt: f32, role: Role) -> InputCommand {
+        let seq = if self.active {
+            self.next_seq += 1;
+            self.next_seq
+        } else {
+            0
+        };
+        let command = InputCommand { seq, dt, input };
+        if self.active {
+            self.pending.push_back((command, role));
+            self.unsent.push(command);
+        }
+        command
+    }
+
+    pub fn reconcile(
+        &mut self,
+        authoritative: Vec3,
+        acked_seq: u32,
+        predicted: Vec3,
+        world: &CollisionWorld,
+    ) -> Option<Vec3> {
+        while self
+            .pending
+            .front()
+            .is_some_and(|(command, _)| command.seq <= acked_seq)
+        {
+            self.pending.pop_front();
+        }
+        let mut replayed = authoritative;
+        for (command, role) in &self.pending {
+            apply_command(&mut replayed, command, *role, world);
+        }
+        let error = replayed - predicted;
+        if error.length() > SNAP_DISTANCE {
+            self.correction = Vec3::ZERO;
+            return Some(replayed);
+        }
+        self.correction = if error.length() > CORRECTION_EPSILON {
+            error
+        } else {
+            Vec3::ZERO
+        };
+        None
+    }
+
+    fn drain_correction(&mut self, dt: f32) -> Vec3 {
+        if self.correction.length_squared() <= CORRECTION_EPSILON * CORRECTION_EPSILON {
+            let rest = self.correction;
+            self.correction = Vec3::ZERO;
+            return rest;
+        }
+        let blend = 1.0 - (-dt * CORRECTION_SMOOTH).exp();
+        let step = self.correction * blend;
+        self.correction -= step;
+        step
+    }
+}
+
+pub fn predict_movement(
+    position: &mut Vec3,
+    input: MoveInput,
+    role: Role,
+    dt: f32,
+    world: &CollisionWorld,
+    mut prediction: Option<&mut PredictionState>,
+) {
+    let mut remaining = dt.max(0.0);
+    while remaining > 0.0 {
+        let step = remaining.min(MAX_INPUT_DT);
+        remaining -= step;
+        let command = match prediction.as_deref_mut() {
+            Some(prediction) => prediction.record(input, step, role),
+            None => InputCommand {
+                seq: 0,
+                dt: step,
+                input,
+            },
+        };
+        apply_command(position, &command, role, world);
+    }
+}
+
+pub fn handle_round_events(
+    mut server_events: EventReader<ServerEvent>,
+    connection: Res<ConnectionState>,
+    role: Res<RoleState>,
+    world: Res<CollisionWorld>,
+    mut prediction: ResMut<PredictionState>,
+    mut ghost: ResMut<GhostState>,
+    mut team: Option<ResMut<InvestigatorTeam>>,
+    mut players: Query<&mut Transform, With<ActiveInvestigator>>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::RoundStarted { .. }
+                if connection.own_role() == Some(Role::Spectator) =>
+            {
+                prediction.stop();
+            }
+            ServerMessage::RoundStarted { spawn, .. } => {
+                prediction.begin();
+                write_position(role.current, *spawn, &mut ghost, &mut players);
+                // Online, each client brings exactly one investigator.
+                if let (Role::Investigator, Some(team)) = (role.current, team.as_mut()) {
+                    team.arrange(vec![*spawn]);
+                }
+            }
+            ServerMessage::RoomUpdate(room) if !room.in_round => prediction.stop(),
+            ServerMessage::Eliminated { player } if connection.player_id == Some(*player) => {
+                prediction.stop();
+            }
+            ServerMessage::Snapshot(snapshot) if prediction.is_active() => {
+                reconcile_snapshot(
+                    snapshot,
+                    &connection,
+                    &world,
+                    &mut prediction,
+                    &mut ghost,
+                    &mut players,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn reconcile_snapshot(
+    snapshot: &MatchSnapshot,
+    connection: &ConnectionState,
+    world: &CollisionWorld,
+    prediction: &mut PredictionState,
+    ghost: &mut GhostState,
+    players: &mut Query<&mut Transform, With<ActiveInvestigator>>,
+) {
+    let Some(me) = snapshot
+        .players
+        .iter()
+        .find(|player| Some(player.id) == connection.player_id)
+    else {
+        return;
+    };
+    let predicted = match me.role {
+        Role::Ghost => ghost.position,
+        Role::Investigator => match players.get_single() {
+            Ok(transform) => transform.translation,
+            Err(_) => return,
+        },
+        Role::Spectator => return,
+    };
+    if let Some(snapped) = prediction.reconcile(me.position, me.last_input_seq, predicted, world) {
+        write_position(me.role, snapped, ghost, players);
+    }
+}
+
+fn write_position(
+    role: Role,
+    position: Vec3,
+    ghost: &mut GhostState,
+    players: &mut Query<&mut Transform, With<ActiveInvestigator>>,
+) {
+    match role {
+        Role::Ghost => ghost.position = position,
+        Role::Investigator => {
+            if let Ok(mut transform) = players.get_single_mut() {
+                transform.translation = position;
+            }
+        }
+        Role::Spectator => {}
+    }
+}
+
+pub fn smooth_prediction_correction(
+    time: Res<Time>,
+    role: Res<RoleState>,
+    mut prediction: ResMut<PredictionState>,
+    mut ghost: ResMut<GhostState>,
+    mut players: Query<&mut Transform, With<ActiveInvestigator>>,
+) {
+    if !prediction.is_active() || prediction.correction() == Vec3::ZERO {
+        return;
+    }
+    let step = prediction.drain_correction(time.delta_seconds());
+    match role.current {
+        Role::Ghost => ghost.position += step,
+        Role::Investigator => {
+            if let Ok(mut transform) = players.get_single_mut() {
+                transform.translation += step;
+            }
+        }
+        Role::Spectator => {}
+    }
+}
+
+pub fn send_predicted_inputs(
+    mut prediction: ResMut<PredictionState>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    for command in prediction.take_unsent() {
+        outgoing.send(OutgoingMessage(ClientMessage::Input(command)));
+    }
+}
+
+#[cfg(test)]
+#[path = "prediction_tests.rs"]
+mod prediction_tests;
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use shared::prelude::{MoveInput, Role};
+
+use crate::gameplay::map::components::{Bounds, CollisionWorld};
+use crate::net::prediction::{predict_movement, PredictionState, SNAP_DISTANCE};
+
+fn open_world() -> CollisionWorld {
+    CollisionWorld {
+        bounds: Bounds {
+            min_x: -10.0,
+            max_x: 10.0,
+            min_z: -10.0,
+            max_z: 10.0,
+        },
+        obstacles: Vec::new(),
+    }
+}
+
+fn forward() -> MoveInput {
+    MoveInput {
+        forward: 1.0,
+        ..MoveInput::default()
+    }
+}
+
+#[test]
+fn inactive_prediction_records_nothing() {
+    let mut prediction = PredictionState::default();
+    let mut position = Vec3::ZERO;
+    predict_movement(
+        &mut position,
+        forward(),
+        Role::Investigator,
+        0.05,
+        &open_world(),
+        Some(&mut prediction),
+    );
+    assert!(position.z > 0.0);
+    assert_eq!(prediction.pending(), 0);
+    assert!(prediction.take_unsent().is_empty());
+}
+
+#[test]
+fn long_frames_are_split_into_sequenced_commands() {
+    let mut prediction = PredictionState::default();
+    prediction.begin();
+    let mut position = Vec3::ZERO;
+    predict_movement(
+        &mut position,
+        forward(),
+        Role::Investigator,
+        0.25,
+        &open_world(),
+        Some(&mut prediction),
+    );
+    let sent = prediction.take_unsent();
+    assert_eq!(
+        sent.iter().map(|command| command.seq).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!((sent.iter().map(|command| command.dt).sum::<f32>() - 0.25).abs() < 1e-5);
+    assert_eq!(prediction.pending(), 3);
+}
+
+#[test]
+fn reconcile_replays_unacknowledged_inputs() {
+    let world = open_world();
+    let mut prediction = PredictionState::default();
+    prediction.begin();
+    let mut position = Vec3::ZERO;
+    for _ in 0..4 {
+        predict_movement(
+            &mut position,
+            forward(),
+            Role::Investigator,
+            0.05,
+            &world,
+            Some(&mut prediction),
+        );
+    }
+
+    let mut acked = Vec3::ZERO;
+    for _ in 0..2 {
+        predict_movement(
+            &mut acked,
+            forward(),
+            Role::Investigator,
+            0.05,
+            &world,
+            None,
+        );
+    }
+    let snapped = prediction.reconcile(acked, 2, position, &world);
+
+    assert!(snapped.is_none());
+    assert_eq!(prediction.pending(), 2);
+    assert!(prediction.correction().length() < 1e-4);
+}
+
+#[test]
+fn small_errors_are_smoothed_and_large_ones_snap() {
+    let world = open_world();
+    let mut prediction = PredictionState::default();
+    prediction.begin();
+
+    let nudged = Vec3::new(0.5, 0.0, 0.0);
+    assert!(prediction
+        .reconcile(nudged, 0, Vec3::ZERO, &world)
+        .is_none());
+    assert!((prediction.correction() - nudged).length() < 1e-4);
+
+    let far = Vec3::new(SNAP_DISTANCE + 1.0, 0.0, 0.0);
+    assert_eq!(prediction.reconcile(far, 0, Vec3::ZERO, &world), Some(far));
+    assert_eq!(prediction.correction(), Vec3::ZERO);
+}
@@ -0,0 +1,459 @@
+use bevy::prelude::*;
+use shared::prelude::{
+    ClientMessage, Equipment, EvidenceEvent, EvidenceKind, ExorcismState, GhostType,
+    HouseLayoutKind, HuntPhase, LobbyRejection, Role, ServerMessage,
+};
+
+use crate::core::{
+    ChatEntry, ChatState, GhostTypeState, JournalState, MenuFlowState, MenuScreen, MenuState,
+    ProfileState, QuickPlayState, ResolutionState, RoleState, SessionState,
+};
+use crate::gameplay::evidence::EvidenceTuning;
+use crate::gameplay::exorcism::{BansheeOrder, ExorcismStatus, InvestigationState};
+use crate::gameplay::ghost::GhostHunt;
+use crate::gameplay::investigator::tools::{EquipmentState, EvidenceState};
+use crate::gameplay::map::HouseLayoutSelection;
+use crate::net::connection::{NetworkChannels, NetworkEvent};
+use crate::net::prediction::PredictionState;
+use crate::net::{ConnectionState, ConnectionStatus, NetworkConfig, OutgoingMessage, ServerEvent};
+
+#[derive(Resource, Default)]
+pub struct LobbyChoices {
+    role: Option<Role>,
+    ghost_type: Option<GhostType>,
+    layout: Option<HouseLayoutKind>,
+    started: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct PendingClaims {
+    queued: Vec<ClientMessage>,
+    equipment: Option<Equipment>,
+    emf_five: bool,
+    spiritbox_response: bool,
+    guess: Option<GhostType>,
+    confirmed: bool,
+    complete: bool,
+}
+
+impl PendingClaims {
+    pub fn push(&mut self, message: ClientMessage) {
+        self.queued.push(message);
+    }
+}
+
+pub fn receive_network_events(
+    channels: Res<NetworkChannels>,
+    mut connection: ResMut<ConnectionState>,
+    mut server_events: EventWriter<ServerEvent>,
+) {
+    while let Ok(event) = channels.rx.try_recv() {
+        match event {
+            NetworkEvent::Status(status) => connection.status = status,
+            NetworkEvent::Message(message) => {
+                match &message {
+                    ServerMessage::Welcome {
+                        player_id, room, ..
+                    } => {
+                        connection.player_id = *player_id;
+                        connection.room = room.clone();
+                        connection.server_shutting_down = false;
+                    }
+                    ServerMessage::RoomJoined { player_id, .. } => {
+                        connection.player_id = Some(*player_id);
+                        connection.last_rejection = None;
+                    }
+                    ServerMessage::RoomUpdate(room) => connection.room = Some(room.clone()),
+                    ServerMessage::LobbyRejected(rejection) => {
+                        connection.last_rejection = Some(*rejection);
+                    }
+                    ServerMessage::ShuttingDown { .. } => connection.server_shutting_down = true,
+                    ServerMessage::ProtocolMismatch(mismatch) => {
+                        connection.protocol_mismatch = Some(*mismatch);
+                    }
+                    _ => {}
+                }
+                server_events.send(ServerEvent(message));
+            }
+        }
+    }
+}
+
+// Arriving after the round started is not an error: the client watches
+// as a spectator until the room is back in the lobby.
+pub fn auto_join_room(
+    config: Res<NetworkConfig>,
+    connection: Res<ConnectionState>,
+    mut server_events: EventReader<ServerEvent>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        let Some(room_code) = config.room_code.clone() else {
+            continue;
+        };
+        let display_name = config.display_name.clone();
+        match message {
+            ServerMessage::Welcome {
+                player_id: None, ..
+            } => {
+                outgoing.send(OutgoingMessage(ClientMessage::JoinLobby {
+                    display_name,
+                    room_code,
+                }));
+            }
+            ServerMessage::LobbyRejected(LobbyRejection::RoundInProgress)
+                if connection.room.is_none() =>
+            {
+                outgoing.send(OutgoingMessage(ClientMessage::SpectateRoom {
+                    display_name,
+                    room_code,
+                }));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn sync_lobby_choices(
+    connection: Res<ConnectionState>,
+    mut menu: ResMut<MenuState>,
+    ghost_type: Res<GhostTypeState>,
+    session: Res<SessionState>,
+    house_selection: Option<Res<HouseLayoutSelection>>,
+    quick_play: Option<Res<QuickPlayState>>,
+    mut sent: ResMut<LobbyChoices>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    if connection.room.is_none() {
+        *sent = LobbyChoices::default();
+        return;
+    }
+    // Spectating and quick play hand out roles on the server; keep them.
+    if sent.role.is_none() {
+        if let Some(role) = connection.own_role() {
+            sent.role = Some(role);
+            menu.selected_role = role;
+        }
+    }
+
+    if sent.role != Some(menu.selected_role) {
+        sent.role = Some(menu.selected_role);
+        outgoing.send(OutgoingMessage(ClientMessage::ChooseRole {
+            role: menu.selected_role,
+        }));
+    }
+    if menu.selected_role == Role::Ghost {
+        if sent.ghost_type != Some(ghost_type.selected) {
+            sent.ghost_type = Some(ghost_type.selected);
+            outgoing.send(OutgoingMessage(ClientMessage::ChooseGhostType {
+                ghost_type: ghost_type.selected,
+            }));
+        }
+        if let Some(selection) = house_selection {
+            if sent.layout != Some(selection.selected_kind) {
+                sent.layout = Some(selection.selected_kind);
+                outgoing.send(OutgoingMessage(ClientMessage::ChooseLayout {
+                    layout: selection.selected_kind,
+                }));
+            }
+        }
+    }
+    let matched = quick_play.is_some_and(|quick_play| quick_play.matched);
+    if session.started && !sent.started && !matched {
+        outgoing.send(OutgoingMessage(ClientMessage::StartRound));
+    }
+    sent.started = session.started;
+}
+
+pub fn report_claims(
+    prediction: Res<PredictionState>,
+    equipment: Res<EquipmentState>,
+    evidence: Res<EvidenceState>,
+    investigation: Res<InvestigationState>,
+    exorcism: Res<ExorcismStatus>,
+    mut claims: ResMut<PendingClaims>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    if !prediction.is_active() {
+        *claims = PendingClaims::default();
+        return;
+    }
+
+    // The server needs the held tool to work out radio range for team chat.
+    if claims.equipment != Some(equipment.active) {
+        outgoing.send(OutgoingMessage(ClientMessage::SelectEquipment {
+            equipment: equipment.active,
+        }));
+        claims.equipment = Some(equipment.active);
+    }
+
+    if evidence.emf_five && !claims.emf_five {
+        outgoing.send(OutgoingMessage(ClientMessage::ClaimEvidence(
+            EvidenceKind::EmfFive,
+        )));
+    }
+    if evidence.spiritbox_response && !claims.spiritbox_response {
+        outgoing.send(OutgoingMessage(ClientMessage::ClaimEvidence(
+            EvidenceKind::SpiritboxResponse,
+        )));
+    }
+    claims.emf_five = evidence.emf_five;
+    claims.spiritbox_response = evidence.spiritbox_response;
+
+    if let Some(ghost_type) = investigation.guess {
+        if claims.guess != Some(ghost_type) && !claims.confirmed {
+            outgoing.send(OutgoingMessage(ClientMessage::JournalGuess { ghost_type }));
+        }
+    }
+    claims.guess = investigation.guess;
+    if investigation.confirmed && !claims.confirmed {
+        outgoing.send(OutgoingMessage(ClientMessage::JournalConfirm));
+    }
+    claims.confirmed = investigation.confirmed;
+
+    for message in std::mem::take(&mut claims.queued) {
+        outgoing.send(OutgoingMessage(message));
+    }
+
+    let complete = exorcism.state == ExorcismState::Complete;
+    if complete && !claims.complete {
+        outgoing.send(OutgoingMessage(ClientMessage::ClaimExorcism));
+    }
+    claims.complete = complete;
+}
+
+// Evidence a teammate collected goes straight into the shared journal and
+// is marked as claimed so this client does not claim it again.
+pub fn sync_team_evidence(
+    mut server_events: EventReader<ServerEvent>,
+    mut evidence: ResMut<EvidenceState>,
+    mut claims: ResMut<PendingClaims>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        let ServerMessage::Evidence(EvidenceEvent::Collected(kind)) = message else {
+            continue;
+        };
+        match kind {
+            EvidenceKind::EmfFive => {
+                evidence.emf_five = true;
+                claims.emf_five = true;
+            }
+            EvidenceKind::SpiritboxResponse => {
+                evidence.spiritbox_response = true;
+                claims.spiritbox_response = true;
+            }
+        }
+    }
+}
+
+// Tool readings come from the server, which knows the ghost type; a
+// spiritbox answer that counts as evidence is claimed like a local one.
+pub fn sync_tool_readings(
+    mut server_events: EventReader<ServerEvent>,
+    tuning: Res<EvidenceTuning>,
+    mut equipment: ResMut<EquipmentState>,
+    mut evidence: ResMut<EvidenceState>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::Evidence(EvidenceEvent::EmfLevel { level }) => {
+                equipment.emf_reading = Some(*level);
+            }
+            ServerMessage::Evidence(EvidenceEvent::SpiritboxReply {
+                message,
+                evidence: counts,
+            }) => {
+                equipment.spiritbox_message.clone_from(message);
+                if *counts {
+                    evidence.spiritbox_response = true;
+                    equipment.spiritbox_cooldown = tuning.spiritbox_cooldown_hit;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn sync_banshee_order(
+    mut server_events: EventReader<ServerEvent>,
+    order: Option<ResMut<BansheeOrder>>,
+) {
+    let Some(mut order) = order else {
+        return;
+    };
+    for ServerEvent(message) in server_events.read() {
+        if let ServerMessage::RoundStarted { banshee_order, .. } = message {
+            order.0.clone_from(banshee_order);
+        }
+    }
+}
+
+pub fn sync_hunt(mut server_events: EventReader<ServerEvent>, mut hunt: ResMut<GhostHunt>) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::RoundStarted { .. } => hunt.restart(),
+            ServerMessage::Hunt {
+                active,
+                remaining_ms,
+            } => {
+                let remaining = *remaining_ms as f32 / 1000.0;
+                hunt.phase = if *active {
+                    HuntPhase::Hunting(remaining)
+                } else {
+                    HuntPhase::Cooldown(remaining)
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn apply_server_verdicts(
+    mut server_events: EventReader<ServerEvent>,
+    mut resolution: ResMut<ResolutionState>,
+    mut menu: ResMut<MenuState>,
+    mut journal: ResMut<JournalState>,
+    mut flow: ResMut<MenuFlowState>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::RoundEnded(summary) => {
+                resolution.outcome = Some(summary.outcome);
+                resolution.shown = true;
+                menu.open = true;
+                journal.open = false;
+                flow.screen = MenuScreen::Resolution;
+            }
+            ServerMessage::ClaimRejected(rejection) => {
+                warn!("server rejected claim: {:?}", rejection);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn sync_profile(
+    config: Res<NetworkConfig>,
+    connection: Res<ConnectionState>,
+    mut profile: ResMut<ProfileState>,
+    mut server_events: EventReader<ServerEvent>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        if let ServerMessage::Profile(received) = message {
+            profile.profile = Some(received.clone());
+        }
+    }
+    if profile.requested && connection.status == ConnectionStatus::Connected {
+        profile.requested = false;
+        outgoing.send(OutgoingMessage(ClientMessage::RequestProfile {
+            display_name: config.display_name.clone(),
+        }));
+    }
+}
+
+// Runs before round events are handled so a matched player's spawn lands on
+// the role the server picked for them.
+pub fn sync_quick_play(
+    time: Res<Time>,
+    config: Res<NetworkConfig>,
+    mut connection: ResMut<ConnectionState>,
+    house_selection: Option<Res<HouseLayoutSelection>>,
+    mut quick_play: ResMut<QuickPlayState>,
+    mut role: ResMut<RoleState>,
+    mut server_events: EventReader<ServerEvent>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    let elapsed_ms = (time.delta_seconds() * 1000.0) as u64;
+    if let Some(remaining) = quick_play.countdown_ms {
+        quick_play.countdown_ms = Some(remaining.saturating_sub(elapsed_ms));
+    }
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::QueueStatus(status) if quick_play.searching => {
+                quick_play.status = Some(*status);
+            }
+            ServerMessage::RoomJoined { .. } if quick_play.searching => {
+                quick_play.searching = false;
+                quick_play.matched = true;
+                quick_play.status = None;
+            }
+            ServerMessage::LobbyRejected(_) if quick_play.searching => {
+                quick_play.searching = false;
+                quick_play.status = None;
+            }
+            ServerMessage::RoundCountdown { starts_in_ms } if quick_play.matched => {
+                quick_play.countdown_ms = *starts_in_ms;
+            }
+            ServerMessage::RoundStarted { .. } if quick_play.matched => {
+                quick_play.countdown_ms = None;
+                if let Some(own) = connection.own_role() {
+                    role.current = own;
+                }
+            }
+            ServerMessage::RoundEnded(_) => quick_play.matched = false,
+            _ => {}
+        }
+    }
+    if connection.status != ConnectionStatus::Connected {
+        return;
+    }
+    if quick_play.requested {
+        quick_play.requested = false;
+        quick_play.searching = true;
+        quick_play.matched = false;
+        quick_play.status = None;
+        quick_play.countdown_ms = None;
+        // The server drops us from any room we were in.
+        connection.room = None;
+        outgoing.send(OutgoingMessage(ClientMessage::QuickPlay {
+            display_name: config.display_name.clone(),
+            role: quick_play.preference,
+            layout: house_selection.map_or(HouseLayoutKind::TwoRoom, |selection| {
+                selection.selected_kind
+            }),
+        }));
+    }
+    if quick_play.cancel_requested {
+        quick_play.cancel_requested = false;
+        if quick_play.searching {
+            quick_play.searching = false;
+            quick_play.status = None;
+            outgoing.send(OutgoingMessage(ClientMessage::LeaveQueue));
+        }
+    }
+}
+
+pub fn sync_chat(
+    connection: Res<ConnectionState>,
+    mut chat: ResMut<ChatState>,
+    mut server_events: EventReader<ServerEvent>,
+    mut outgoing: EventWriter<OutgoingMessage>,
+) {
+    for ServerEvent(message) in server_events.read() {
+        match message {
+            ServerMessage::Chat(received) => chat.push(ChatEntry::Message(received.clone())),
+            ServerMessage::ChatRejected(rejection) => chat.push(ChatEntry::Rejected(*rejection)),
+            _ => {}
+        }
+    }
+    if chat.outbox.is_empty() || connection.status != ConnectionStatus::Connected {
+        return;
+    }
+    for (channel, text) in std::mem::take(&mut chat.outbox) {
+        outgoing.send(OutgoingMessage(ClientMessage::Chat { channel, text }));
+    }
+}
+
+pub fn forward_outgoing_messages(
+    channels: Res<NetworkChannels>,
+    mut outgoing: EventReader<OutgoingMessage>,
+) {
+    for OutgoingMessage(message) in outgoing.read() {
+        let _ = channels.tx.send(message.clone());
+    }
+}
+
+#[cfg(test)]
+#[path = "systems_tests.rs"]
+mod systems_tests;
@@ -0,0 +1,518 @@
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender};
+use shared::prelude::{
+    ChatChannel, ChatMessage, ChatRejection, ClientMessage, EvidenceEvent, EvidenceKind, GhostType,
+    HouseLayoutKind, LobbyRejection, PlayerProfile, QueueStatus, Role, RoomMember, RoomState,
+    ServerMessage, UpdateRequired, VersionMismatch, VersionRange, PROTOCOL_VERSION,
+};
+
+use crate::core::{
+    ChatEntry, ChatState, GhostTypeState, MenuState, ProfileState, QuickPlayState, RoleState,
+    SessionState,
+};
+use crate::gameplay::evidence::EvidenceTuning;
+use crate::gameplay::exorcism::BansheeOrder;
+use crate::gameplay::investigator::tools::{EquipmentState, EvidenceState};
+use crate::gameplay::map::HouseLayoutSelection;
+use crate::net::connection::{NetworkChannels, NetworkEvent};
+use crate::net::systems::{
+    auto_join_room, forward_outgoing_messages, receive_network_events, sync_banshee_order,
+    sync_chat, sync_lobby_choices, sync_profile, sync_quick_play, sync_team_evidence,
+    sync_tool_readings, LobbyChoices, PendingClaims,
+};
+use crate::net::{ConnectionState, ConnectionStatus, NetworkConfig, OutgoingMessage, ServerEvent};
+
+fn network_app(config: NetworkConfig) -> (App, Sender<NetworkEvent>, Receiver<ClientMessage>) {
+    let (outgoing_tx, outgoing_rx) = crossbeam_channel::unbounded();
+    let (events_tx, events_rx) = crossbeam_channel::unbounded();
+    let mut app = App::new();
+    app.insert_resource(NetworkChannels {
+        tx: outgoing_tx,
+        rx: events_rx,
+    })
+    .insert_resource(config)
+    .init_resource::<ConnectionState>()
+    .init_resource::<LobbyChoices>()
+    .insert_resource(MenuState {
+        open: true,
+        selected_role: Role::Ghost,
+    })
+    .insert_resource(GhostTypeState {
+        selected: GhostType::Spirit,
+        active: GhostType::Spirit,
+    })
+    .insert_resource(SessionState { started: false })
+    .insert_resource(HouseLayoutSelection::default())
+    .add_event::<ServerEvent>()
+    .add_event::<OutgoingMessage>()
+    .add_systems(
+        Update,
+        (
+            receive_network_events,
+            auto_join_room,
+            sync_lobby_choices,
+            forward_outgoing_messages,
+        )
+            .chain(),
+    );
+    (app, events_tx, outgoing_rx)
+}
+
+fn room_state() -> RoomState {
+    RoomState {
+        code: "ABCD".to_string(),
+        name: "Haunted".to_string(),
+        layout: HouseLayoutKind::TwoRoom,
+        members: Vec::new(),
+        in_round: false,
+    }
+}
+
+fn welcome(room: Option<RoomState>) -> NetworkEvent {
+    NetworkEvent::Message(ServerMessage::Welcome {
+        protocol_version: shared::prelude::PROTOCOL_VERSION,
+        resume_token: "token".to_string(),
+        heartbeat_ms: 5000,
+        player_id: room.as_ref().map(|_| 3),
+        room,
+        codec: shared::prelude::WireCodec::Binary,
+    })
+}
+
+#[test]
+fn status_and_rejections_update_connection_state() {
+    let (mut app, events, _) = network_app(NetworkConfig::default());
+    events
+        .send(NetworkEvent::Status(ConnectionStatus::Connected))
+        .unwrap();
+    events
+        .send(NetworkEvent::Message(ServerMessage::LobbyRejected(
+            LobbyRejection::RoomFull,
+        )))
+        .unwrap();
+    app.update();
+
+    let connection = app.world().resource::<ConnectionState>();
+    assert_eq!(connection.status, ConnectionStatus::Connected);
+    assert_eq!(connection.last_rejection, Some(LobbyRejection::RoomFull));
+}
+
+#[test]
+fn welcome_auto_joins_the_configured_room() {
+    let config = NetworkConfig {
+        room_code: Some("abcd".to_string()),
+        display_name: "Casey".to_string(),
+        ..NetworkConfig::default()
+    };
+    let (mut app, events, outgoing) = network_app(config);
+    events.send(welcome(None)).unwrap();
+    app.update();
+
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(
+        sent.as_slice(),
+        [ClientMessage::JoinLobby { display_name, room_code }]
+            if display_name == "Casey" && room_code == "abcd"
+    ));
+}
+
+#[test]
+fn round_in_progress_falls_back_to_spectating() {
+    let config = NetworkConfig {
+        room_code: Some("abcd".to_string()),
+        display_name: "Casey".to_string(),
+        ..NetworkConfig::default()
+    };
+    let (mut app, events, outgoing) = network_app(config);
+    events.send(welcome(None)).unwrap();
+    events
+        .send(NetworkEvent::Message(ServerMessage::LobbyRejected(
+            LobbyRejection::RoundInProgress,
+        )))
+        .unwrap();
+    app.update();
+
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(
+        sent.as_slice(),
+        [
+            ClientMessage::JoinLobby { .. },
+            ClientMessage::SpectateRoom { display_name, room_code },
+        ] if display_name == "Casey" && room_code == "abcd"
+    ));
+}
+
+#[test]
+fn server_assigned_spectators_keep_their_role() {
+    let (mut app, events, outgoing) = network_app(NetworkConfig::default());
+    let room = RoomState {
+        members: vec![RoomMember {
+            id: 3,
+            display_name: "Casey".to_string(),
+            role: Some(Role::Spectator),
+        }],
+        in_round: true,
+        ..room_state()
+    };
+    events.send(welcome(Some(room))).unwrap();
+    app.update();
+
+    assert_eq!(outgoing.try_iter().count(), 0);
+    let menu = app.world().resource::<MenuState>();
+    assert_eq!(menu.selected_role, Role::Spectator);
+}
+
+#[test]
+fn lobby_choices_are_sent_once_inside_a_room() {
+    let (mut app, events, outgoing) = network_app(NetworkConfig::default());
+    app.update();
+    assert_eq!(outgoing.try_iter().count(), 0);
+
+    events.send(welcome(Some(room_state()))).unwrap();
+    app.update();
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(sent
+        .iter()
+        .any(|message| matches!(message, ClientMessage::ChooseRole { role: Role::Ghost })));
+    assert!(sent
+        .iter()
+        .any(|message| matches!(message, ClientMessage::ChooseLayout { .. })));
+
+    app.update();
+    assert_eq!(outgoing.try_iter().count(), 0);
+
+    app.world_mut().resource_mut::<SessionState>().started = true;
+    app.update();
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(sent.as_slice(), [ClientMessage::StartRound]));
+}
+
+#[test]
+fn shutdown_notice_is_kept_until_the_next_welcome() {
+    let (mut app, events, _) = network_app(NetworkConfig::default());
+    events
+        .send(NetworkEvent::Message(ServerMessage::ShuttingDown {
+            remaining_ms: 120_000,
+        }))
+        .unwrap();
+    app.update();
+    assert!(
+        app.world()
+            .resource::<ConnectionState>()
+            .server_shutting_down
+    );
+
+    events.send(welcome(None)).unwrap();
+    app.update();
+    assert!(
+        !app.world()
+            .resource::<ConnectionState>()
+            .server_shutting_down
+    );
+}
+
+#[test]
+fn protocol_mismatch_records_the_server_version() {
+    let (mut app, events, _) = network_app(NetworkConfig::default());
+    events
+        .send(NetworkEvent::Message(ServerMessage::ProtocolMismatch(
+            VersionMismatch {
+                server: VersionRange::exact(PROTOCOL_VERSION + 1),
+                client: VersionRange::SUPPORTED,
+                update: UpdateRequired::Client,
+            },
+        )))
+        .unwrap();
+    events
+        .send(NetworkEvent::Status(ConnectionStatus::Incompatible))
+        .unwrap();
+    app.update();
+    let connection = app.world().resource::<ConnectionState>();
+    assert_eq!(connection.status, ConnectionStatus::Incompatible);
+    let mismatch = connection.protocol_mismatch.expect("mismatch kept");
+    assert_eq!(mismatch.server.max, PROTOCOL_VERSION + 1);
+    assert_eq!(mismatch.update, UpdateRequired::Client);
+}
+
+#[test]
+fn profile_requests_wait_for_a_connection() {
+    let (mut app, events, outgoing) = network_app(NetworkConfig {
+        display_name: "Ray".to_string(),
+        ..NetworkConfig::default()
+    });
+    app.init_resource::<ProfileState>().add_systems(
+        Update,
+        sync_profile
+            .after(receive_network_events)
+            .before(forward_outgoing_messages),
+    );
+    app.world_mut().resource_mut::<ProfileState>().requested = true;
+    app.update();
+    assert_eq!(outgoing.try_iter().count(), 0);
+
+    events
+        .send(NetworkEvent::Status(ConnectionStatus::Connected))
+        .unwrap();
+    app.update();
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(
+        sent.as_slice(),
+        [ClientMessage::RequestProfile { display_name }] if display_name == "Ray"
+    ));
+
+    events
+        .send(NetworkEvent::Message(ServerMessage::Profile(
+            PlayerProfile::new("Ray"),
+        )))
+        .unwrap();
+    app.update();
+    let profile = app.world().resource::<ProfileState>();
+    assert!(!profile.requested);
+    assert_eq!(
+        profile
+            .profile
+            .as_ref()
+            .map(|profile| profile.display_name.as_str()),
+        Some("Ray")
+    );
+}
+
+fn quick_play_app() -> (App, Sender<NetworkEvent>, Receiver<ClientMessage>) {
+    let (mut app, events, outgoing) = network_app(NetworkConfig {
+        display_name: "Ray".to_string(),
+        ..NetworkConfig::default()
+    });
+    app.init_resource::<QuickPlayState>()
+        .insert_resource(RoleState {
+            current: Role::Investigator,
+        })
+        .init_resource::<Time>()
+        .add_systems(
+            Update,
+            sync_quick_play
+                .after(receive_network_events)
+                .before(sync_lobby_choices),
+        );
+    events
+        .send(NetworkEvent::Status(ConnectionStatus::Connected))
+        .unwrap();
+    (app, events, outgoing)
+}
+
+#[test]
+fn quick_play_enters_and_leaves_the_queue() {
+    let (mut app, events, outgoing) = quick_play_app();
+    {
+        let mut quick_play = app.world_mut().resource_mut::<QuickPlayState>();
+        quick_play.preference = Some(Role::Ghost);
+        quick_play.requested = true;
+    }
+    app.update();
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(
+        sent.as_slice(),
+        [ClientMessage::QuickPlay {
+            display_name,
+            role: Some(Role::Ghost),
+            layout: HouseLayoutKind::TwoRoom,
+        }] if display_name == "Ray"
+    ));
+
+    let status = QueueStatus {
+        position: 1,
+        waiting: 1,
+        estimated_wait_ms: None,
+    };
+    events
+        .send(NetworkEvent::Message(ServerMessage::QueueStatus(status)))
+        .unwrap();
+    app.update();
+    let quick_play = app.world().resource::<QuickPlayState>();
+    assert!(quick_play.searching);
+    assert_eq!(quick_play.status, Some(status));
+
+    app.world_mut()
+        .resource_mut::<QuickPlayState>()
+        .cancel_requested = true;
+    app.update();
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(sent.as_slice(), [ClientMessage::LeaveQueue]));
+    assert!(!app.world().resource::<QuickPlayState>().searching);
+}
+
+#[test]
+fn matched_players_keep_the_server_role_and_skip_start_round() {
+    let (mut app, events, outgoing) = quick_play_app();
+    app.world_mut().resource_mut::<QuickPlayState>().requested = true;
+    app.update();
+    outgoing.try_iter().for_each(drop);
+
+    let room = RoomState {
+        members: vec![RoomMember {
+            id: 3,
+            display_name: "Ray".to_string(),
+            role: Some(Role::Investigator),
+        }],
+        ..room_state()
+    };
+    for message in [
+        ServerMessage::RoomJoined {
+            player_id: 3,
+            room_code: "ABCD".to_string(),
+        },
+        ServerMessage::RoomUpdate(room),
+        ServerMessage::RoundCountdown {
+            starts_in_ms: Some(10_000),
+        },
+    ] {
+        events.send(NetworkEvent::Message(message)).unwrap();
+    }
+    app.update();
+    let quick_play = app.world().resource::<QuickPlayState>();
+    assert!(quick_play.matched);
+    assert!(quick_play.countdown_ms.is_some());
+    assert_eq!(
+        app.world().resource::<MenuState>().selected_role,
+        Role::Investigator
+    );
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(!sent
+        .iter()
+        .any(|message| matches!(message, ClientMessage::ChooseRole { .. })));
+
+    events
+        .send(NetworkEvent::Message(ServerMessage::RoundStarted {
+            layout: HouseLayoutKind::TwoRoom,
+            spawn: Vec3::ZERO,
+            banshee_order: vec![2, 0, 1],
+        }))
+        .unwrap();
+    app.world_mut().resource_mut::<SessionState>().started = true;
+    app.update();
+    assert_eq!(
+        app.world().resource::<RoleState>().current,
+        Role::Investigator
+    );
+    assert!(!outgoing
+        .try_iter()
+        .any(|message| matches!(message, ClientMessage::StartRound)));
+}
+
+#[test]
+fn chat_is_sent_when_connected_and_received_into_the_log() {
+    let (mut app, events, outgoing) = network_app(NetworkConfig::default());
+    app.init_resource::<ChatState>().add_systems(
+        Update,
+        sync_chat
+            .after(receive_network_events)
+            .before(forward_outgoing_messages),
+    );
+    app.world_mut()
+        .resource_mut::<ChatState>()
+        .outbox
+        .push((ChatChannel::Lobby, "ready?".to_string()));
+    app.update();
+    assert_eq!(outgoing.try_iter().count(), 0);
+
+    events
+        .send(NetworkEvent::Status(ConnectionStatus::Connected))
+        .unwrap();
+    app.update();
+    let sent: Vec<_> = outgoing.try_iter().collect();
+    assert!(matches!(
+        sent.as_slice(),
+        [ClientMessage::Chat {
+            channel: ChatChannel::Lobby,
+            text,
+        }] if text == "ready?"
+    ));
+
+    let message = ChatMessage {
+        channel: ChatChannel::Team,
+        from: 4,
+        display_name: "Egon".to_string(),
+        text: "EMF 5".to_string(),
+    };
+    for event in [
+        ServerMessage::Chat(message.clone()),
+        ServerMessage::ChatRejected(ChatRejection::ChannelClosed),
+    ] {
+        events.send(NetworkEvent::Message(event)).unwrap();
+    }
+    app.update();
+    let chat = app.world().resource::<ChatState>();
+    assert_eq!(
+        chat.log,
+        vec![
+            ChatEntry::Message(message),
+            ChatEntry::Rejected(ChatRejection::ChannelClosed),
+        ]
+    );
+}
+
+#[test]
+fn round_start_brings_the_servers_banshee_order() {
+    let (mut app, events, _outgoing) = network_app(NetworkConfig::default());
+    app.init_resource::<BansheeOrder>()
+        .add_systems(Update, sync_banshee_order.after(receive_network_events));
+
+    events
+        .send(NetworkEvent::Message(ServerMessage::RoundStarted {
+            layout: HouseLayoutKind::TwoRoom,
+            spawn: Vec3::ZERO,
+            banshee_order: vec![1, 2, 0],
+        }))
+        .unwrap();
+    app.update();
+
+    assert_eq!(app.world().resource::<BansheeOrder>().0, vec![1, 2, 0]);
+}
+
+#[test]
+fn teammate_evidence_fills_the_journal_without_a_second_claim() {
+    let (mut app, events, _outgoing) = network_app(NetworkConfig::default());
+    app.init_resource::<EvidenceState>()
+        .init_resource::<PendingClaims>()
+        .add_systems(Update, sync_team_evidence.after(receive_network_events));
+
+    events
+        .send(NetworkEvent::Message(ServerMessage::Evidence(
+            EvidenceEvent::Collected(EvidenceKind::SpiritboxResponse),
+        )))
+        .unwrap();
+    app.update();
+
+    let evidence = app.world().resource::<EvidenceState>();
+    assert!(evidence.spiritbox_response);
+    assert!(!evidence.emf_five);
+    let claims = app.world().resource::<PendingClaims>();
+    assert!(claims.spiritbox_response);
+    assert!(!claims.emf_five);
+}
+
+#[test]
+fn tool_readings_follow_the_server() {
+    let (mut app, events, _outgoing) = network_app(NetworkConfig::default());
+    app.init_resource::<EvidenceState>()
+        .init_resource::<EquipmentState>()
+        .init_resource::<EvidenceTuning>()
+        .add_systems(Update, sync_tool_readings.after(receive_network_events));
+
+    for event in [
+        EvidenceEvent::EmfLevel { level: 5 },
+        EvidenceEvent::SpiritboxReply {
+            message: "Behind you...".to_string(),
+            evidence: true,
+        },
+    ] {
+        events
+            .send(NetworkEvent::Message(ServerMessage::Evidence(event)))
+            .unwrap();
+    }
+    app.update();
+
+    let equipment = app.world().resource::<EquipmentState>();
+    assert_eq!(equipment.emf_reading, Some(5));
+    assert_eq!(equipment.spiritbox_message, "Behind you...");
+    assert!(app.world().resource::<EvidenceState>().spiritbox_response);
+}
@@ -1,19 +1,29 @@
 use crate::prelude::*;
 
-use crate::core::{JournalState, MenuState, RoleState, SessionState};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
+use shared::prelude::{ChatChannel, ChatRejection, HuntPhase, RoomState, MAX_CHAT_LEN};
+
+use crate::core::{ChatEntry, ChatState, JournalState, MenuState, RoleState, SessionState};
 use crate::gameplay::exorcism::tables::{puzzle_name, ExorcismTables};
 use crate::gameplay::exorcism::{
     BansheeSequence, ExorcismState, ExorcismStatus, InvestigationState,
 };
+use crate::gameplay::ghost::GhostHunt;
 use crate::gameplay::investigator::tools::{EquipmentState, EvidenceState};
+use crate::gameplay::spectator::{SpectatorFollow, SpectatorState};
+use crate::net::ConnectionState;
+use crate::ui::lobby::ghost_type_name;
 use crate::ui::{
-    EmfText, GhostAbilityText, GhostHudRoot, HudRoot, JournalConfirmButton, JournalConfirmText,
-    JournalEmfText, JournalGuessText, JournalSection, JournalSelectBansheeButton,
-    JournalSelectOnryoButton, JournalSelectSpiritButton, JournalSpiritText, ObjectiveBodyText,
-    ObjectiveTitleText, PuzzleDetailText, PuzzleStatusText, PuzzleTitleText, SpiritboxText,
-    ToolText,
+    ChatInputText, ChatLogText, ChatRoot, EmfText, GhostAbilityText, GhostHudRoot, HudRoot,
+    JournalConfirmButton, JournalConfirmText, JournalEmfText, JournalGuessText, JournalSection,
+    JournalSelectBansheeButton, JournalSelectOnryoButton, JournalSelectSpiritButton,
+    JournalSpiritText, ObjectiveBodyText, ObjectiveTitleText, PuzzleDetailText, PuzzleStatusText,
+    PuzzleTitleText, SpectatorHudRoot, SpectatorHudText, SpiritboxText, ToolText,
 };
 
+const CHAT_VISIBLE_LINES: usize = 6;
+
 pub fn setup_hud(mut commands: Commands) {
     let panel_color = BackgroundColor(Color::srgba(0.05, 0.08, 0.15, 0.75));
     commands
@@ -364,6 +374,91 @@ pub fn setup_hud(mut commands: Commands) {
                 GhostAbilityText,
             ));
         });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(24.0),
+                    right: Val::Px(24.0),
+                    width: Val::Px(280.0),
+                    padding: UiRect::all(Val::Px(14.0)),
+                    row_gap: Val::Px(6.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: panel_color,
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            SpectatorHudRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "SPECTATING",
+                TextStyle {
+                    font_size: 14.0,
+                    color: Color::srgb(0.65, 0.7, 1.0),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 13.0,
+                        color: Color::srgb(0.85, 0.9, 1.0),
+                        ..default()
+                    },
+                ),
+                SpectatorHudText,
+            ));
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(24.0),
+                    left: Val::Px(24.0),
+                    width: Val::Px(360.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    row_gap: Val::Px(6.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: panel_color,
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            ChatRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 13.0,
+                        color: Color::srgb(0.85, 0.9, 1.0),
+                        ..default()
+                    },
+                ),
+                ChatLogText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 13.0,
+                        color: Color::srgb(0.65, 0.7, 1.0),
+                        ..default()
+                    },
+                ),
+                ChatInputText,
+            ));
+        });
 }
 
 pub fn sync_hud_visibility(
@@ -372,7 +467,7 @@ pub fn sync_hud_visibility(
     mut root: Query<&mut Visibility, With<HudRoot>>,
 ) {
     let mut visibility = root.single_mut();
-    *visibility = if menu.open || role.current == Role::Ghost {
+    *visibility = if menu.open || role.current != Role::Investigator {
         Visibility::Hidden
     } else {
         Visibility::Visible
@@ -392,6 +487,95 @@ pub fn sync_ghost_hud_visibility(
     };
 }
 
+pub fn sync_ghost_ability_text(
+    hunt: Res<GhostHunt>,
+    mut texts: Query<&mut Text, With<GhostAbilityText>>,
+) {
+    if !hunt.is_changed() {
+        return;
+    }
+    let summary = ghost_ability_summary(hunt.phase);
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = summary.clone();
+    }
+}
+
+pub fn ghost_ability_summary(phase: HuntPhase) -> String {
+    let hunt = match phase {
+        HuntPhase::Hunting(_) => format!("HUNTING - {:.0}s left", phase.remaining().ceil()),
+        _ if phase.is_ready() => "H: Hunt (ready)".to_string(),
+        _ => format!("H: Hunt in {:.0}s", phase.remaining().ceil()),
+    };
+    format!("L: Toggle room lights\n{}", hunt)
+}
+
+pub fn sync_spectator_hud_visibility(
+    menu: Res<MenuState>,
+    role: Res<RoleState>,
+    mut root: Query<&mut Visibility, With<SpectatorHudRoot>>,
+) {
+    let Ok(mut visibility) = root.get_single_mut() else {
+        return;
+    };
+    *visibility = if menu.open || role.current != Role::Spectator {
+        Visibility::Hidden
+    } else {
+        Visibility::Visible
+    };
+}
+
+pub fn sync_spectator_hud_text(
+    spectator: Res<SpectatorState>,
+    connection: Res<ConnectionState>,
+    mut texts: Query<&mut Text, With<SpectatorHudText>>,
+) {
+    if !spectator.is_changed() && !connection.is_changed() {
+        return;
+    }
+    let summary = spectator_summary(&spectator, connection.room.as_ref());
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = summary.clone();
+    }
+}
+
+pub fn spectator_summary(spectator: &SpectatorState, room: Option<&RoomState>) -> String {
+    let ghost = match spectator.ghost_type {
+        Some(ghost_type) => ghost_type_name(ghost_type),
+        None => "waiting for the round",
+    };
+    let camera = match spectator.follow {
+        SpectatorFollow::Free => "Free camera (WASD, Space/C to rise and sink)".to_string(),
+        SpectatorFollow::Player(id) => {
+            let member = room.and_then(|room| room.members.iter().find(|member| member.id == id));
+            match member {
+                Some(member) => format!(
+                    "Following {} ({})",
+                    member.display_name,
+                    role_name(member.role)
+                ),
+                None => format!("Following player {}", id),
+            }
+        }
+    };
+    let summary = format!(
+        "Ghost: {}\n{}\nTab/E: next player  Q: previous  F: free camera",
+        ghost, camera
+    );
+    if spectator.caught {
+        return format!("The ghost caught you.\n{}", summary);
+    }
+    summary
+}
+
+fn role_name(role: Option<Role>) -> &'static str {
+    match role {
+        Some(Role::Ghost) => "Ghost",
+        Some(Role::Investigator) => "Investigator",
+        Some(Role::Spectator) => "Spectator",
+        None => "no role",
+    }
+}
+
 pub fn handle_journal_toggle(
     keys: Res<ButtonInput<KeyCode>>,
     menu: Res<MenuState>,
@@ -406,6 +590,157 @@ pub fn handle_journal_toggle(
     }
 }
 
+// Typing must not walk the player around or fire tool keys, so while the chat
+// is open every gameplay system sees an idle keyboard.
+pub fn release_keys_while_chatting(chat: Res<ChatState>, mut keys: ResMut<ButtonInput<KeyCode>>) {
+    if chat.open {
+        keys.reset_all();
+    }
+}
+
+pub fn handle_chat_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    menu: Res<MenuState>,
+    journal: Res<JournalState>,
+    connection: Res<ConnectionState>,
+    mut typed: EventReader<KeyboardInput>,
+    mut chat: ResMut<ChatState>,
+) {
+    if !chat.open {
+        typed.clear();
+        if !menu.open && !journal.open && keys.just_pressed(KeyCode::Enter) {
+            chat.open = true;
+        }
+        return;
+    }
+    if menu.open || journal.open {
+        chat.open = false;
+        return;
+    }
+    for event in typed.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match &event.logical_key {
+            Key::Enter => {
+                let text = std::mem::take(&mut chat.draft);
+                if let Some(channel) = chat_channel(&connection) {
+                    if !text.trim().is_empty() {
+                        chat.outbox.push((channel, text));
+                    }
+                }
+                chat.open = false;
+            }
+            Key::Escape => {
+                chat.draft.clear();
+                chat.open = false;
+            }
+            Key::Backspace => {
+                chat.draft.pop();
+            }
+            Key::Space => push_typed(&mut chat.draft, " "),
+            Key::Character(typed) => push_typed(&mut chat.draft, typed),
+            _ => {}
+        }
+        if !chat.open {
+            break;
+        }
+    }
+}
+
+fn push_typed(draft: &mut String, typed: &str) {
+    for character in typed.chars().filter(|character| !character.is_control()) {
+        if draft.chars().count() >= MAX_CHAT_LEN {
+            return;
+        }
+        draft.push(character);
+    }
+}
+
+pub fn chat_channel(connection: &ConnectionState) -> Option<ChatChannel> {
+    let room = connection.room.as_ref()?;
+    ChatChannel::for_sender(connection.own_role(), room.in_round)
+}
+
+pub fn sync_chat_visibility(
+    menu: Res<MenuState>,
+    chat: Res<ChatState>,
+    mut root: Query<&mut Visibility, With<ChatRoot>>,
+) {
+    let Ok(mut visibility) = root.get_single_mut() else {
+        return;
+    };
+    *visibility = if !menu.open && (chat.open || !chat.log.is_empty()) {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+}
+
+pub fn sync_chat_text(
+    chat: Res<ChatState>,
+    connection: Res<ConnectionState>,
+    mut texts: ParamSet<(
+        Query<&mut Text, With<ChatLogText>>,
+        Query<&mut Text, With<ChatInputText>>,
+    )>,
+) {
+    if !chat.is_changed() && !connection.is_changed() {
+        return;
+    }
+    let log = chat_log(&chat.log, CHAT_VISIBLE_LINES);
+    for mut text in texts.p0().iter_mut() {
+        text.sections[0].value = log.clone();
+    }
+    let prompt = chat_prompt(&chat, chat_channel(&connection));
+    for mut text in texts.p1().iter_mut() {
+        text.sections[0].value = prompt.clone();
+    }
+}
+
+pub fn chat_log(log: &[ChatEntry], lines: usize) -> String {
+    log[log.len().saturating_sub(lines)..]
+        .iter()
+        .map(|entry| match entry {
+            ChatEntry::Message(message) => format!(
+                "[{}] {}: {}",
+                channel_name(message.channel),
+                message.display_name,
+                message.text
+            ),
+            ChatEntry::Rejected(rejection) => chat_rejection_text(*rejection).to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn chat_prompt(chat: &ChatState, channel: Option<ChatChannel>) -> String {
+    if !chat.open {
+        return "Enter: chat".to_string();
+    }
+    match channel {
+        Some(channel) => format!("[{}] > {}_", channel_name(channel), chat.draft),
+        None => "Chat is read-only right now (Esc to close)".to_string(),
+    }
+}
+
+fn channel_name(channel: ChatChannel) -> &'static str {
+    match channel {
+        ChatChannel::Lobby => "Lobby",
+        ChatChannel::Team => "Team",
+        ChatChannel::Overheard => "Overheard",
+    }
+}
+
+fn chat_rejection_text(rejection: ChatRejection) -> &'static str {
+    match rejection {
+        ChatRejection::NotInRoom => "Join a room to chat.",
+        ChatRejection::Empty => "Message was empty.",
+        ChatRejection::TooLong => "Message was too long.",
+        ChatRejection::ChannelClosed => "That channel is closed to you right now.",
+    }
+}
+
 pub fn sync_journal_panel_visibility(
     menu: Res<MenuState>,
     role: Res<RoleState>,
@@ -597,6 +932,7 @@ pub fn sync_hud_text(
     let active_label = match equipment.active {
         crate::core::Equipment::Emf => "EMF Reader",
         crate::core::Equipment::Spiritbox => "Spiritbox",
+        crate::core::Equipment::Radio => "Radio",
     };
 
     let emf_value =
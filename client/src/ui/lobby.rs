@@ -1,22 +1,31 @@
 use crate::prelude::*;
 
+use shared::prelude::{PlayerProfile, ServerMessage};
+
+use crate::core::health::update_notice;
 use crate::core::{
-    GhostTypeState, JournalState, MenuFlowState, MenuScreen, MenuState, ResolutionState, Role,
-    RoleState, RoleYaw, RoundOutcome, SessionState,
+    GhostTypeState, JournalState, MenuFlowState, MenuScreen, MenuState, ProfileState,
+    QuickPlayState, ResolutionState, Role, RoleState, RoleYaw, RoundOutcome, SessionState,
 };
 use crate::gameplay::exorcism::{ExorcismState, ExorcismStatus, InvestigationState, PuzzleSpawned};
-use crate::gameplay::ghost::GhostState;
+use crate::gameplay::ghost::{GhostHunt, GhostState};
+use crate::gameplay::investigator::systems::InvestigatorTeam;
 use crate::gameplay::investigator::tools::EvidenceState;
-use crate::gameplay::investigator::Player;
+use crate::gameplay::investigator::{Player, TeamSlot};
 use crate::gameplay::map::components::CollisionWorld;
-use crate::gameplay::map::systems::random_round_start_positions;
+use crate::gameplay::map::systems::random_team_start_positions;
 use crate::gameplay::map::{HouseLayout, HouseLayoutKind, HouseLayoutSelection};
+use crate::gameplay::spectator::SpectatorState;
+use crate::net::prediction::PredictionState;
+use crate::net::{ConnectionState, ConnectionStatus, ServerEvent};
 use crate::ui::{
     BansheeGhostButton, BeginHauntButton, BeginInvestigationButton, ExitButton, GhostDetailRoot,
     GhostSelectButton, InvestigatorDetailRoot, InvestigatorSelectButton, OnryoGhostButton,
+    ProfileBackButton, ProfileBodyText, ProfileButton, ProfileRoot, QuickPlayButton,
+    QuickPlayButtonText, QuickPlayRoleButton, QuickPlayRoleText, QuickPlayStatusText,
     ResolutionBodyText, ResolutionContinueButton, ResolutionRoot, ResolutionTitleText,
-    RoleSelectRoot, SpiritGhostButton, StartScreenButton, StartScreenRoot, ThreeRoomCountButton,
-    TwoRoomCountButton,
+    RoleSelectRoot, SpectateButton, SpiritGhostButton, StartScreenButton, StartScreenNoticeText,
+    StartScreenRoot, TeamSizeButton, TeamSizeText, ThreeRoomCountButton, TwoRoomCountButton,
 };
 
 pub fn setup_menu(mut commands: Commands) {
@@ -61,6 +70,21 @@ pub fn setup_menu(mut commands: Commands) {
                     ..default()
                 },
             ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(1.0, 0.72, 0.35),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    max_width: Val::Px(560.0),
+                    ..default()
+                }),
+                StartScreenNoticeText,
+            ));
             parent
                 .spawn((
                     ButtonBundle {
@@ -83,6 +107,98 @@ pub fn setup_menu(mut commands: Commands) {
                         },
                     ));
                 });
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(12.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|row| {
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::axes(Val::Px(18.0), Val::Px(10.0)),
+                                ..default()
+                            },
+                            background_color: button_color,
+                            ..default()
+                        },
+                        QuickPlayButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            TextBundle::from_section(
+                                "Quick Play",
+                                TextStyle {
+                                    font_size: 16.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            ),
+                            QuickPlayButtonText,
+                        ));
+                    });
+                    row.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::axes(Val::Px(18.0), Val::Px(10.0)),
+                                ..default()
+                            },
+                            background_color: button_color,
+                            ..default()
+                        },
+                        QuickPlayRoleButton,
+                    ))
+                    .with_children(|button| {
+                        button.spawn((
+                            TextBundle::from_section(
+                                "Role: Any",
+                                TextStyle {
+                                    font_size: 16.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            ),
+                            QuickPlayRoleText,
+                        ));
+                    });
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.6, 0.7, 0.85),
+                        ..default()
+                    },
+                ),
+                QuickPlayStatusText,
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(18.0), Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: button_color,
+                        ..default()
+                    },
+                    ProfileButton,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Profile",
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                });
         });
 
     commands
@@ -197,6 +313,29 @@ pub fn setup_menu(mut commands: Commands) {
                     });
                 });
 
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(18.0), Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: button_color,
+                        ..default()
+                    },
+                    SpectateButton,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Spectate",
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                });
+
             parent
                 .spawn((
                     ButtonBundle {
@@ -433,13 +572,38 @@ pub fn setup_menu(mut commands: Commands) {
                 },
             ));
             parent.spawn(TextBundle::from_section(
-                "Cosmetics and loadouts coming soon.",
+                "Play offline as a team of up to four. Tab passes control between them.",
                 TextStyle {
                     font_size: 16.0,
                     color: Color::srgb(0.7, 0.75, 0.9),
                     ..default()
                 },
             ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: button_color,
+                        ..default()
+                    },
+                    TeamSizeButton,
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        TextBundle::from_section(
+                            team_size_label(1),
+                            TextStyle {
+                                font_size: 16.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ),
+                        TeamSizeText,
+                    ));
+                });
             parent
                 .spawn((
                     ButtonBundle {
@@ -538,6 +702,76 @@ pub fn setup_menu(mut commands: Commands) {
                     ));
                 });
         });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(18.0),
+                    padding: UiRect::axes(Val::Px(32.0), Val::Px(24.0)),
+                    ..default()
+                },
+                background_color: panel,
+                ..default()
+            },
+            ProfileRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Career",
+                TextStyle {
+                    font_size: 36.0,
+                    color: Color::srgb(0.88, 0.92, 1.0),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "Loading profile...",
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::srgb(0.72, 0.78, 0.92),
+                            ..default()
+                        },
+                    ),
+                    style: Style {
+                        max_width: Val::Px(640.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ProfileBodyText,
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::axes(Val::Px(24.0), Val::Px(12.0)),
+                            margin: UiRect::top(Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: primary_button,
+                        ..default()
+                    },
+                    ProfileBackButton,
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        "Back",
+                        TextStyle {
+                            font_size: 18.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                });
+        });
 }
 
 pub fn handle_menu_toggle(
@@ -548,6 +782,10 @@ pub fn handle_menu_toggle(
     session: Res<SessionState>,
 ) {
     if keys.just_pressed(KeyCode::Escape) {
+        if menu.open && flow.screen == MenuScreen::Profile {
+            flow.screen = MenuScreen::Start;
+            return;
+        }
         if menu.open
             && matches!(
                 flow.screen,
@@ -601,12 +839,14 @@ pub fn handle_menu_interactions(
     mut control: ResMut<crate::core::CameraControl>,
     mut journal: ResMut<JournalState>,
     mut ghost: Option<ResMut<GhostState>>,
+    mut hunt: Option<ResMut<GhostHunt>>,
     layout_resources: (
         Option<ResMut<HouseLayout>>,
         Option<ResMut<CollisionWorld>>,
         Option<ResMut<HouseLayoutSelection>>,
     ),
-    mut players: Query<&mut Transform, With<Player>>,
+    mut team: Option<ResMut<InvestigatorTeam>>,
+    mut players: Query<(&mut Transform, Option<&TeamSlot>), With<Player>>,
     mut exit_events: EventWriter<AppExit>,
 ) {
     let (mut evidence, mut puzzle_spawned, mut investigation, mut resolution, mut session) =
@@ -666,11 +906,12 @@ pub fn handle_menu_interactions(
             let begin_haunt = begin_haunt_btn.is_some();
             let begin_investigation = begin_investigation_btn.is_some();
             if begin_haunt || begin_investigation {
-                let mut fresh_start_positions: Option<(Vec3, Vec3)> = None;
+                let team_size = team.as_ref().map_or(1, |team| team.size);
+                let mut fresh_start_positions: Option<(Vec<Vec3>, Vec3)> = None;
                 if let Some(ref mut selection) = house_selection {
                     selection.active_kind = selection.selected_kind;
                     let new_layout = HouseLayout::for_kind(selection.active_kind);
-                    fresh_start_positions = Some(new_layout.random_start_positions());
+                    fresh_start_positions = Some(new_layout.random_team_start_positions(team_size));
                     if let Some(ref mut collision) = collision_world {
                         **collision = new_layout.collision_world();
                     }
@@ -694,20 +935,28 @@ pub fn handle_menu_interactions(
                 *resolution = ResolutionState::default();
                 session.started = true;
                 set_default_camera(role.current, &mut control, &mut role_yaw);
-                let (investigator_spawn, ghost_spawn) = fresh_start_positions
+                let (team_starts, ghost_spawn) = fresh_start_positions
                     .or_else(|| {
                         active_house_layout
                             .as_ref()
-                            .map(|layout| layout.random_start_positions())
+                            .map(|layout| layout.random_team_start_positions(team_size))
                     })
-                    .unwrap_or_else(random_round_start_positions);
-                if let Ok(mut player_transform) = players.get_single_mut() {
-                    player_transform.translation = investigator_spawn;
-                    player_transform.rotation = Quat::IDENTITY;
+                    .unwrap_or_else(|| random_team_start_positions(team_size));
+                for (mut player_transform, slot) in &mut players {
+                    if let Some(start) = team_starts.get(slot.map_or(0, |slot| slot.0)) {
+                        player_transform.translation = *start;
+                        player_transform.rotation = Quat::IDENTITY;
+                    }
+                }
+                if let Some(ref mut team) = team {
+                    team.arrange(team_starts);
                 }
                 if let Some(ref mut ghost_state) = ghost {
                     ghost_state.position = ghost_spawn;
                 }
+                if let Some(ref mut hunt) = hunt {
+                    hunt.restart();
+                }
                 menu.open = false;
                 journal.open = false;
                 flow.screen = MenuScreen::RoleSelect;
@@ -749,10 +998,16 @@ pub fn maybe_open_resolution_screen(
     mut menu: ResMut<MenuState>,
     mut journal: ResMut<JournalState>,
     mut flow: ResMut<MenuFlowState>,
+    prediction: Option<Res<PredictionState>>,
 ) {
     if !session.started || resolution.shown || !investigation.confirmed {
         return;
     }
+    // Online the server judges the round: only its `RoundEnded` opens the
+    // results (see `apply_server_verdicts`).
+    if prediction.is_some_and(|prediction| prediction.is_active()) {
+        return;
+    }
 
     let outcome = match exorcism.state {
         ExorcismState::Complete => Some(RoundOutcome::SuccessfulExorcism),
@@ -788,6 +1043,341 @@ pub fn sync_start_screen_visibility(
     };
 }
 
+pub fn sync_start_screen_notice(
+    connection: Res<ConnectionState>,
+    mut texts: Query<&mut Text, With<StartScreenNoticeText>>,
+) {
+    if !connection.is_changed() {
+        return;
+    }
+    let notice = match (&connection.status, &connection.protocol_mismatch) {
+        (ConnectionStatus::Incompatible, Some(mismatch)) => update_notice(mismatch),
+        (ConnectionStatus::Incompatible, None) => {
+            "This game cannot talk to the server. Please update the game.".to_string()
+        }
+        _ => String::new(),
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = notice.clone();
+    }
+}
+
+// Spectating skips the loadout screens: the camera is free straight away and
+// the server's round start moves it to the house entrance.
+pub fn handle_spectate_interactions(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<SpectateButton>)>,
+    mut menu: ResMut<MenuState>,
+    mut flow: ResMut<MenuFlowState>,
+    mut role: ResMut<RoleState>,
+    mut journal: ResMut<JournalState>,
+    mut control: ResMut<CameraControl>,
+    mut role_yaw: ResMut<RoleYaw>,
+    mut spectator: ResMut<SpectatorState>,
+) {
+    if !interactions
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        return;
+    }
+    role.current = Role::Spectator;
+    menu.selected_role = Role::Spectator;
+    menu.open = false;
+    journal.open = false;
+    flow.screen = MenuScreen::RoleSelect;
+    set_default_camera(Role::Spectator, &mut control, &mut role_yaw);
+    *spectator = SpectatorState::default();
+}
+
+pub fn handle_quick_play_interactions(
+    interactions: Query<
+        (
+            &Interaction,
+            Option<&QuickPlayButton>,
+            Option<&QuickPlayRoleButton>,
+        ),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut quick_play: ResMut<QuickPlayState>,
+) {
+    for (interaction, play_btn, role_btn) in interactions.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if play_btn.is_some() {
+            if quick_play.searching {
+                quick_play.cancel_requested = true;
+            } else if !quick_play.matched {
+                quick_play.requested = true;
+            }
+        }
+        if role_btn.is_some() && !quick_play.searching && !quick_play.matched {
+            quick_play.preference = match quick_play.preference {
+                None => Some(Role::Ghost),
+                Some(Role::Ghost) => Some(Role::Investigator),
+                Some(_) => None,
+            };
+        }
+    }
+}
+
+pub fn handle_team_size_interactions(
+    interactions: Query<&Interaction, (Changed<Interaction>, With<TeamSizeButton>)>,
+    mut team: ResMut<InvestigatorTeam>,
+) {
+    for interaction in &interactions {
+        if *interaction == Interaction::Pressed {
+            team.cycle_size();
+        }
+    }
+}
+
+pub fn sync_team_size_text(
+    team: Res<InvestigatorTeam>,
+    mut texts: Query<&mut Text, With<TeamSizeText>>,
+) {
+    if !team.is_changed() {
+        return;
+    }
+    for mut text in &mut texts {
+        text.sections[0].value = team_size_label(team.size);
+    }
+}
+
+pub fn team_size_label(size: usize) -> String {
+    format!("Investigators: {size}")
+}
+
+// Quick-play rounds start on the server's countdown instead of the begin
+// buttons, so the round setup from the menu is repeated here with the
+// layout and role the server chose.
+pub fn start_matched_round(
+    mut server_events: EventReader<ServerEvent>,
+    quick_play: Res<QuickPlayState>,
+    role: Res<RoleState>,
+    mut menu: ResMut<MenuState>,
+    mut flow: ResMut<MenuFlowState>,
+    mut journal: ResMut<JournalState>,
+    mut control: ResMut<CameraControl>,
+    mut role_yaw: ResMut<RoleYaw>,
+    mut ghost_type: ResMut<GhostTypeState>,
+    round_resources: (
+        ResMut<EvidenceState>,
+        ResMut<PuzzleSpawned>,
+        ResMut<InvestigationState>,
+        ResMut<ResolutionState>,
+        ResMut<SessionState>,
+    ),
+    layout_resources: (
+        Option<ResMut<HouseLayout>>,
+        Option<ResMut<CollisionWorld>>,
+        Option<ResMut<HouseLayoutSelection>>,
+    ),
+) {
+    let (mut evidence, mut puzzle_spawned, mut investigation, mut resolution, mut session) =
+        round_resources;
+    let (mut active_house_layout, mut collision_world, mut house_selection) = layout_resources;
+
+    for ServerEvent(message) in server_events.read() {
+        let ServerMessage::RoundStarted { layout, .. } = message else {
+            continue;
+        };
+        if !quick_play.matched {
+            continue;
+        }
+        let new_layout = HouseLayout::for_kind(*layout);
+        if let Some(ref mut selection) = house_selection {
+            selection.selected_kind = *layout;
+            selection.active_kind = *layout;
+        }
+        if let Some(ref mut collision) = collision_world {
+            **collision = new_layout.collision_world();
+        }
+        if let Some(ref mut active) = active_house_layout {
+            **active = new_layout;
+        }
+
+        menu.selected_role = role.current;
+        ghost_type.active = ghost_type.selected;
+        *evidence = EvidenceState::default();
+        puzzle_spawned.0 = false;
+        investigation.guess = None;
+        investigation.confirmed = false;
+        *resolution = ResolutionState::default();
+        session.started = true;
+        set_default_camera(role.current, &mut control, &mut role_yaw);
+        menu.open = false;
+        journal.open = false;
+        flow.screen = MenuScreen::RoleSelect;
+    }
+}
+
+pub fn sync_quick_play_text(
+    quick_play: Res<QuickPlayState>,
+    mut texts: ParamSet<(
+        Query<&mut Text, With<QuickPlayButtonText>>,
+        Query<&mut Text, With<QuickPlayRoleText>>,
+        Query<&mut Text, With<QuickPlayStatusText>>,
+    )>,
+) {
+    if !quick_play.is_changed() {
+        return;
+    }
+    let button = if quick_play.searching {
+        "Cancel"
+    } else {
+        "Quick Play"
+    };
+    for mut text in texts.p0().iter_mut() {
+        text.sections[0].value = button.to_string();
+    }
+    let preference = match quick_play.preference {
+        Some(Role::Ghost) => "Role: Ghost",
+        Some(Role::Investigator) => "Role: Investigator",
+        _ => "Role: Any",
+    };
+    for mut text in texts.p1().iter_mut() {
+        text.sections[0].value = preference.to_string();
+    }
+    let status = quick_play_summary(&quick_play);
+    for mut text in texts.p2().iter_mut() {
+        text.sections[0].value = status.clone();
+    }
+}
+
+pub fn quick_play_summary(quick_play: &QuickPlayState) -> String {
+    if quick_play.matched {
+        return match quick_play.countdown_ms {
+            Some(ms) => format!("Match found. Starting in {}s", ms.div_ceil(1000)),
+            None => "Match found. Waiting for players...".to_string(),
+        };
+    }
+    if !quick_play.searching {
+        return String::new();
+    }
+    let Some(status) = quick_play.status else {
+        return "Searching...".to_string();
+    };
+    let wait = match status.estimated_wait_ms {
+        Some(ms) => format!("about {}s", ms.div_ceil(1000)),
+        None => "estimating wait".to_string(),
+    };
+    format!(
+        "In queue: #{} of {}, {}",
+        status.position, status.waiting, wait
+    )
+}
+
+pub fn handle_profile_interactions(
+    interactions: Query<
+        (
+            &Interaction,
+            Option<&ProfileButton>,
+            Option<&ProfileBackButton>,
+        ),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut flow: ResMut<MenuFlowState>,
+    mut profile: ResMut<ProfileState>,
+) {
+    for (interaction, open_btn, back_btn) in interactions.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if open_btn.is_some() {
+            profile.requested = true;
+            flow.screen = MenuScreen::Profile;
+        }
+        if back_btn.is_some() {
+            flow.screen = MenuScreen::Start;
+        }
+    }
+}
+
+pub fn sync_profile_visibility(
+    menu: Res<MenuState>,
+    flow: Res<MenuFlowState>,
+    mut root: Query<&mut Visibility, With<ProfileRoot>>,
+) {
+    let mut visibility = root.single_mut();
+    *visibility = if menu.open && flow.screen == MenuScreen::Profile {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+}
+
+pub fn sync_profile_text(
+    profile: Res<ProfileState>,
+    mut texts: Query<&mut Text, With<ProfileBodyText>>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+    let body = match &profile.profile {
+        Some(profile) => profile_summary(profile),
+        None => "Loading profile...".to_string(),
+    };
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = body.clone();
+    }
+}
+
+pub fn profile_summary(profile: &PlayerProfile) -> String {
+    if profile.games_played() == 0 {
+        return format!(
+            "{}\n\nNo rounds played yet. Finish a case to start your record.",
+            profile.display_name
+        );
+    }
+    let mut lines = vec![
+        profile.display_name.clone(),
+        String::new(),
+        format!(
+            "Rounds: {} as ghost, {} as investigator",
+            profile.games_as_ghost, profile.games_as_investigator
+        ),
+    ];
+    for (role, label, played) in [
+        (
+            Role::Investigator,
+            "As investigator",
+            profile.games_as_investigator,
+        ),
+        (Role::Ghost, "As ghost", profile.games_as_ghost),
+    ] {
+        if played == 0 {
+            continue;
+        }
+        let percent = |outcome| profile.win_rate(role, outcome) * 100.0;
+        lines.push(format!(
+            "{}  Banished: {:.0}%   Wrong ghost: {:.0}%   Ritual failed: {:.0}%   Team lost: {:.0}%",
+            label,
+            percent(RoundOutcome::SuccessfulExorcism),
+            percent(RoundOutcome::WrongGhost),
+            percent(RoundOutcome::FailedExorcism),
+            percent(RoundOutcome::InvestigatorsDead)
+        ));
+    }
+    lines.push(format!(
+        "Favourite ghost: {}",
+        profile.favourite_ghost.map_or("-", ghost_type_name)
+    ));
+    if !profile.fastest_exorcisms.is_empty() {
+        lines.push(String::new());
+        lines.push("Fastest exorcisms".to_string());
+        for record in &profile.fastest_exorcisms {
+            lines.push(format!(
+                "  {} ({}): {:.1}s",
+                ghost_type_name(record.puzzle),
+                layout_name(record.layout),
+                record.seconds
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
 pub fn sync_role_select_visibility(
     menu: Res<MenuState>,
     flow: Res<MenuFlowState>,
@@ -862,6 +1452,7 @@ pub fn sync_resolution_text(
                 Some(RoundOutcome::SuccessfulExorcism) => "Ghost Banished".to_string(),
                 Some(RoundOutcome::WrongGhost) => "Wrong Ghost".to_string(),
                 Some(RoundOutcome::FailedExorcism) => "Exorcism Failed".to_string(),
+                Some(RoundOutcome::InvestigatorsDead) => "No One Made It Out".to_string(),
                 None => "Case Closed".to_string(),
             };
         } else if body_tag.is_some() {
@@ -878,6 +1469,10 @@ pub fn sync_resolution_text(
                     "You had the right ghost, but the {} ritual broke down before completion. Reopen the case and try it again.",
                     actual_name
                 ),
+                Some(RoundOutcome::InvestigatorsDead) => format!(
+                    "The {} hunted down every investigator before the case was closed. When the lights start to flicker, break line of sight and keep your distance until the hunt ends.",
+                    actual_name
+                ),
                 None => "Return to the role select when you're ready.".to_string(),
             };
         }
@@ -1014,7 +1609,14 @@ pub fn update_cursor_lock(
     }
 }
 
-fn ghost_type_name(ghost_type: GhostType) -> &'static str {
+fn layout_name(layout: HouseLayoutKind) -> &'static str {
+    match layout {
+        HouseLayoutKind::TwoRoom => "two rooms",
+        HouseLayoutKind::ThreeRoom => "three rooms",
+    }
+}
+
+pub fn ghost_type_name(ghost_type: GhostType) -> &'static str {
     match ghost_type {
         GhostType::Spirit => "Spirit",
         GhostType::Banshee => "Banshee",
@@ -9,6 +9,9 @@ mod ui_flow_tests;
 #[derive(Component)]
 pub struct StartScreenRoot;
 
+#[derive(Component)]
+pub struct StartScreenNoticeText;
+
 #[derive(Component)]
 pub struct RoleSelectRoot;
 
@@ -21,6 +24,12 @@ pub struct InvestigatorDetailRoot;
 #[derive(Component)]
 pub struct ResolutionRoot;
 
+#[derive(Component)]
+pub struct ProfileRoot;
+
+#[derive(Component)]
+pub struct ProfileBodyText;
+
 #[derive(Component)]
 pub struct HudRoot;
 
@@ -120,27 +129,84 @@ pub struct TwoRoomCountButton;
 #[derive(Component)]
 pub struct ThreeRoomCountButton;
 
+#[derive(Component)]
+pub struct TeamSizeButton;
+
+#[derive(Component)]
+pub struct TeamSizeText;
+
 #[derive(Component)]
 pub struct ResolutionContinueButton;
 
+#[derive(Component)]
+pub struct SpectateButton;
+
+#[derive(Component)]
+pub struct SpectatorHudRoot;
+
+#[derive(Component)]
+pub struct SpectatorHudText;
+
+#[derive(Component)]
+pub struct QuickPlayButton;
+
+#[derive(Component)]
+pub struct QuickPlayButtonText;
+
+#[derive(Component)]
+pub struct QuickPlayRoleButton;
+
+#[derive(Component)]
+pub struct QuickPlayRoleText;
+
+#[derive(Component)]
+pub struct QuickPlayStatusText;
+
+#[derive(Component)]
+pub struct ChatRoot;
+
+#[derive(Component)]
+pub struct ChatLogText;
+
+#[derive(Component)]
+pub struct ChatInputText;
+
+#[derive(Component)]
+pub struct ProfileButton;
+
+#[derive(Component)]
+pub struct ProfileBackButton;
+
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, (lobby::setup_menu, hud::setup_hud))
+            .add_systems(
+                PreUpdate,
+                hud::release_keys_while_chatting.after(bevy::input::InputSystem),
+            )
             .add_systems(
                 Update,
                 (
                     lobby::handle_menu_toggle,
                     lobby::handle_menu_interactions,
                     lobby::handle_resolution_interactions,
+                    lobby::handle_spectate_interactions,
+                    lobby::handle_quick_play_interactions,
+                    lobby::handle_team_size_interactions,
+                    lobby::start_matched_round,
+                    lobby::handle_profile_interactions,
                     lobby::maybe_open_resolution_screen,
                     lobby::sync_start_screen_visibility,
+                    lobby::sync_start_screen_notice,
                     lobby::sync_role_select_visibility,
                     lobby::sync_ghost_detail_visibility,
                     lobby::sync_investigator_detail_visibility,
                     lobby::sync_resolution_visibility,
                     lobby::sync_resolution_text,
+                    lobby::sync_profile_visibility,
+                    lobby::sync_profile_text,
                 ),
             )
             .add_systems(
@@ -149,14 +215,22 @@ impl Plugin for UiPlugin {
                     lobby::sync_menu_styles,
                     lobby::sync_role_select_hover,
                     lobby::update_cursor_lock,
+                    lobby::sync_quick_play_text,
+                    lobby::sync_team_size_text,
                     hud::sync_hud_visibility,
                     hud::sync_ghost_hud_visibility,
+                    hud::sync_ghost_ability_text,
+                    hud::sync_spectator_hud_visibility,
+                    hud::sync_spectator_hud_text,
                     hud::handle_journal_toggle,
                     hud::handle_journal_interactions,
                     hud::sync_journal_panel_visibility,
                     hud::sync_journal_visibility,
                     hud::sync_journal_styles,
                     hud::sync_hud_text,
+                    hud::handle_chat_input,
+                    hud::sync_chat_visibility,
+                    hud::sync_chat_text,
                 ),
             );
     }
@@ -1,10 +1,18 @@
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::ButtonState;
 use bevy::prelude::*;
 use bevy::window::{Cursor, PrimaryWindow};
+use shared::map::{ExorcismLayout, RoomZone};
+use shared::prelude::{
+    ChatChannel, ChatMessage, QueueStatus, RoomMember, RoomState, UpdateRequired, VersionMismatch,
+    VersionRange,
+};
 
 use crate::core::CameraControl;
 use crate::core::{
-    Equipment, GhostType, GhostTypeState, JournalState, MenuFlowState, MenuScreen, MenuState,
-    ResolutionState, Role, RoleState, RoundOutcome, SessionState,
+    ChatEntry, ChatState, Equipment, GhostType, GhostTypeState, JournalState, MenuFlowState,
+    MenuScreen, MenuState, ProfileState, QuickPlayState, ResolutionState, Role, RoleState,
+    RoundOutcome, SessionState,
 };
 use crate::gameplay::evidence::EvidenceTuning;
 use crate::gameplay::exorcism::{
@@ -15,8 +23,10 @@ use crate::gameplay::ghost::GhostState;
 use crate::gameplay::investigator::tools::{
     handle_spiritbox, update_emf_reading, EquipmentState, EvidenceState,
 };
-use crate::gameplay::map::components::{Bounds, CollisionWorld, RoomZone};
+use crate::gameplay::map::components::{Bounds, CollisionWorld};
 use crate::gameplay::map::{HouseLayout, HouseLayoutKind, HouseLayoutSelection};
+use crate::gameplay::spectator::{SpectatorFollow, SpectatorState};
+use crate::net::{ConnectionState, ConnectionStatus};
 use crate::ui::hud;
 use crate::ui::*;
 
@@ -187,6 +197,7 @@ fn puzzle_title_waits_for_confirmation() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -230,6 +241,41 @@ fn start_screen_visible_when_menu_open() {
     assert_eq!(*visibility, Visibility::Visible);
 }
 
+#[test]
+fn start_screen_shows_update_notice_when_incompatible() {
+    let mut app = App::new();
+    app.add_systems(Update, crate::ui::lobby::sync_start_screen_notice);
+    app.insert_resource(ConnectionState {
+        status: ConnectionStatus::Incompatible,
+        protocol_mismatch: Some(VersionMismatch {
+            server: VersionRange { min: 3, max: 4 },
+            client: VersionRange::exact(2),
+            update: UpdateRequired::Client,
+        }),
+        ..default()
+    });
+
+    let entity = app
+        .world_mut()
+        .spawn((
+            Text::from_section("", TextStyle::default()),
+            StartScreenNoticeText,
+        ))
+        .id();
+
+    app.update();
+
+    let text = app.world().entity(entity).get::<Text>().unwrap();
+    assert!(text.sections[0].value.contains("update the game"));
+    assert!(text.sections[0].value.contains("v3-v4"));
+
+    app.world_mut().resource_mut::<ConnectionState>().status = ConnectionStatus::Connecting;
+    app.update();
+
+    let text = app.world().entity(entity).get::<Text>().unwrap();
+    assert!(text.sections[0].value.is_empty());
+}
+
 #[test]
 fn role_select_visible_when_screen_active() {
     let mut app = App::new();
@@ -411,6 +457,7 @@ fn begin_haunt_applies_selected_room_count_layout() {
         Transform::from_xyz(0.0, 0.9, 0.0),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
 
     let room_button = app
@@ -507,6 +554,7 @@ fn begin_investigation_applies_selected_room_count_layout() {
         Transform::from_xyz(0.0, 0.9, 0.0),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
 
     let room_button = app
@@ -586,6 +634,7 @@ fn begin_investigation_resets_player_and_ghost_spawns() {
             Transform::from_xyz(8.0, 0.9, 8.0),
             GlobalTransform::default(),
             crate::gameplay::investigator::Player,
+            crate::gameplay::investigator::ActiveInvestigator,
         ))
         .id();
 
@@ -691,7 +740,7 @@ fn begin_investigation_uses_house_layout_spawn_metadata_when_present() {
             },
         ],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![Vec3::new(-8.0, 0.7, -4.0)],
             banshee_anchors: vec![Vec3::new(8.0, 0.5, 0.0), Vec3::new(9.0, 0.5, 0.0)],
             onryo_cursed_positions: vec![Vec3::new(-8.0, 0.4, 0.0)],
@@ -708,6 +757,7 @@ fn begin_investigation_uses_house_layout_spawn_metadata_when_present() {
             Transform::from_xyz(1.0, 0.9, 1.0),
             GlobalTransform::default(),
             crate::gameplay::investigator::Player,
+            crate::gameplay::investigator::ActiveInvestigator,
         ))
         .id();
 
@@ -819,6 +869,7 @@ fn emf_updates_even_when_spiritbox_active() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -829,6 +880,7 @@ fn emf_updates_even_when_spiritbox_active() {
         Transform::from_xyz(0.0, 0.0, 0.5),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
     app.world_mut().spawn(Camera3dBundle::default());
 
@@ -875,6 +927,7 @@ fn spiritbox_gives_directional_banshee_reply_in_same_room() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -899,7 +952,7 @@ fn spiritbox_gives_directional_banshee_reply_in_same_room() {
             },
         }],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![Vec3::new(0.0, 0.7, 0.0)],
             banshee_anchors: vec![Vec3::new(0.0, 0.5, 0.0)],
             onryo_cursed_positions: vec![Vec3::new(0.0, 0.4, 0.0)],
@@ -914,6 +967,7 @@ fn spiritbox_gives_directional_banshee_reply_in_same_room() {
         Transform::from_xyz(0.0, 0.0, 0.0),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
 
     {
@@ -959,6 +1013,7 @@ fn spiritbox_says_here_only_when_banshee_is_actually_close() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -983,7 +1038,7 @@ fn spiritbox_says_here_only_when_banshee_is_actually_close() {
             },
         }],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![Vec3::new(0.0, 0.7, 0.0)],
             banshee_anchors: vec![Vec3::new(0.0, 0.5, 0.0)],
             onryo_cursed_positions: vec![Vec3::new(0.0, 0.4, 0.0)],
@@ -998,6 +1053,7 @@ fn spiritbox_says_here_only_when_banshee_is_actually_close() {
         Transform::from_xyz(0.0, 0.0, 0.0),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
 
     {
@@ -1043,6 +1099,7 @@ fn spiritbox_stays_static_when_banshee_is_in_another_room() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -1079,7 +1136,7 @@ fn spiritbox_stays_static_when_banshee_is_in_another_room() {
             },
         ],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![Vec3::new(0.0, 0.7, 0.0)],
             banshee_anchors: vec![Vec3::new(0.0, 0.5, 0.0)],
             onryo_cursed_positions: vec![Vec3::new(0.0, 0.4, 0.0)],
@@ -1094,6 +1151,7 @@ fn spiritbox_stays_static_when_banshee_is_in_another_room() {
         Transform::from_xyz(-4.0, 0.0, 0.0),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
 
     {
@@ -1188,7 +1246,7 @@ fn spirit_markers_use_house_layout_anchor_positions() {
             },
         }],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![Vec3::new(-7.0, 0.7, -3.0), Vec3::new(7.0, 0.7, 3.0)],
             banshee_anchors: vec![Vec3::new(0.0, 0.5, 0.0)],
             onryo_cursed_positions: vec![Vec3::new(-3.0, 0.4, 0.0)],
@@ -1252,6 +1310,7 @@ fn objective_text_guides_investigation_before_confirm() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -1316,6 +1375,7 @@ fn spirit_objective_text_explains_anchor_vigil() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -1392,6 +1452,7 @@ fn banshee_objective_text_shows_color_order_and_next_target() {
         emf_dwell: 0.0,
         emf_smoothed: 0.0,
         emf_evidence_latch: 0.0,
+        emf_reading: None,
         spiritbox_message: "Silence...".to_string(),
         spiritbox_cooldown: 0.0,
     });
@@ -1466,7 +1527,7 @@ fn banshee_sequence_accepts_second_correct_press_after_valid_delay() {
             },
         }],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![Vec3::new(0.0, 0.7, 0.0)],
             banshee_anchors: vec![
                 Vec3::new(-4.0, 0.5, 0.0),
@@ -1484,6 +1545,7 @@ fn banshee_sequence_accepts_second_correct_press_after_valid_delay() {
         Transform::from_xyz(-4.0, 0.9, 0.0),
         GlobalTransform::default(),
         crate::gameplay::investigator::Player,
+        crate::gameplay::investigator::ActiveInvestigator,
     ));
 
     {
@@ -1597,7 +1659,7 @@ fn spirit_puzzle_progress_builds_from_partial_anchor_coverage() {
             },
         }],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![
                 Vec3::new(-4.0, 0.7, 0.0),
                 Vec3::new(0.0, 0.7, 0.0),
@@ -1679,7 +1741,7 @@ fn spirit_puzzle_completes_when_two_anchors_are_maintained() {
             },
         }],
         walls: Vec::new(),
-        exorcism: crate::gameplay::map::components::ExorcismLayout {
+        exorcism: ExorcismLayout {
             spirit_anchors: vec![
                 Vec3::new(-3.0, 0.7, 0.0),
                 Vec3::new(3.0, 0.7, 0.0),
@@ -1768,6 +1830,47 @@ fn resolution_screen_opens_for_wrong_ghost_outcome() {
     assert!(resolution.shown);
 }
 
+#[test]
+fn online_rounds_leave_the_resolution_to_the_server() {
+    let mut app = App::new();
+    app.add_systems(Update, crate::ui::lobby::maybe_open_resolution_screen);
+    app.insert_resource(MenuState {
+        open: false,
+        selected_role: Role::Investigator,
+    });
+    app.insert_resource(MenuFlowState {
+        screen: MenuScreen::RoleSelect,
+    });
+    app.insert_resource(SessionState { started: true });
+    app.insert_resource(JournalState { open: true });
+    app.insert_resource(GhostTypeState {
+        selected: GhostType::Banshee,
+        active: GhostType::Spirit,
+    });
+    app.insert_resource(InvestigationState {
+        guess: Some(GhostType::Banshee),
+        confirmed: true,
+    });
+    app.insert_resource(ExorcismStatus {
+        state: crate::gameplay::exorcism::ExorcismState::Failed,
+        progress: 0.0,
+        stage: 0,
+        stacks: 0.0,
+        max_stacks: 0.0,
+    });
+    app.insert_resource(ResolutionState::default());
+    let mut prediction = crate::net::prediction::PredictionState::default();
+    prediction.begin();
+    app.insert_resource(prediction);
+
+    app.update();
+
+    let resolution = app.world().resource::<ResolutionState>();
+    assert_eq!(resolution.outcome, None);
+    assert!(!resolution.shown);
+    assert!(!app.world().resource::<MenuState>().open);
+}
+
 #[test]
 fn resolution_text_describes_wrong_ghost() {
     let mut app = App::new();
@@ -1808,3 +1911,427 @@ fn resolution_text_describes_wrong_ghost() {
     assert!(body_text.contains("Banshee"));
     assert!(body_text.contains("Spirit"));
 }
+
+#[test]
+fn resolution_text_describes_a_team_lost_to_the_hunt() {
+    let mut app = App::new();
+    app.add_systems(Update, crate::ui::lobby::sync_resolution_text);
+    app.insert_resource(ResolutionState {
+        outcome: Some(RoundOutcome::InvestigatorsDead),
+        shown: true,
+    });
+    app.insert_resource(InvestigationState::default());
+    app.insert_resource(GhostTypeState {
+        selected: GhostType::Spirit,
+        active: GhostType::Onryo,
+    });
+
+    let title = app
+        .world_mut()
+        .spawn((
+            TextBundle::from_section("Placeholder", TextStyle::default()),
+            ResolutionTitleText,
+        ))
+        .id();
+    let body = app
+        .world_mut()
+        .spawn((
+            TextBundle::from_section("Placeholder", TextStyle::default()),
+            ResolutionBodyText,
+        ))
+        .id();
+
+    app.update();
+
+    let title_text = &app.world().entity(title).get::<Text>().unwrap().sections[0].value;
+    let body_text = &app.world().entity(body).get::<Text>().unwrap().sections[0].value;
+    assert_eq!(title_text, "No One Made It Out");
+    assert!(body_text.contains("Onryo"));
+}
+
+#[test]
+fn profile_button_opens_the_profile_screen() {
+    let mut app = App::new();
+    app.add_systems(
+        Update,
+        (
+            crate::ui::lobby::handle_profile_interactions,
+            crate::ui::lobby::sync_profile_visibility,
+            crate::ui::lobby::sync_profile_text,
+        )
+            .chain(),
+    );
+    app.insert_resource(MenuState {
+        open: true,
+        selected_role: Role::Ghost,
+    });
+    app.insert_resource(MenuFlowState {
+        screen: MenuScreen::Start,
+    });
+    app.init_resource::<ProfileState>();
+
+    let button = app
+        .world_mut()
+        .spawn((Button, Interaction::Pressed, ProfileButton))
+        .id();
+    let root = app
+        .world_mut()
+        .spawn((Visibility::Hidden, ProfileRoot))
+        .id();
+    let body = app
+        .world_mut()
+        .spawn((
+            TextBundle::from_section("Placeholder", TextStyle::default()),
+            ProfileBodyText,
+        ))
+        .id();
+
+    app.update();
+
+    assert!(matches!(
+        app.world().resource::<MenuFlowState>().screen,
+        MenuScreen::Profile
+    ));
+    assert!(app.world().resource::<ProfileState>().requested);
+    assert_eq!(
+        *app.world().entity(root).get::<Visibility>().unwrap(),
+        Visibility::Visible
+    );
+    let body_text = &app.world().entity(body).get::<Text>().unwrap().sections[0].value;
+    assert_eq!(body_text, "Loading profile...");
+
+    app.world_mut().despawn(button);
+    app.world_mut().resource_mut::<ProfileState>().profile = Some(shared::prelude::PlayerProfile {
+        games_as_investigator: 2,
+        outcomes_as_investigator: shared::prelude::OutcomeTally {
+            successful_exorcism: 1,
+            wrong_ghost: 1,
+            failed_exorcism: 0,
+            investigators_dead: 0,
+        },
+        favourite_ghost: Some(GhostType::Banshee),
+        fastest_exorcisms: vec![shared::prelude::FastestExorcism {
+            puzzle: GhostType::Banshee,
+            layout: HouseLayoutKind::ThreeRoom,
+            seconds: 84.3,
+        }],
+        ..shared::prelude::PlayerProfile::new("Ray")
+    });
+    app.update();
+
+    let body_text = &app.world().entity(body).get::<Text>().unwrap().sections[0].value;
+    assert!(body_text.starts_with("Ray"));
+    assert!(body_text.contains("As investigator  Banished: 50%"));
+    assert!(!body_text.contains("As ghost"));
+    assert!(body_text.contains("Team lost: 0%"));
+    assert!(body_text.contains("Favourite ghost: Banshee"));
+    assert!(body_text.contains("Banshee (three rooms): 84.3s"));
+}
+
+#[test]
+fn spectate_button_closes_the_menu_into_a_free_camera() {
+    let mut app = App::new();
+    app.add_systems(Update, crate::ui::lobby::handle_spectate_interactions);
+    app.insert_resource(MenuState {
+        open: true,
+        selected_role: Role::Ghost,
+    });
+    app.insert_resource(MenuFlowState {
+        screen: MenuScreen::RoleSelect,
+    });
+    app.insert_resource(RoleState {
+        current: Role::Ghost,
+    });
+    app.insert_resource(JournalState { open: false });
+    app.insert_resource(CameraControl {
+        yaw: 1.0,
+        pitch: 0.0,
+    });
+    app.insert_resource(RoleYaw {
+        ghost: 1.0,
+        investigator: 0.0,
+    });
+    app.insert_resource(SpectatorState {
+        follow: SpectatorFollow::Player(3),
+        ..SpectatorState::default()
+    });
+    app.world_mut()
+        .spawn((Button, Interaction::Pressed, SpectateButton));
+
+    app.update();
+
+    assert!(!app.world().resource::<MenuState>().open);
+    assert_eq!(
+        app.world().resource::<MenuState>().selected_role,
+        Role::Spectator
+    );
+    assert_eq!(app.world().resource::<RoleState>().current, Role::Spectator);
+    assert_eq!(
+        app.world().resource::<SpectatorState>().follow,
+        SpectatorFollow::Free
+    );
+}
+
+#[test]
+fn spectator_hud_names_the_ghost_and_the_followed_player() {
+    let room = RoomState {
+        code: "ABCD".to_string(),
+        name: "Haunted".to_string(),
+        layout: HouseLayoutKind::TwoRoom,
+        members: vec![RoomMember {
+            id: 3,
+            display_name: "Ray".to_string(),
+            role: Some(Role::Investigator),
+        }],
+        in_round: true,
+    };
+    let watching = SpectatorState {
+        follow: SpectatorFollow::Player(3),
+        ghost_type: Some(GhostType::Onryo),
+        ..SpectatorState::default()
+    };
+    let summary = hud::spectator_summary(&watching, Some(&room));
+    assert!(summary.contains("Ghost: Onryo"));
+    assert!(summary.contains("Following Ray (Investigator)"));
+
+    let summary = hud::spectator_summary(&SpectatorState::default(), None);
+    assert!(summary.contains("waiting for the round"));
+    assert!(summary.contains("Free camera"));
+    assert!(!summary.contains("caught"));
+
+    let caught = SpectatorState {
+        caught: true,
+        ..SpectatorState::default()
+    };
+    assert!(hud::spectator_summary(&caught, None).starts_with("The ghost caught you."));
+}
+
+#[test]
+fn ghost_ability_text_counts_down_to_the_next_hunt() {
+    use shared::hunt::HuntPhase;
+
+    assert!(hud::ghost_ability_summary(HuntPhase::Cooldown(12.3)).ends_with("H: Hunt in 13s"));
+    assert!(hud::ghost_ability_summary(HuntPhase::Cooldown(0.0)).ends_with("H: Hunt (ready)"));
+    assert!(hud::ghost_ability_summary(HuntPhase::Hunting(4.0)).ends_with("HUNTING - 4s left"));
+}
+
+#[test]
+fn quick_play_button_toggles_between_search_and_cancel() {
+    let mut app = App::new();
+    app.add_systems(
+        Update,
+        (
+            crate::ui::lobby::handle_quick_play_interactions,
+            crate::ui::lobby::sync_quick_play_text,
+        )
+            .chain(),
+    );
+    app.insert_resource(QuickPlayState::default());
+    let role_button = app
+        .world_mut()
+        .spawn((Button, Interaction::Pressed, QuickPlayRoleButton))
+        .id();
+    let role_text = app
+        .world_mut()
+        .spawn((
+            Text::from_section("", TextStyle::default()),
+            QuickPlayRoleText,
+        ))
+        .id();
+    let button_text = app
+        .world_mut()
+        .spawn((
+            Text::from_section("", TextStyle::default()),
+            QuickPlayButtonText,
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(
+        app.world().resource::<QuickPlayState>().preference,
+        Some(Role::Ghost)
+    );
+    let text = app.world().entity(role_text).get::<Text>().unwrap();
+    assert_eq!(text.sections[0].value, "Role: Ghost");
+
+    app.world_mut().despawn(role_button);
+    let play_button = app
+        .world_mut()
+        .spawn((Button, Interaction::Pressed, QuickPlayButton))
+        .id();
+    app.update();
+    assert!(app.world().resource::<QuickPlayState>().requested);
+
+    app.world_mut().resource_mut::<QuickPlayState>().searching = true;
+    *app.world_mut().get_mut::<Interaction>(play_button).unwrap() = Interaction::Pressed;
+    app.update();
+    assert!(app.world().resource::<QuickPlayState>().cancel_requested);
+    let text = app.world().entity(button_text).get::<Text>().unwrap();
+    assert_eq!(text.sections[0].value, "Cancel");
+}
+
+#[test]
+fn quick_play_status_shows_position_wait_and_countdown() {
+    let mut quick_play = QuickPlayState {
+        searching: true,
+        ..QuickPlayState::default()
+    };
+    assert_eq!(lobby::quick_play_summary(&quick_play), "Searching...");
+
+    quick_play.status = Some(QueueStatus {
+        position: 2,
+        waiting: 3,
+        estimated_wait_ms: Some(11_200),
+    });
+    let summary = lobby::quick_play_summary(&quick_play);
+    assert!(summary.contains("#2 of 3"));
+    assert!(summary.contains("about 12s"));
+
+    quick_play.searching = false;
+    quick_play.matched = true;
+    quick_play.countdown_ms = Some(4_000);
+    assert!(lobby::quick_play_summary(&quick_play).contains("Starting in 4s"));
+
+    assert!(lobby::quick_play_summary(&QuickPlayState::default()).is_empty());
+}
+
+fn key_press(key_code: KeyCode, logical_key: Key) -> KeyboardInput {
+    KeyboardInput {
+        key_code,
+        logical_key,
+        state: ButtonState::Pressed,
+        window: Entity::PLACEHOLDER,
+    }
+}
+
+fn chat_app(in_round: bool, role: Role) -> App {
+    let mut app = App::new();
+    app.add_event::<KeyboardInput>()
+        .add_systems(Update, hud::handle_chat_input)
+        .insert_resource(ButtonInput::<KeyCode>::default())
+        .insert_resource(MenuState {
+            open: false,
+            selected_role: role,
+        })
+        .insert_resource(JournalState { open: false })
+        .insert_resource(ChatState::default())
+        .insert_resource(ConnectionState {
+            player_id: Some(3),
+            room: Some(RoomState {
+                code: "ABCD".to_string(),
+                name: "Haunted".to_string(),
+                layout: HouseLayoutKind::TwoRoom,
+                members: vec![RoomMember {
+                    id: 3,
+                    display_name: "Ray".to_string(),
+                    role: Some(role),
+                }],
+                in_round,
+            }),
+            ..default()
+        });
+    app
+}
+
+#[test]
+fn chat_opens_with_enter_and_queues_the_draft_on_the_team_channel() {
+    let mut app = chat_app(true, Role::Investigator);
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::Enter);
+    app.world_mut()
+        .send_event(key_press(KeyCode::Enter, Key::Enter));
+    app.update();
+    assert!(app.world().resource::<ChatState>().open);
+    assert!(app.world().resource::<ChatState>().draft.is_empty());
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+    for event in [
+        key_press(KeyCode::KeyH, Key::Character("h".into())),
+        key_press(KeyCode::KeyX, Key::Character("x".into())),
+        key_press(KeyCode::Backspace, Key::Backspace),
+        key_press(KeyCode::KeyI, Key::Character("i".into())),
+        key_press(KeyCode::Enter, Key::Enter),
+    ] {
+        app.world_mut().send_event(event);
+    }
+    app.update();
+
+    let chat = app.world().resource::<ChatState>();
+    assert!(!chat.open);
+    assert_eq!(chat.outbox, vec![(ChatChannel::Team, "hi".to_string())]);
+}
+
+#[test]
+fn chat_stays_closed_behind_the_journal_and_is_read_only_for_the_ghost() {
+    let mut app = chat_app(true, Role::Ghost);
+    app.insert_resource(JournalState { open: true });
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::Enter);
+    app.update();
+    assert!(!app.world().resource::<ChatState>().open);
+
+    app.insert_resource(JournalState { open: false });
+    app.update();
+    assert!(app.world().resource::<ChatState>().open);
+    assert_eq!(
+        hud::chat_channel(app.world().resource::<ConnectionState>()),
+        None
+    );
+
+    app.world_mut()
+        .send_event(key_press(KeyCode::KeyB, Key::Character("b".into())));
+    app.world_mut()
+        .send_event(key_press(KeyCode::Enter, Key::Enter));
+    app.update();
+    let chat = app.world().resource::<ChatState>();
+    assert!(!chat.open);
+    assert!(chat.outbox.is_empty());
+    let read_only = ChatState {
+        open: true,
+        ..ChatState::default()
+    };
+    assert!(hud::chat_prompt(&read_only, None).contains("read-only"));
+}
+
+#[test]
+fn chat_log_shows_the_latest_lines_with_their_channel() {
+    let line = |text: &str| {
+        ChatEntry::Message(ChatMessage {
+            channel: ChatChannel::Lobby,
+            from: 1,
+            display_name: "Ray".to_string(),
+            text: text.to_string(),
+        })
+    };
+    let log = vec![line("one"), line("two"), line("three")];
+    assert_eq!(
+        hud::chat_log(&log, 2),
+        "[Lobby] Ray: two\n[Lobby] Ray: three"
+    );
+    let overheard = ChatEntry::Message(ChatMessage {
+        channel: ChatChannel::Overheard,
+        from: 2,
+        display_name: "Egon".to_string(),
+        text: "cold spot".to_string(),
+    });
+    assert_eq!(
+        hud::chat_log(&[overheard], 1),
+        "[Overheard] Egon: cold spot"
+    );
+
+    let mut chat = ChatState {
+        open: true,
+        draft: "boo".to_string(),
+        ..ChatState::default()
+    };
+    assert_eq!(
+        hud::chat_prompt(&chat, Some(ChatChannel::Lobby)),
+        "[Lobby] > boo_"
+    );
+    chat.open = false;
+    assert_eq!(hud::chat_prompt(&chat, None), "Enter: chat");
+}
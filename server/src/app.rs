@@ -1,25 +1,104 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::time::Instant;
 
-use crate::core::{schedule::StartupTimer, AppConfig};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
+use crate::core::{
+    config::LogFormat,
+    schedule::{spawn_checkpointer, spawn_match_ticker, StartupTimer},
+    shutdown::graceful_shutdown,
+    AppConfig, AppState,
+};
 use crate::net;
 use crate::prelude::*;
+use crate::services::persistence::{CheckpointStore, PersistError};
 
-pub async fn run() {
+fn init_tracing(format: LogFormat) {
+    let layer = match format {
+        LogFormat::Full => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Compact => tracing_subscriber::fmt::layer().compact().boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().pretty().boxed(),
+    };
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(
             std::env::var("RUST_LOG").unwrap_or_else(|_| "server=info".into()),
         ))
-        .with(tracing_subscriber::fmt::layer())
+        .with(layer)
         .init();
+}
+
+fn recover_state(config: &AppConfig, store: &CheckpointStore) -> AppState {
+    match store.load() {
+        Ok(Some(checkpoint)) => {
+            info!("checkpoint: restoring from {}", store.path().display());
+            AppState::restore(config, &checkpoint, Instant::now())
+        }
+        Ok(None) => AppState::from_config(config),
+        Err(err @ PersistError::Corrupt { .. }) => {
+            error!("checkpoint: {}", err);
+            match store.quarantine() {
+                Ok(moved) => warn!("checkpoint: moved aside to {}", moved.display()),
+                Err(err) => warn!("checkpoint: {}", err),
+            }
+            AppState::from_config(config)
+        }
+        Err(err) => {
+            error!("checkpoint: {}", err);
+            AppState::from_config(config)
+        }
+    }
+}
+
+pub async fn run() -> ExitCode {
+    let config = AppConfig::load();
+    init_tracing(
+        config
+            .as_ref()
+            .map_or(LogFormat::default(), |config| config.log_format),
+    );
 
     let boot = StartupTimer::start();
-    let config = AppConfig::from_env();
-    let app = net::router();
+    let config = match config {
+        Ok(config) => config,
+        Err(err) => {
+            boot.fail(&err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match axum::Server::try_bind(&config.addr) {
+        Ok(server) => server,
+        Err(err) => {
+            boot.fail(&format!("cannot bind {}: {}", config.addr, err));
+            return ExitCode::FAILURE;
+        }
+    };
+    let store = config.checkpoint_store();
+    let state = match &store {
+        Some(store) => recover_state(&config, store),
+        None => AppState::from_config(&config),
+    };
+    spawn_match_ticker(state.clone(), config.tick_rate);
+    if let Some(store) = store.clone() {
+        spawn_checkpointer(state.clone(), store, config.checkpoint_interval());
+    }
+    let app = net::router(state.clone());
 
     info!("server listening on {}", config.addr);
     boot.finish();
-    axum::Server::bind(&config.addr)
-        .serve(app.into_make_service())
-        .await
-        .unwrap();
+    let served = server
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(graceful_shutdown(state.clone(), config.shutdown_grace()))
+        .await;
+    if let Some(store) = &store {
+        if let Err(err) = store.save(&state.checkpoint()) {
+            warn!("checkpoint: final save failed: {}", err);
+        }
+    }
+    if let Err(err) = served {
+        error!("server stopped: {}", err);
+        return ExitCode::FAILURE;
+    }
+    info!("server stopped");
+    ExitCode::SUCCESS
 }
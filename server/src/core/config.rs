@@ -1,16 +1,408 @@
+use std::fmt;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
-#[derive(Clone, Copy, Debug)]
+use serde::Deserialize;
+use shared::chat::DEFAULT_CHAT_RANGE;
+use shared::evidence::EvidenceTuning;
+use shared::exorcism::ExorcismTables;
+use shared::game::MAX_INVESTIGATORS;
+use shared::hunt::HuntTuning;
+
+use crate::services::auth::{TokenSigner, DEFAULT_TOKEN_TTL};
+use crate::services::lobby::{
+    LobbySettings, QuickPlaySettings, EMPTY_ROOM_TTL_MS, MAX_PLAYERS_PER_ROOM, MAX_ROOMS,
+    MAX_SPECTATORS_PER_ROOM,
+};
+use crate::services::persistence::CheckpointStore;
+use crate::services::ratelimit::{RateLimits, FRAME_CEILING, MAX_MESSAGE_BYTES};
+use crate::services::session::{SessionTimeouts, HEARTBEAT_INTERVAL, IDLE_TIMEOUT, RESUME_GRACE};
+
+const DEFAULT_TICK_RATE: u32 = 20;
+const MAX_TICK_RATE: u32 = 120;
+const DEFAULT_CONFIG_PATH: &str = "server.toml";
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(120);
+const DEFAULT_CHECKPOINT_PATH: &str = "server-state.json";
+const DEFAULT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Full,
+    Compact,
+    Pretty,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "full" => Ok(Self::Full),
+            "compact" => Ok(Self::Compact),
+            "pretty" => Ok(Self::Pretty),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AppConfig {
     pub addr: SocketAddr,
+    pub tick_rate: u32,
+    pub max_rooms: usize,
+    pub max_players_per_room: usize,
+    pub max_spectators_per_room: usize,
+    pub chat_range: f32,
+    pub empty_room_ttl_ms: u64,
+    pub heartbeat_ms: u64,
+    pub idle_timeout_ms: u64,
+    pub resume_grace_ms: u64,
+    pub shutdown_grace_ms: u64,
+    pub checkpoint_path: PathBuf,
+    pub checkpoint_interval_ms: u64,
+    pub auth_secret: String,
+    pub token_ttl_ms: u64,
+    pub max_message_bytes: usize,
+    pub log_format: LogFormat,
+    pub rate_limits: RateLimits,
+    pub quick_play: QuickPlaySettings,
+    pub evidence: EvidenceTuning,
+    pub exorcism: ExorcismTables,
+    pub hunt: HuntTuning,
 }
 
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            addr: SocketAddr::from(([0, 0, 0, 0], 8000)),
+            tick_rate: DEFAULT_TICK_RATE,
+            max_rooms: MAX_ROOMS,
+            max_players_per_room: MAX_PLAYERS_PER_ROOM,
+            max_spectators_per_room: MAX_SPECTATORS_PER_ROOM,
+            chat_range: DEFAULT_CHAT_RANGE,
+            empty_room_ttl_ms: EMPTY_ROOM_TTL_MS,
+            heartbeat_ms: HEARTBEAT_INTERVAL.as_millis() as u64,
+            idle_timeout_ms: IDLE_TIMEOUT.as_millis() as u64,
+            resume_grace_ms: RESUME_GRACE.as_millis() as u64,
+            shutdown_grace_ms: DEFAULT_SHUTDOWN_GRACE.as_millis() as u64,
+            checkpoint_path: PathBuf::from(DEFAULT_CHECKPOINT_PATH),
+            checkpoint_interval_ms: DEFAULT_CHECKPOINT_INTERVAL.as_millis() as u64,
+            auth_secret: String::new(),
+            token_ttl_ms: DEFAULT_TOKEN_TTL.as_millis() as u64,
+            max_message_bytes: MAX_MESSAGE_BYTES,
+            log_format: LogFormat::default(),
+            rate_limits: RateLimits::default(),
+            quick_play: QuickPlaySettings::default(),
+            evidence: EvidenceTuning::default(),
+            exorcism: ExorcismTables::default(),
+            hunt: HuntTuning::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Read { path: PathBuf, reason: String },
+    Parse { path: PathBuf, reason: String },
+    Env { name: &'static str, value: String },
+    Invalid { field: &'static str, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, reason } => {
+                write!(f, "cannot read config {}: {}", path.display(), reason)
+            }
+            Self::Parse { path, reason } => {
+                write!(f, "invalid config {}: {}", path.display(), reason.trim())
+            }
+            Self::Env { name, value } => write!(f, "invalid value {:?} for {}", value, name),
+            Self::Invalid { field, reason } => write!(f, "invalid {}: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
 impl AppConfig {
-    pub fn from_env() -> Self {
-        let addr = std::env::var("SERVER_ADDR")
-            .ok()
-            .and_then(|value| value.parse().ok())
-            .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 8000)));
-        Self { addr }
+    pub fn load() -> Result<Self, ConfigError> {
+        let explicit = std::env::var("SERVER_CONFIG").ok().map(PathBuf::from);
+        let path = explicit
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+        let file = if explicit.is_some() || path.exists() {
+            let text = std::fs::read_to_string(&path).map_err(|err| ConfigError::Read {
+                path: path.clone(),
+                reason: err.to_string(),
+            })?;
+            Some((path, text))
+        } else {
+            None
+        };
+        Self::from_sources(
+            file.as_ref()
+                .map(|(path, text)| (path.as_path(), text.as_str())),
+            |name| std::env::var(name).ok(),
+        )
+    }
+
+    pub fn from_sources(
+        file: Option<(&Path, &str)>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let mut config = match file {
+            Some((path, text)) => toml::from_str(text).map_err(|err| ConfigError::Parse {
+                path: path.to_path_buf(),
+                reason: err.to_string(),
+            })?,
+            None => Self::default(),
+        };
+        override_from(&env, "SERVER_ADDR", &mut config.addr)?;
+        override_from(&env, "TICK_RATE", &mut config.tick_rate)?;
+        override_from(&env, "MAX_ROOMS", &mut config.max_rooms)?;
+        override_from(
+            &env,
+            "MAX_PLAYERS_PER_ROOM",
+            &mut config.max_players_per_room,
+        )?;
+        override_from(
+            &env,
+            "MAX_SPECTATORS_PER_ROOM",
+            &mut config.max_spectators_per_room,
+        )?;
+        override_from(&env, "CHAT_RANGE", &mut config.chat_range)?;
+        override_from(&env, "EMPTY_ROOM_TTL_MS", &mut config.empty_room_ttl_ms)?;
+        override_from(&env, "HEARTBEAT_MS", &mut config.heartbeat_ms)?;
+        override_from(&env, "IDLE_TIMEOUT_MS", &mut config.idle_timeout_ms)?;
+        override_from(&env, "RESUME_GRACE_MS", &mut config.resume_grace_ms)?;
+        override_from(&env, "SHUTDOWN_GRACE_MS", &mut config.shutdown_grace_ms)?;
+        override_from(&env, "CHECKPOINT_PATH", &mut config.checkpoint_path)?;
+        override_from(
+            &env,
+            "CHECKPOINT_INTERVAL_MS",
+            &mut config.checkpoint_interval_ms,
+        )?;
+        override_from(&env, "AUTH_SECRET", &mut config.auth_secret)?;
+        override_from(&env, "TOKEN_TTL_MS", &mut config.token_ttl_ms)?;
+        override_from(&env, "MAX_MESSAGE_BYTES", &mut config.max_message_bytes)?;
+        override_from(&env, "LOG_FORMAT", &mut config.log_format)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(1..=MAX_TICK_RATE).contains(&self.tick_rate) {
+            return invalid(
+                "tick_rate",
+                format!(
+                    "must be between 1 and {} (got {})",
+                    MAX_TICK_RATE, self.tick_rate
+                ),
+            );
+        }
+        if self.max_rooms == 0 {
+            return invalid("max_rooms", "must be at least 1".to_string());
+        }
+        if !(2..=MAX_INVESTIGATORS + 1).contains(&self.max_players_per_room) {
+            return invalid(
+                "max_players_per_room",
+                format!(
+                    "must be between 2 and {} (got {})",
+                    MAX_INVESTIGATORS + 1,
+                    self.max_players_per_room
+                ),
+            );
+        }
+        if !(self.chat_range >= 0.0 && self.chat_range.is_finite()) {
+            return invalid(
+                "chat_range",
+                format!(
+                    "must be a finite distance of 0 or more (got {})",
+                    self.chat_range
+                ),
+            );
+        }
+        if self.empty_room_ttl_ms == 0 {
+            return invalid("empty_room_ttl_ms", "must be greater than 0".to_string());
+        }
+        if self.heartbeat_ms == 0 {
+            return invalid("heartbeat_ms", "must be greater than 0".to_string());
+        }
+        if self.idle_timeout_ms <= self.heartbeat_ms {
+            return invalid(
+                "idle_timeout_ms",
+                format!(
+                    "must be longer than heartbeat_ms ({} <= {})",
+                    self.idle_timeout_ms, self.heartbeat_ms
+                ),
+            );
+        }
+        if self.resume_grace_ms == 0 {
+            return invalid("resume_grace_ms", "must be greater than 0".to_string());
+        }
+        if self.checkpoint_interval_ms > 0 && self.checkpoint_path.as_os_str().is_empty() {
+            return invalid(
+                "checkpoint_path",
+                "must be set while checkpoint_interval_ms is above 0".to_string(),
+            );
+        }
+        if self.token_ttl_ms == 0 {
+            return invalid("token_ttl_ms", "must be greater than 0".to_string());
+        }
+        if !(1..=FRAME_CEILING).contains(&self.max_message_bytes) {
+            return invalid(
+                "max_message_bytes",
+                format!(
+                    "must be between 1 and {} (got {})",
+                    FRAME_CEILING, self.max_message_bytes
+                ),
+            );
+        }
+        for (name, limit) in self.rate_limits.buckets() {
+            if limit.per_second <= 0.0 || limit.burst < 1.0 {
+                return invalid(
+                    "rate_limits",
+                    format!("{} needs per_second > 0 and burst >= 1", name),
+                );
+            }
+        }
+
+        let investigators = self.quick_play.investigators;
+        if investigators == 0 || investigators >= self.max_players_per_room {
+            return invalid(
+                "quick_play.investigators",
+                format!(
+                    "must be between 1 and {} (got {})",
+                    self.max_players_per_room - 1,
+                    investigators
+                ),
+            );
+        }
+
+        let evidence = &self.evidence;
+        if !(evidence.emf_range_4 <= evidence.emf_range_3
+            && evidence.emf_range_3 <= evidence.emf_range_2
+            && evidence.emf_range_4 > 0.0)
+        {
+            return invalid(
+                "evidence.emf_range_*",
+                "ranges must be positive and ordered emf_range_4 <= emf_range_3 <= emf_range_2"
+                    .to_string(),
+            );
+        }
+        if evidence.tool_bubble_radius < 0.0 || evidence.ghost_influence_radius < 0.0 {
+            return invalid("evidence", "radii must not be negative".to_string());
+        }
+
+        let banshee = &self.exorcism.banshee;
+        if banshee.default_sequence_len == 0 {
+            return invalid(
+                "exorcism.banshee.default_sequence_len",
+                "must be at least 1".to_string(),
+            );
+        }
+        if banshee.timing_min >= banshee.timing_max {
+            return invalid(
+                "exorcism.banshee.timing_min",
+                format!(
+                    "must be below timing_max ({} >= {})",
+                    banshee.timing_min, banshee.timing_max
+                ),
+            );
+        }
+        if banshee.interact_distance <= 0.0 || self.exorcism.onryo.interact_distance <= 0.0 {
+            return invalid(
+                "exorcism.*.interact_distance",
+                "must be greater than 0".to_string(),
+            );
+        }
+        if self.exorcism.spirit.rate_up <= 0.0 {
+            return invalid(
+                "exorcism.spirit.rate_up",
+                "must be greater than 0".to_string(),
+            );
+        }
+
+        let hunt = &self.hunt;
+        if !(hunt.cooldown_seconds >= 0.0 && hunt.duration_seconds > 0.0 && hunt.kill_radius > 0.0)
+        {
+            return invalid(
+                "hunt",
+                "cooldown_seconds must not be negative; duration_seconds and kill_radius must be greater than 0"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+
+    pub fn session_timeouts(&self) -> SessionTimeouts {
+        SessionTimeouts {
+            heartbeat: Duration::from_millis(self.heartbeat_ms),
+            idle: Duration::from_millis(self.idle_timeout_ms),
+            resume_grace: Duration::from_millis(self.resume_grace_ms),
+        }
+    }
+
+    pub fn shutdown_grace(&self) -> Duration {
+        Duration::from_millis(self.shutdown_grace_ms)
+    }
+
+    pub fn checkpoint_store(&self) -> Option<CheckpointStore> {
+        (self.checkpoint_interval_ms > 0).then(|| CheckpointStore::new(&self.checkpoint_path))
     }
+
+    pub fn checkpoint_interval(&self) -> Duration {
+        Duration::from_millis(self.checkpoint_interval_ms)
+    }
+
+    // A configured secret wins; otherwise reuse the one saved in the last
+    // checkpoint so guest tokens stay valid across restarts.
+    pub fn token_signer(&self, saved_secret: Option<&str>) -> TokenSigner {
+        let ttl = Duration::from_millis(self.token_ttl_ms);
+        if !self.auth_secret.is_empty() {
+            return TokenSigner::new(self.auth_secret.as_bytes(), ttl);
+        }
+        saved_secret
+            .and_then(|secret| TokenSigner::from_hex(secret, ttl))
+            .unwrap_or_else(|| TokenSigner::generate(ttl))
+    }
+
+    pub fn lobby_settings(&self) -> LobbySettings {
+        LobbySettings {
+            max_rooms: self.max_rooms,
+            max_players_per_room: self.max_players_per_room,
+            max_spectators_per_room: self.max_spectators_per_room,
+            chat_range: self.chat_range,
+            empty_room_ttl_ms: self.empty_room_ttl_ms,
+            quick_play: self.quick_play,
+            evidence: self.evidence.clone(),
+            exorcism: self.exorcism.clone(),
+            hunt: self.hunt.clone(),
+        }
+    }
+}
+
+fn override_from<T: FromStr>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    target: &mut T,
+) -> Result<(), ConfigError> {
+    let Some(value) = env(name) else {
+        return Ok(());
+    };
+    *target = value
+        .trim()
+        .parse()
+        .map_err(|_| ConfigError::Env { name, value })?;
+    Ok(())
+}
+
+fn invalid(field: &'static str, reason: String) -> Result<(), ConfigError> {
+    Err(ConfigError::Invalid { field, reason })
 }
@@ -1,4 +1,7 @@
 pub mod config;
 pub mod schedule;
+pub mod shutdown;
+pub mod state;
 
 pub use config::AppConfig;
+pub use state::AppState;
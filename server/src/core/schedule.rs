@@ -1,6 +1,11 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use tracing::info;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::core::AppState;
+use crate::services::persistence::CheckpointStore;
 
 pub struct StartupTimer {
     started: Instant,
@@ -17,4 +22,88 @@ impl StartupTimer {
     pub fn finish(self) {
         info!("boot: ready in {}ms", self.started.elapsed().as_millis());
     }
+
+    pub fn fail(self, reason: &dyn std::fmt::Display) {
+        error!(
+            "boot: failed after {}ms: {}",
+            self.started.elapsed().as_millis(),
+            reason
+        );
+    }
+}
+
+#[derive(Default)]
+pub struct SchedulerStatus {
+    period: Option<Duration>,
+    last_tick: Option<Instant>,
+}
+
+impl SchedulerStatus {
+    pub fn start(&mut self, period: Duration) {
+        self.period = Some(period);
+    }
+
+    pub fn record_tick(&mut self, at: Instant) {
+        self.last_tick = Some(at);
+    }
+
+    pub fn period(&self) -> Option<Duration> {
+        self.period
+    }
+
+    pub fn last_tick_age(&self, now: Instant) -> Option<Duration> {
+        self.last_tick.map(|at| now.saturating_duration_since(at))
+    }
+}
+
+pub fn spawn_match_ticker(state: AppState, tick_rate: u32) -> JoinHandle<()> {
+    let period = Duration::from_secs_f32(1.0 / tick_rate.max(1) as f32);
+    state.scheduler().start(period);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let started = Instant::now();
+            let expired = state.sessions().expire(Instant::now());
+            let outbound = {
+                let mut lobby = state.lobby();
+                for player in expired {
+                    info!("session: player {} did not reconnect in time", player);
+                    lobby.leave(player);
+                }
+                lobby.step(period.as_secs_f32());
+                lobby.drain_outbound()
+            };
+            state.metrics().observe_tick(started.elapsed());
+            state.scheduler().record_tick(Instant::now());
+            state.broadcast(outbound);
+        }
+    })
+}
+
+pub fn spawn_checkpointer(
+    state: AppState,
+    store: CheckpointStore,
+    period: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_saved = None;
+        loop {
+            interval.tick().await;
+            let checkpoint = state.checkpoint();
+            if last_saved.as_ref() == Some(&checkpoint) {
+                continue;
+            }
+            let writer = store.clone();
+            let saving = checkpoint.clone();
+            match tokio::task::spawn_blocking(move || writer.save(&saving)).await {
+                Ok(Ok(())) => last_saved = Some(checkpoint),
+                Ok(Err(err)) => warn!("checkpoint: {}", err),
+                Err(err) => warn!("checkpoint: writer panicked: {}", err),
+            }
+        }
+    })
 }
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use shared::prelude::ServerMessage;
+use tokio::time::Instant;
+use tracing::info;
+
+use crate::core::AppState;
+
+const DRAIN_POLL: Duration = Duration::from_millis(250);
+// How long open sockets get to send their close frame before the server stops.
+const CLOSE_WAIT: Duration = Duration::from_secs(1);
+// Sent to every socket when the server stops.
+pub const CLOSE_GOING_AWAY: u16 = 1001;
+
+// What every socket is told about the shutdown so far. Sockets watch this
+// rather than the lobby outbox so players outside rooms hear it too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShutdownPhase {
+    #[default]
+    Running,
+    Draining {
+        deadline: Instant,
+    },
+    Closing,
+}
+
+impl ShutdownPhase {
+    // Counts down to the drain deadline, so sockets that connect late are not
+    // promised the whole grace period again.
+    pub fn notice(&self, now: Instant) -> Option<ServerMessage> {
+        match self {
+            Self::Draining { deadline } => Some(ServerMessage::ShuttingDown {
+                remaining_ms: deadline.saturating_duration_since(now).as_millis() as u64,
+            }),
+            Self::Running | Self::Closing => None,
+        }
+    }
+}
+
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+pub async fn drain_matches(state: AppState, grace: Duration) {
+    let deadline = Instant::now() + grace;
+    {
+        let mut lobby = state.lobby();
+        lobby.begin_shutdown();
+        info!(
+            "shutdown: draining {} running round(s), waiting up to {}s",
+            lobby.active_rounds(),
+            grace.as_secs()
+        );
+    }
+    state.announce_shutdown(ShutdownPhase::Draining { deadline });
+
+    loop {
+        let running = state.lobby().active_rounds();
+        if running == 0 {
+            info!("shutdown: all rounds finished");
+            return;
+        }
+        if Instant::now() >= deadline {
+            info!(
+                "shutdown: deadline reached with {} round(s) running",
+                running
+            );
+            return;
+        }
+        tokio::time::sleep(DRAIN_POLL.min(deadline - Instant::now())).await;
+    }
+}
+
+pub async fn close_sockets(state: &AppState) {
+    state.announce_shutdown(ShutdownPhase::Closing);
+    let deadline = Instant::now() + CLOSE_WAIT;
+    while state.metrics().connections_active() > 0 && Instant::now() < deadline {
+        tokio::time::sleep(DRAIN_POLL.min(deadline - Instant::now())).await;
+    }
+}
+
+pub async fn graceful_shutdown(state: AppState, grace: Duration) {
+    shutdown_signal().await;
+    tokio::select! {
+        _ = drain_matches(state.clone(), grace) => {},
+        _ = shutdown_signal() => info!("shutdown: second signal, stopping now"),
+    }
+    close_sockets(&state).await;
+}
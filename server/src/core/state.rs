@@ -0,0 +1,170 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
+
+use shared::prelude::PlayerId;
+use tokio::sync::{broadcast, watch};
+
+use crate::core::schedule::SchedulerStatus;
+use crate::core::shutdown::ShutdownPhase;
+use crate::core::AppConfig;
+use crate::services::auth::{TokenSigner, DEFAULT_TOKEN_TTL};
+use crate::services::lobby::{Lobby, Outbound};
+use crate::services::metrics::Metrics;
+use crate::services::persistence::{Checkpoint, CHECKPOINT_VERSION};
+use crate::services::ratelimit::{AddressLimiter, RateLimits, MAX_MESSAGE_BYTES};
+use crate::services::session::SessionRegistry;
+
+// Shared by every room, which sends a snapshot per audience each tick.
+const BROADCAST_CAPACITY: usize = 1024;
+
+#[derive(Clone)]
+pub struct AppState {
+    lobby: Arc<Mutex<Lobby>>,
+    sessions: Arc<Mutex<SessionRegistry>>,
+    metrics: Arc<Mutex<Metrics>>,
+    scheduler: Arc<Mutex<SchedulerStatus>>,
+    auth: Arc<TokenSigner>,
+    rate_limits: RateLimits,
+    guest_mints: Arc<Mutex<AddressLimiter>>,
+    max_message_bytes: usize,
+    broadcasts: broadcast::Sender<Outbound>,
+    shutdown: Arc<watch::Sender<ShutdownPhase>>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        Self::from_parts(
+            Lobby::new(),
+            SessionRegistry::new(),
+            TokenSigner::generate(DEFAULT_TOKEN_TTL),
+        )
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::from_parts(
+            Lobby::with_settings(config.lobby_settings()),
+            SessionRegistry::with_timeouts(config.session_timeouts()),
+            config.token_signer(None),
+        )
+        .with_limits(config)
+    }
+
+    pub fn restore(config: &AppConfig, checkpoint: &Checkpoint, now: Instant) -> Self {
+        let mut lobby = Lobby::restore(config.lobby_settings(), &checkpoint.lobby);
+        let sessions =
+            SessionRegistry::restore(config.session_timeouts(), &checkpoint.sessions, now);
+        let resumable: Vec<PlayerId> = checkpoint
+            .sessions
+            .iter()
+            .filter_map(|session| session.player)
+            .collect();
+        for player in lobby.players() {
+            if !resumable.contains(&player) {
+                lobby.leave(player);
+            }
+        }
+        lobby.drain_outbound();
+        let auth = config.token_signer(checkpoint.auth_secret.as_deref());
+        Self::from_parts(lobby, sessions, auth).with_limits(config)
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        let lobby = self.lobby();
+        Checkpoint {
+            version: CHECKPOINT_VERSION,
+            lobby: lobby.checkpoint(),
+            sessions: self.sessions().checkpoint(),
+            auth_secret: self.auth.checkpoint_secret(),
+        }
+    }
+
+    fn from_parts(lobby: Lobby, sessions: SessionRegistry, auth: TokenSigner) -> Self {
+        let (broadcasts, _) = broadcast::channel(BROADCAST_CAPACITY);
+        let (shutdown, _) = watch::channel(ShutdownPhase::Running);
+        Self {
+            lobby: Arc::new(Mutex::new(lobby)),
+            sessions: Arc::new(Mutex::new(sessions)),
+            metrics: Arc::new(Mutex::new(Metrics::new())),
+            scheduler: Arc::new(Mutex::new(SchedulerStatus::default())),
+            auth: Arc::new(auth),
+            rate_limits: RateLimits::default(),
+            guest_mints: Arc::new(Mutex::new(AddressLimiter::new(
+                RateLimits::default().guest_tokens,
+            ))),
+            max_message_bytes: MAX_MESSAGE_BYTES,
+            broadcasts,
+            shutdown: Arc::new(shutdown),
+        }
+    }
+
+    fn with_limits(self, config: &AppConfig) -> Self {
+        Self {
+            rate_limits: config.rate_limits,
+            guest_mints: Arc::new(Mutex::new(AddressLimiter::new(
+                config.rate_limits.guest_tokens,
+            ))),
+            max_message_bytes: config.max_message_bytes,
+            ..self
+        }
+    }
+
+    pub fn lobby(&self) -> MutexGuard<'_, Lobby> {
+        self.lobby.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn sessions(&self) -> MutexGuard<'_, SessionRegistry> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn metrics(&self) -> MutexGuard<'_, Metrics> {
+        self.metrics.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn scheduler(&self) -> MutexGuard<'_, SchedulerStatus> {
+        self.scheduler
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn auth(&self) -> &TokenSigner {
+        &self.auth
+    }
+
+    pub fn rate_limits(&self) -> &RateLimits {
+        &self.rate_limits
+    }
+
+    pub fn guest_mints(&self) -> MutexGuard<'_, AddressLimiter> {
+        self.guest_mints
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn max_message_bytes(&self) -> usize {
+        self.max_message_bytes
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Outbound> {
+        self.broadcasts.subscribe()
+    }
+
+    pub fn broadcast(&self, outbound: Vec<Outbound>) {
+        for message in outbound {
+            let _ = self.broadcasts.send(message);
+        }
+    }
+
+    pub fn watch_shutdown(&self) -> watch::Receiver<ShutdownPhase> {
+        self.shutdown.subscribe()
+    }
+
+    pub fn announce_shutdown(&self, phase: ShutdownPhase) {
+        self.shutdown.send_replace(phase);
+    }
+}
@@ -1,4 +1,6 @@
+use std::process::ExitCode;
+
 #[tokio::main]
-async fn main() {
-    server::app::run().await;
+async fn main() -> ExitCode {
+    server::app::run().await
 }
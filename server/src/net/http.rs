@@ -1,6 +1,121 @@
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use axum::extract::{ConnectInfo, Path};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::Response;
+
+use crate::core::AppState;
 use crate::prelude::*;
 use crate::services;
 
 pub async fn health() -> Json<Health> {
     Json(services::health::health())
 }
+
+pub async fn ready(State(state): State<AppState>) -> (StatusCode, Json<Readiness>) {
+    let lobby = state.lobby().stats();
+    let (age, period) = {
+        let scheduler = state.scheduler();
+        (scheduler.last_tick_age(Instant::now()), scheduler.period())
+    };
+    let readiness = services::health::readiness(age, period, &lobby);
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness))
+}
+
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    let lobby = state.lobby().stats();
+    let (sessions, resumed) = {
+        let sessions = state.sessions();
+        (sessions.len(), sessions.resumed_total())
+    };
+    let body = state.metrics().render(&lobby, sessions, resumed);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
+
+pub async fn list_rooms(State(state): State<AppState>) -> Json<Vec<RoomInfo>> {
+    Json(state.lobby().open_rooms())
+}
+
+// Rooms hold a `max_rooms` slot until they close, so only guests with a
+// token may open one.
+pub async fn create_room(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateRoomRequest>,
+) -> Response {
+    let token = bearer_token(&headers);
+    if let Err(err) = state
+        .auth()
+        .verify(token.as_deref(), services::auth::unix_ms())
+    {
+        info!("lobby: rejected room creation: {}", err);
+        state.metrics().rejected("auth", format!("{:?}", err));
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let created = state.lobby().create_room(&request.name);
+    match created {
+        Ok(info) => Json(info).into_response(),
+        Err(rejection) => (rejection_status(rejection), Json(rejection)).into_response(),
+    }
+}
+
+pub async fn guest_token(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Json(request): Json<GuestTokenRequest>,
+) -> Response {
+    if !state.guest_mints().check(peer.ip(), Instant::now()) {
+        info!("auth: refused a guest token for {}", peer.ip());
+        state
+            .metrics()
+            .rejected("rate_limit", "guest_tokens".to_string());
+        return StatusCode::TOO_MANY_REQUESTS.into_response();
+    }
+    match state
+        .auth()
+        .issue(&request.display_name, services::auth::unix_ms())
+    {
+        Ok(token) => Json(token).into_response(),
+        Err(rejection) => (rejection_status(rejection), Json(rejection)).into_response(),
+    }
+}
+
+pub async fn profile(
+    State(state): State<AppState>,
+    Path(account_id): Path<String>,
+) -> Result<Json<PlayerProfile>, StatusCode> {
+    state
+        .lobby()
+        .profile(&account_id)
+        .cloned()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    value
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+fn rejection_status(rejection: LobbyRejection) -> StatusCode {
+    match rejection {
+        LobbyRejection::InvalidName | LobbyRejection::InvalidRole => StatusCode::BAD_REQUEST,
+        LobbyRejection::TooManyRooms | LobbyRejection::ShuttingDown => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        LobbyRejection::RoomNotFound | LobbyRejection::NotInRoom => StatusCode::NOT_FOUND,
+        LobbyRejection::RoomFull
+        | LobbyRejection::GhostTaken
+        | LobbyRejection::NotGhost
+        | LobbyRejection::RoundInProgress
+        | LobbyRejection::RolesIncomplete => StatusCode::CONFLICT,
+    }
+}
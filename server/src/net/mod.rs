@@ -1,11 +1,19 @@
+use crate::core::AppState;
 use crate::prelude::*;
 
 pub mod http;
 pub mod protocol;
 pub mod ws;
 
-pub fn router() -> Router {
+pub fn router(state: AppState) -> Router {
     Router::new()
         .route("/health", get(http::health))
+        .route("/health/live", get(http::health))
+        .route("/health/ready", get(http::ready))
+        .route("/metrics", get(http::metrics))
+        .route("/rooms", get(http::list_rooms).post(http::create_room))
+        .route("/auth/guest", post(http::guest_token))
+        .route("/profiles/:account", get(http::profile))
         .route("/ws", get(ws::ws_handler))
+        .with_state(state)
 }
@@ -1,12 +1,25 @@
+use shared::codec::{self, Frame};
+
 use crate::prelude::*;
 
 pub fn parse_client_message(text: &str) -> Option<ClientMessage> {
     if text.eq_ignore_ascii_case("ping") {
         return Some(ClientMessage::Ping);
     }
-    serde_json::from_str::<ClientMessage>(text).ok()
+    codec::decode_text(text)
+}
+
+pub fn parse_client_binary(bytes: &[u8]) -> Option<ClientMessage> {
+    codec::decode_binary(bytes)
 }
 
 pub fn server_message_text(message: &ServerMessage) -> Option<String> {
     serde_json::to_string(message).ok()
 }
+
+pub fn encode_server_message(message: &ServerMessage, codec: WireCodec) -> Option<Message> {
+    match codec.encode(message)? {
+        Frame::Text(text) => Some(Message::Text(text)),
+        Frame::Binary(bytes) => Some(Message::Binary(bytes)),
+    }
+}
@@ -1,26 +1,300 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::ws::CloseFrame;
+use axum::extract::Query;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Response;
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Notify;
+use tokio::time::MissedTickBehavior;
+
+use crate::core::shutdown::{ShutdownPhase, CLOSE_GOING_AWAY};
+use crate::core::AppState;
+use crate::net::http::bearer_token;
+use crate::net::protocol;
 use crate::prelude::*;
 use crate::services;
+use crate::services::auth::GuestAccount;
+use crate::services::ratelimit::{
+    ConnectionLimiter, Verdict, CLOSE_POLICY_VIOLATION, FRAME_CEILING,
+};
+use crate::services::session::{SessionHandle, CLOSE_FELL_BEHIND, CLOSE_SESSION_TAKEN_OVER};
+use crate::services::ws::SocketAction;
 
-pub async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(handle_socket)
+#[derive(Debug, Default, Deserialize)]
+pub struct ConnectParams {
+    token: Option<String>,
 }
 
-async fn handle_socket(mut socket: WebSocket) {
-    while let Some(msg) = socket.recv().await {
-        match msg {
-            Ok(message) => match services::ws::handle_message(message) {
-                services::ws::SocketAction::Send(response) => {
-                    let _ = socket.send(response).await;
+pub async fn ws_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<ConnectParams>,
+    headers: HeaderMap,
+) -> Response {
+    let token = params.token.or_else(|| bearer_token(&headers));
+    let account = match state
+        .auth()
+        .verify(token.as_deref(), services::auth::unix_ms())
+    {
+        Ok(account) => account,
+        Err(err) => {
+            info!("session: rejected websocket auth: {}", err);
+            state.metrics().rejected("auth", format!("{:?}", err));
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    };
+    ws.max_frame_size(FRAME_CEILING)
+        .max_message_size(FRAME_CEILING)
+        .on_upgrade(move |socket| handle_socket(socket, state, account))
+}
+
+async fn send_server_message(
+    socket: &mut WebSocket,
+    state: &AppState,
+    codec: WireCodec,
+    message: &ServerMessage,
+) -> bool {
+    state.metrics().message_out(message);
+    match protocol::encode_server_message(message, codec) {
+        Some(frame) => socket.send(frame).await.is_ok(),
+        None => true,
+    }
+}
+
+async fn handle_socket(mut socket: WebSocket, state: AppState, account: GuestAccount) {
+    state.metrics().connection_opened();
+    let mut broadcasts = state.subscribe();
+    let mut shutdown = state.watch_shutdown();
+    let mut session: Option<SessionHandle> = None;
+    let mut player: Option<PlayerId> = None;
+    let mut last_seen = Instant::now();
+    let timeouts = state.sessions().timeouts();
+    let mut heartbeat = tokio::time::interval(timeouts.heartbeat);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut heartbeat_seq = 0u64;
+    let mut limiter = ConnectionLimiter::new(state.rate_limits(), last_seen);
+    let mut codec = WireCodec::Json;
+    let taken_over = Arc::new(Notify::new());
+
+    loop {
+        tokio::select! {
+            msg = socket.recv() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+                last_seen = Instant::now();
+                match msg {
+                    Ok(message) => match classify(&state, &mut limiter, codec, message, last_seen) {
+                        SocketAction::Send(response) => {
+                            let _ = socket.send(response).await;
+                        }
+                        SocketAction::Dispatch(ClientMessage::Hello { version, resume_token, codecs }) => {
+                            if session.is_some() {
+                                continue;
+                            }
+                            let handshake = {
+                                let lobby = state.lobby();
+                                services::ws::handle_authenticated_hello(
+                                    &mut state.sessions(),
+                                    &lobby,
+                                    &account,
+                                    version,
+                                    resume_token.as_deref(),
+                                    &codecs,
+                                    Instant::now(),
+                                )
+                            };
+                            let (handle, welcome) = match handshake {
+                                Ok(accepted) => accepted,
+                                Err(mismatch) => {
+                                    info!("session: rejected protocol version {}", version);
+                                    state.metrics().rejected("protocol", format!("Update{:?}", mismatch.update));
+                                    let reply = ServerMessage::ProtocolMismatch(mismatch);
+                                    send_server_message(&mut socket, &state, codec, &reply).await;
+                                    break;
+                                }
+                            };
+                            player = handle.player;
+                            state.sessions().watch_takeover(&handle, taken_over.clone());
+                            session = Some(handle);
+                            // The welcome itself always goes out as JSON.
+                            if !send_server_message(&mut socket, &state, WireCodec::Json, &welcome).await {
+                                break;
+                            }
+                            if let ServerMessage::Welcome { codec: chosen, .. } = welcome {
+                                codec = chosen;
+                            }
+                            // Connections made while draining still hear about it.
+                            let notice = shutdown.borrow().notice(tokio::time::Instant::now());
+                            if let Some(notice) = notice {
+                                if !send_server_message(&mut socket, &state, codec, &notice).await {
+                                    break;
+                                }
+                            }
+                            let rejoined = player.and_then(|id| state.lobby().rejoin_round(id));
+                            if let Some(round) = rejoined {
+                                if !send_server_message(&mut socket, &state, codec, &round).await {
+                                    break;
+                                }
+                            }
+                        }
+                        SocketAction::Dispatch(client_msg) => {
+                            let Some(handle) = session.as_ref() else {
+                                continue;
+                            };
+                            if !state.sessions().is_current(handle) {
+                                continue;
+                            }
+                            let (reply, outbound) = {
+                                let mut lobby = state.lobby();
+                                let reply = services::ws::handle_authenticated_message(&mut lobby, &mut player, &account, client_msg);
+                                (reply, lobby.drain_outbound())
+                            };
+                            if player != handle.player {
+                                state.sessions().bind_player(&handle.token, player);
+                                session = Some(SessionHandle {
+                                    player,
+                                    ..handle.clone()
+                                });
+                            }
+                            state.broadcast(outbound);
+                            if let Some(reply) = reply.as_ref() {
+                                if !send_server_message(&mut socket, &state, codec, reply).await {
+                                    break;
+                                }
+                            }
+                        }
+                        SocketAction::Reject(rejection) => {
+                            let reply = ServerMessage::MessageRejected(rejection);
+                            if !send_server_message(&mut socket, &state, codec, &reply).await {
+                                break;
+                            }
+                        }
+                        SocketAction::Disconnect { code, reason } => {
+                            info!("session: disconnecting client ({})", reason);
+                            state.metrics().rejected("disconnect", reason.to_string());
+                            let frame = CloseFrame {
+                                code,
+                                reason: reason.into(),
+                            };
+                            let _ = socket.send(Message::Close(Some(frame))).await;
+                            break;
+                        }
+                        SocketAction::Close => {
+                            break;
+                        }
+                        SocketAction::Ignore => {}
+                    },
+                    Err(err) => {
+                        error!("websocket error: {}", err);
+                        break;
+                    }
                 }
-                services::ws::SocketAction::Close => {
+            }
+            broadcast = broadcasts.recv() => match broadcast {
+                Ok(outbound) => {
+                    if !player.is_some_and(|id| outbound.recipients.contains(&id)) {
+                        continue;
+                    }
+                    if !send_server_message(&mut socket, &state, codec, &outbound.message).await {
+                        break;
+                    }
+                }
+                // Whatever was missed may have been a round start or end, so
+                // the client reconnects and resumes to get the room again.
+                Err(RecvError::Lagged(missed)) => {
+                    info!("session: socket missed {} broadcasts, closing it", missed);
+                    state.metrics().socket_lagged(missed);
+                    let frame = CloseFrame {
+                        code: CLOSE_FELL_BEHIND,
+                        reason: "fell behind".into(),
+                    };
+                    let _ = socket.send(Message::Close(Some(frame))).await;
                     break;
                 }
-                services::ws::SocketAction::Ignore => {}
+                Err(RecvError::Closed) => break,
             },
-            Err(err) => {
-                error!("websocket error: {}", err);
+            Ok(()) = shutdown.changed() => {
+                let phase = *shutdown.borrow_and_update();
+                match phase {
+                    ShutdownPhase::Draining { .. } if session.is_some() => {
+                        if let Some(notice) = phase.notice(tokio::time::Instant::now()) {
+                            if !send_server_message(&mut socket, &state, codec, &notice).await {
+                                break;
+                            }
+                        }
+                    }
+                    ShutdownPhase::Closing => {
+                        let frame = CloseFrame {
+                            code: CLOSE_GOING_AWAY,
+                            reason: "server shutting down".into(),
+                        };
+                        let _ = socket.send(Message::Close(Some(frame))).await;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            _ = taken_over.notified() => {
+                info!("session: resumed by another connection, closing this one");
+                let frame = CloseFrame {
+                    code: CLOSE_SESSION_TAKEN_OVER,
+                    reason: "session resumed elsewhere".into(),
+                };
+                let _ = socket.send(Message::Close(Some(frame))).await;
                 break;
             }
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > timeouts.idle {
+                    info!("session: dropping idle socket");
+                    break;
+                }
+                if session.is_some() {
+                    heartbeat_seq += 1;
+                    let message = ServerMessage::Heartbeat { seq: heartbeat_seq };
+                    if !send_server_message(&mut socket, &state, codec, &message).await {
+                        break;
+                    }
+                }
+            }
         }
     }
+
+    if let Some(handle) = session {
+        state.sessions().disconnect(&handle, Instant::now());
+    }
+    state.metrics().connection_closed();
+}
+
+fn classify(
+    state: &AppState,
+    limiter: &mut ConnectionLimiter,
+    codec: WireCodec,
+    message: Message,
+    now: Instant,
+) -> SocketAction {
+    let (action, kind) = services::ws::classify_frame(message, state.max_message_bytes(), codec);
+    state.metrics().message_in(kind);
+    match services::ws::admit(limiter, &action, now) {
+        Verdict::Allow => action,
+        Verdict::Reject => match action {
+            SocketAction::Reject(rejection) => {
+                state
+                    .metrics()
+                    .rejected("message", format!("{:?}", rejection));
+                action
+            }
+            _ => {
+                state.metrics().rejected("rate_limit", kind.to_string());
+                SocketAction::Reject(MessageRejection::RateLimited)
+            }
+        },
+        Verdict::Disconnect => SocketAction::Disconnect {
+            code: CLOSE_POLICY_VIOLATION,
+            reason: "rate limit exceeded",
+        },
+    }
 }
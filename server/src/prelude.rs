@@ -1,9 +1,10 @@
 pub use axum::{
     extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::State,
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
-pub use tracing::{error, info};
+pub use tracing::{error, info, warn};
 
 pub use shared::prelude::*;
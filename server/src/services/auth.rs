@@ -0,0 +1,173 @@
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Write};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::prelude::*;
+use crate::services::lobby;
+
+pub const DEFAULT_TOKEN_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const SECRET_LEN: usize = 32;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestAccount {
+    pub account_id: String,
+    pub display_name: String,
+    pub expires_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    Missing,
+    Malformed,
+    BadSignature,
+    Expired,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => write!(f, "no token presented"),
+            Self::Malformed => write!(f, "token is malformed"),
+            Self::BadSignature => write!(f, "token signature does not match"),
+            Self::Expired => write!(f, "token has expired"),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+pub struct TokenSigner {
+    secret: Vec<u8>,
+    ttl: Duration,
+    configured: bool,
+    issued: AtomicU64,
+    entropy: RandomState,
+}
+
+impl TokenSigner {
+    pub fn new(secret: &[u8], ttl: Duration) -> Self {
+        Self {
+            secret: secret.to_vec(),
+            ttl,
+            configured: true,
+            issued: AtomicU64::new(0),
+            entropy: RandomState::new(),
+        }
+    }
+
+    pub fn generate(ttl: Duration) -> Self {
+        let mut secret = [0u8; SECRET_LEN];
+        getrandom::getrandom(&mut secret).expect("the OS random number generator is available");
+        Self {
+            configured: false,
+            ..Self::new(&secret, ttl)
+        }
+    }
+
+    pub fn from_hex(secret: &str, ttl: Duration) -> Option<Self> {
+        let secret = decode_hex(secret).filter(|bytes| !bytes.is_empty())?;
+        Some(Self {
+            configured: false,
+            ..Self::new(&secret, ttl)
+        })
+    }
+
+    // Secrets from the config file stay there; only generated ones are
+    // written to the checkpoint so issued tokens survive a restart. Anyone
+    // who can read the checkpoint can sign tokens, so it is saved owner-only;
+    // set `auth_secret` to keep the key out of it entirely.
+    pub fn checkpoint_secret(&self) -> Option<String> {
+        (!self.configured).then(|| encode_hex(&self.secret))
+    }
+
+    pub fn issue(&self, display_name: &str, now_ms: u64) -> Result<GuestToken, LobbyRejection> {
+        let display_name = lobby::validate_name(display_name)?;
+        let account = GuestAccount {
+            account_id: self.next_account_id(),
+            display_name,
+            expires_ms: now_ms.saturating_add(self.ttl.as_millis() as u64),
+        };
+        let payload = serde_json::to_vec(&account).map_err(|_| LobbyRejection::InvalidName)?;
+        let token = format!(
+            "{}.{}",
+            encode_hex(&payload),
+            encode_hex(&self.sign(&payload))
+        );
+        Ok(GuestToken {
+            token,
+            account_id: account.account_id,
+            display_name: account.display_name,
+            expires_ms: account.expires_ms,
+        })
+    }
+
+    pub fn verify(&self, token: Option<&str>, now_ms: u64) -> Result<GuestAccount, AuthError> {
+        let token = token.map(str::trim).filter(|token| !token.is_empty());
+        let (payload, signature) = token
+            .ok_or(AuthError::Missing)?
+            .split_once('.')
+            .ok_or(AuthError::Malformed)?;
+        let payload = decode_hex(payload).ok_or(AuthError::Malformed)?;
+        let signature = decode_hex(signature).ok_or(AuthError::Malformed)?;
+        let mut mac = self.mac();
+        mac.update(&payload);
+        mac.verify_slice(&signature)
+            .map_err(|_| AuthError::BadSignature)?;
+        let account: GuestAccount =
+            serde_json::from_slice(&payload).map_err(|_| AuthError::Malformed)?;
+        if account.expires_ms <= now_ms {
+            return Err(AuthError::Expired);
+        }
+        Ok(account)
+    }
+
+    fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        let mut mac = self.mac();
+        mac.update(payload);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.secret).expect("hmac accepts keys of any length")
+    }
+
+    fn next_account_id(&self) -> String {
+        let issued = self.issued.fetch_add(1, Ordering::Relaxed);
+        let mut hasher = self.entropy.build_hasher();
+        hasher.write_u64(issued);
+        hasher.write_u64(unix_ms());
+        format!("guest-{:016x}", hasher.finish())
+    }
+}
+
+pub fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{:02x}", byte);
+    }
+    out
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&text[index..index + 2], 16).ok())
+        .collect()
+}
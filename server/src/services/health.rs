@@ -1,7 +1,45 @@
+use std::time::Duration;
+
 use crate::prelude::*;
+use crate::services::lobby::LobbyStats;
+
+pub const LATE_TICKS: u32 = 3;
 
 pub fn health() -> Health {
     Health {
         status: "ok".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        build: option_env!("BUILD_ID").unwrap_or("dev").to_string(),
+        protocol_version: PROTOCOL_VERSION,
+    }
+}
+
+pub fn readiness(
+    last_tick_age: Option<Duration>,
+    tick_period: Option<Duration>,
+    lobby: &LobbyStats,
+) -> Readiness {
+    let scheduler_on_time = match (last_tick_age, tick_period) {
+        (Some(age), Some(period)) => age <= period * LATE_TICKS,
+        _ => false,
+    };
+    let ready = scheduler_on_time && !lobby.draining;
+    let mut health = health();
+    if !ready {
+        health.status = if lobby.draining {
+            "draining".to_string()
+        } else {
+            "scheduler stalled".to_string()
+        };
+    }
+    Readiness {
+        ready,
+        scheduler_on_time,
+        last_tick_ms: last_tick_age.map(|age| age.as_millis() as u64),
+        tick_period_ms: tick_period.map(|period| period.as_millis() as u64),
+        rooms: lobby.rooms,
+        rooms_at_capacity: lobby.rooms_full,
+        draining: lobby.draining,
+        health,
     }
 }
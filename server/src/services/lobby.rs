@@ -0,0 +1,1530 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use shared::chat::{overheard_by_ghost, validate_chat, within_earshot, DEFAULT_CHAT_RANGE};
+use shared::evidence::EvidenceTuning;
+use shared::exorcism::{random_banshee_order, ExorcismTables};
+use shared::hunt::caught_by_ghost;
+
+use crate::prelude::*;
+use crate::services::persistence::{
+    LobbyCheckpoint, MemberCheckpoint, RoomCheckpoint, RoundCheckpoint,
+};
+use crate::services::profiles::{ProfileBook, RoundRecord};
+use crate::services::referee::{EvidenceMiss, Referee};
+use crate::services::simulation::MatchSimulation;
+
+pub const MAX_PLAYERS_PER_ROOM: usize = 5;
+pub const MAX_SPECTATORS_PER_ROOM: usize = 4;
+pub const MAX_ROOMS: usize = 64;
+pub const QUICK_PLAY_INVESTIGATORS: usize = 3;
+pub const QUICK_PLAY_COUNTDOWN_MS: u64 = 10_000;
+pub const EMPTY_ROOM_TTL_MS: u64 = 60_000;
+const QUICK_PLAY_ROOM_NAME: &str = "Quick Play";
+const QUEUE_REPORT_INTERVAL: f32 = 1.0;
+const QUEUE_WAIT_SMOOTHING: f32 = 0.3;
+const MAX_NAME_LEN: usize = 24;
+const ROOM_CODE_LEN: usize = 4;
+const ROOM_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+
+#[derive(Debug, Clone)]
+pub struct LobbySettings {
+    pub max_rooms: usize,
+    pub max_players_per_room: usize,
+    pub max_spectators_per_room: usize,
+    pub chat_range: f32,
+    pub empty_room_ttl_ms: u64,
+    pub quick_play: QuickPlaySettings,
+    pub evidence: EvidenceTuning,
+    pub exorcism: ExorcismTables,
+    pub hunt: HuntTuning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuickPlaySettings {
+    pub investigators: usize,
+    pub countdown_ms: u64,
+}
+
+impl Default for QuickPlaySettings {
+    fn default() -> Self {
+        Self {
+            investigators: QUICK_PLAY_INVESTIGATORS,
+            countdown_ms: QUICK_PLAY_COUNTDOWN_MS,
+        }
+    }
+}
+
+impl Default for LobbySettings {
+    fn default() -> Self {
+        Self {
+            max_rooms: MAX_ROOMS,
+            max_players_per_room: MAX_PLAYERS_PER_ROOM,
+            max_spectators_per_room: MAX_SPECTATORS_PER_ROOM,
+            chat_range: DEFAULT_CHAT_RANGE,
+            empty_room_ttl_ms: EMPTY_ROOM_TTL_MS,
+            quick_play: QuickPlaySettings::default(),
+            evidence: EvidenceTuning::default(),
+            exorcism: ExorcismTables::default(),
+            hunt: HuntTuning::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LobbyStats {
+    pub rooms: usize,
+    pub rooms_full: usize,
+    pub rounds: usize,
+    pub draining: bool,
+    pub ghosts: usize,
+    pub investigators: usize,
+    pub spectators: usize,
+    pub unassigned: usize,
+    pub queued: usize,
+    pub violations: u64,
+    pub evidence_wrong_ghost: u64,
+    pub evidence_out_of_range: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Outbound {
+    pub recipients: Vec<PlayerId>,
+    pub message: ServerMessage,
+}
+
+pub struct Member {
+    pub display_name: String,
+    pub account: Option<String>,
+    pub role: Option<Role>,
+}
+
+struct QueueEntry {
+    id: PlayerId,
+    display_name: String,
+    account: Option<String>,
+    role: Option<Role>,
+    layout: HouseLayoutKind,
+    joined: f32,
+}
+
+pub struct Room {
+    code: String,
+    name: String,
+    layout: HouseLayoutKind,
+    ghost_type: GhostType,
+    max_players: usize,
+    max_spectators: usize,
+    members: BTreeMap<PlayerId, Member>,
+    game: Option<MatchSimulation>,
+    referee: Option<Referee>,
+    hunt: HuntPhase,
+    eliminated: Vec<PlayerId>,
+    quick_play: bool,
+    countdown: Option<f32>,
+    empty_for: f32,
+}
+
+impl Room {
+    fn new(code: String, name: String, max_players: usize, max_spectators: usize) -> Self {
+        Self {
+            code,
+            name,
+            max_players,
+            max_spectators,
+            layout: HouseLayoutKind::TwoRoom,
+            ghost_type: GhostType::Spirit,
+            members: BTreeMap::new(),
+            game: None,
+            referee: None,
+            hunt: HuntPhase::Cooldown(0.0),
+            eliminated: Vec::new(),
+            quick_play: false,
+            countdown: None,
+            empty_for: 0.0,
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn ghost_type(&self) -> GhostType {
+        self.ghost_type
+    }
+
+    pub fn member(&self, id: PlayerId) -> Option<&Member> {
+        self.members.get(&id)
+    }
+
+    pub fn game(&self) -> Option<&MatchSimulation> {
+        self.game.as_ref()
+    }
+
+    pub fn referee(&self) -> Option<&Referee> {
+        self.referee.as_ref()
+    }
+
+    // Seconds until a quick-play room starts its round on its own.
+    pub fn countdown(&self) -> Option<f32> {
+        self.countdown
+    }
+
+    pub fn in_round(&self) -> bool {
+        self.game.is_some()
+    }
+
+    pub fn hunt(&self) -> HuntPhase {
+        self.hunt
+    }
+
+    // Investigators caught by a hunt this round. They watch as spectators
+    // but are not told the ghost type before the round ends.
+    pub fn eliminated(&self) -> &[PlayerId] {
+        &self.eliminated
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.in_round() && !self.is_full()
+    }
+
+    // Spectators have their own seats and never take a player's place.
+    pub fn player_count(&self) -> usize {
+        self.members.len() - self.spectator_count()
+    }
+
+    // Caught investigators watch from their player seat, not a spectator one.
+    pub fn spectator_count(&self) -> usize {
+        self.members
+            .iter()
+            .filter(|(id, member)| {
+                member.role == Some(Role::Spectator) && !self.eliminated.contains(id)
+            })
+            .count()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.player_count() >= self.max_players
+    }
+
+    pub fn role_count(&self, role: Role) -> usize {
+        self.members
+            .values()
+            .filter(|member| member.role == Some(role))
+            .count()
+    }
+
+    pub fn roles_ready(&self) -> bool {
+        self.role_count(Role::Ghost) == 1
+            && self.role_count(Role::Investigator) >= 1
+            && self.members.values().all(|member| member.role.is_some())
+    }
+
+    pub fn info(&self) -> RoomInfo {
+        RoomInfo {
+            code: self.code.clone(),
+            name: self.name.clone(),
+            players: self.player_count(),
+            max_players: self.max_players,
+            spectators: self.spectator_count(),
+            has_ghost: self.role_count(Role::Ghost) > 0,
+            in_round: self.in_round(),
+        }
+    }
+
+    pub fn state(&self) -> RoomState {
+        RoomState {
+            code: self.code.clone(),
+            name: self.name.clone(),
+            layout: self.layout,
+            members: self
+                .members
+                .iter()
+                .map(|(id, member)| RoomMember {
+                    id: *id,
+                    display_name: member.display_name.clone(),
+                    role: member.role,
+                })
+                .collect(),
+            in_round: self.in_round(),
+        }
+    }
+
+    fn recipients(&self) -> Vec<PlayerId> {
+        self.members.keys().copied().collect()
+    }
+
+    fn recipients_with(&self, role: Role) -> Vec<PlayerId> {
+        self.members
+            .iter()
+            .filter(|(_, member)| member.role == Some(role))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    // In a round, team chat reaches investigators within earshot and every
+    // spectator; the ghost overhears it when standing in the speaker's room.
+    fn chat_listeners(
+        &self,
+        speaker: PlayerId,
+        channel: ChatChannel,
+        range: f32,
+    ) -> (Vec<PlayerId>, Vec<PlayerId>) {
+        let from = self.game.as_ref().and_then(|game| game.player(speaker));
+        let (Some(game), Some(from), ChatChannel::Team) = (self.game.as_ref(), from, channel)
+        else {
+            let readers = self
+                .members
+                .iter()
+                .filter(|(_, member)| channel.readable_by(member.role))
+                .map(|(id, _)| *id)
+                .collect();
+            return (readers, Vec::new());
+        };
+        let layout = game.layout();
+        let radio = from.equipment == Equipment::Radio;
+        let mut listeners = Vec::new();
+        let mut overhearing = Vec::new();
+        for (id, member) in &self.members {
+            let at = game.player(*id).map(|player| player.position);
+            match (member.role, at) {
+                (Some(Role::Spectator), _) => listeners.push(*id),
+                (Some(Role::Investigator), Some(at))
+                    if within_earshot(layout, range, from.position, at, radio) =>
+                {
+                    listeners.push(*id)
+                }
+                (Some(Role::Ghost), Some(at)) if overheard_by_ghost(layout, from.position, at) => {
+                    overhearing.push(*id)
+                }
+                _ => {}
+            }
+        }
+        (listeners, overhearing)
+    }
+
+    // Caught investigators leave the simulation and watch the rest of the
+    // round as spectators.
+    fn catch_investigators(&mut self, tuning: &HuntTuning) -> Vec<PlayerId> {
+        let Some(game) = self.game.as_mut() else {
+            return Vec::new();
+        };
+        let Some(ghost) = game.ghost_position() else {
+            return Vec::new();
+        };
+        let caught: Vec<PlayerId> = self
+            .members
+            .iter()
+            .filter(|(id, member)| {
+                member.role == Some(Role::Investigator)
+                    && game
+                        .player(**id)
+                        .is_some_and(|player| caught_by_ghost(ghost, player.position, tuning))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &caught {
+            game.remove_player(*id);
+            if let Some(member) = self.members.get_mut(id) {
+                member.role = Some(Role::Spectator);
+            }
+        }
+        self.eliminated.extend(&caught);
+        caught
+    }
+
+    fn spectator_view(&self) -> Option<ServerMessage> {
+        let game = self.game.as_ref()?;
+        Some(ServerMessage::RoundStarted {
+            layout: self.layout,
+            spawn: game.spawn_point(Role::Spectator),
+            banshee_order: self.banshee_order(),
+        })
+    }
+
+    fn banshee_order(&self) -> Vec<u8> {
+        self.referee
+            .as_ref()
+            .map(|referee| referee.banshee_order().to_vec())
+            .unwrap_or_default()
+    }
+}
+
+pub struct Lobby {
+    rooms: BTreeMap<String, Room>,
+    player_rooms: HashMap<PlayerId, String>,
+    next_player_id: PlayerId,
+    code_seed: u64,
+    outbox: Vec<Outbound>,
+    violations: HashMap<PlayerId, u32>,
+    violations_total: u64,
+    evidence_misses: HashMap<EvidenceMiss, u64>,
+    settings: LobbySettings,
+    profiles: ProfileBook,
+    draining: bool,
+    queue: Vec<QueueEntry>,
+    queue_clock: f32,
+    queue_wait: Option<f32>,
+    queue_report: f32,
+}
+
+impl Default for Lobby {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lobby {
+    pub fn new() -> Self {
+        Self::with_settings(LobbySettings::default())
+    }
+
+    pub fn with_settings(settings: LobbySettings) -> Self {
+        let code_seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or(0x9e37_79b9_7f4a_7c15);
+        Self {
+            rooms: BTreeMap::new(),
+            player_rooms: HashMap::new(),
+            next_player_id: 1,
+            code_seed: code_seed | 1,
+            outbox: Vec::new(),
+            violations: HashMap::new(),
+            violations_total: 0,
+            evidence_misses: HashMap::new(),
+            settings,
+            profiles: ProfileBook::new(),
+            draining: false,
+            queue: Vec::new(),
+            queue_clock: 0.0,
+            queue_wait: None,
+            queue_report: 0.0,
+        }
+    }
+
+    pub fn restore(settings: LobbySettings, saved: &LobbyCheckpoint) -> Self {
+        let mut lobby = Self::with_settings(settings);
+        lobby.next_player_id = saved.next_player_id;
+        lobby.profiles = ProfileBook::restore(&saved.profiles);
+        for saved_room in &saved.rooms {
+            let mut room = Room::new(
+                saved_room.code.clone(),
+                saved_room.name.clone(),
+                saved_room.max_players,
+                lobby.settings.max_spectators_per_room,
+            );
+            room.layout = saved_room.layout;
+            room.ghost_type = saved_room.ghost_type;
+            for member in &saved_room.members {
+                room.members.insert(
+                    member.id,
+                    Member {
+                        display_name: member.display_name.clone(),
+                        account: member.account.clone(),
+                        role: member.role,
+                    },
+                );
+                lobby.player_rooms.insert(member.id, room.code.clone());
+                lobby.next_player_id = lobby.next_player_id.max(member.id + 1);
+            }
+            if let Some(round) = &saved_room.round {
+                room.game = Some(MatchSimulation::restore(
+                    room.layout,
+                    round.tick,
+                    round.elapsed,
+                    &round.players,
+                ));
+                let mut referee = Referee::with_rules(
+                    room.ghost_type,
+                    lobby.settings.evidence.clone(),
+                    lobby.settings.exorcism.clone(),
+                )
+                .with_banshee_order(round.banshee_order.clone());
+                referee.restore(&round.investigations);
+                room.referee = Some(referee);
+                room.hunt = HuntPhase::new(&lobby.settings.hunt);
+                room.eliminated = round.eliminated.clone();
+            }
+            lobby.rooms.insert(room.code.clone(), room);
+        }
+        info!(
+            "lobby: restored {} rooms ({} rounds)",
+            lobby.rooms.len(),
+            lobby.active_rounds()
+        );
+        lobby
+    }
+
+    pub fn checkpoint(&self) -> LobbyCheckpoint {
+        LobbyCheckpoint {
+            next_player_id: self.next_player_id,
+            rooms: self
+                .rooms
+                .values()
+                .map(|room| RoomCheckpoint {
+                    code: room.code.clone(),
+                    name: room.name.clone(),
+                    layout: room.layout,
+                    ghost_type: room.ghost_type,
+                    max_players: room.max_players,
+                    members: room
+                        .members
+                        .iter()
+                        .map(|(id, member)| MemberCheckpoint {
+                            id: *id,
+                            display_name: member.display_name.clone(),
+                            account: member.account.clone(),
+                            role: member.role,
+                        })
+                        .collect(),
+                    round: room.game.as_ref().map(|game| RoundCheckpoint {
+                        tick: game.tick(),
+                        elapsed: game.server_time(),
+                        players: game.checkpoint(),
+                        investigations: room
+                            .referee
+                            .as_ref()
+                            .map(Referee::checkpoint)
+                            .unwrap_or_default(),
+                        eliminated: room.eliminated.clone(),
+                        banshee_order: room.banshee_order(),
+                    }),
+                })
+                .collect(),
+            profiles: self.profiles.checkpoint(),
+        }
+    }
+
+    pub fn profile(&self, key: &str) -> Option<&PlayerProfile> {
+        self.profiles.get(key)
+    }
+
+    pub fn players(&self) -> Vec<PlayerId> {
+        self.player_rooms.keys().copied().collect()
+    }
+
+    pub fn room(&self, code: &str) -> Option<&Room> {
+        self.rooms.get(&normalize_code(code))
+    }
+
+    pub fn room_of(&self, player: PlayerId) -> Option<&Room> {
+        self.player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get(code))
+    }
+
+    pub fn open_rooms(&self) -> Vec<RoomInfo> {
+        self.rooms
+            .values()
+            .filter(|room| !self.draining && room.is_open())
+            .map(Room::info)
+            .collect()
+    }
+
+    pub fn create_room(&mut self, name: &str) -> Result<RoomInfo, LobbyRejection> {
+        if self.draining {
+            return Err(LobbyRejection::ShuttingDown);
+        }
+        let name = validate_name(name)?;
+        if self.rooms.len() >= self.settings.max_rooms {
+            return Err(LobbyRejection::TooManyRooms);
+        }
+        let code = loop {
+            let code = self.next_room_code();
+            if !self.rooms.contains_key(&code) {
+                break code;
+            }
+        };
+        let room = Room::new(
+            code.clone(),
+            name,
+            self.settings.max_players_per_room,
+            self.settings.max_spectators_per_room,
+        );
+        let info = room.info();
+        self.rooms.insert(code, room);
+        info!("lobby: created room {}", info.code);
+        Ok(info)
+    }
+
+    pub fn join(&mut self, code: &str, display_name: &str) -> Result<PlayerId, LobbyRejection> {
+        self.join_as(code, display_name, None)
+    }
+
+    pub fn join_as(
+        &mut self,
+        code: &str,
+        display_name: &str,
+        account: Option<&str>,
+    ) -> Result<PlayerId, LobbyRejection> {
+        if self.draining {
+            return Err(LobbyRejection::ShuttingDown);
+        }
+        let display_name = validate_name(display_name)?;
+        let code = normalize_code(code);
+        let room = self
+            .rooms
+            .get_mut(&code)
+            .ok_or(LobbyRejection::RoomNotFound)?;
+        if room.in_round() {
+            return Err(LobbyRejection::RoundInProgress);
+        }
+        if room.is_full() {
+            return Err(LobbyRejection::RoomFull);
+        }
+        let id = self.next_player_id;
+        self.next_player_id += 1;
+        room.members.insert(
+            id,
+            Member {
+                display_name,
+                account: account.map(str::to_string),
+                role: None,
+            },
+        );
+        self.player_rooms.insert(id, code.clone());
+        self.queue_room_update(&code);
+        Ok(id)
+    }
+
+    pub fn spectate(&mut self, code: &str, display_name: &str) -> Result<PlayerId, LobbyRejection> {
+        self.spectate_as(code, display_name, None)
+    }
+
+    // Unlike `join_as`, spectators may walk into a round that is already
+    // running; they get the round start right away so they can watch.
+    pub fn spectate_as(
+        &mut self,
+        code: &str,
+        display_name: &str,
+        account: Option<&str>,
+    ) -> Result<PlayerId, LobbyRejection> {
+        if self.draining {
+            return Err(LobbyRejection::ShuttingDown);
+        }
+        let display_name = validate_name(display_name)?;
+        let code = normalize_code(code);
+        let room = self
+            .rooms
+            .get_mut(&code)
+            .ok_or(LobbyRejection::RoomNotFound)?;
+        if room.spectator_count() >= room.max_spectators {
+            return Err(LobbyRejection::RoomFull);
+        }
+        let id = self.next_player_id;
+        self.next_player_id += 1;
+        room.members.insert(
+            id,
+            Member {
+                display_name,
+                account: account.map(str::to_string),
+                role: Some(Role::Spectator),
+            },
+        );
+        self.player_rooms.insert(id, code.clone());
+        self.queue_room_update(&code);
+        if let Some(message) = self.rooms.get(&code).and_then(Room::spectator_view) {
+            self.outbox.push(Outbound {
+                recipients: vec![id],
+                message,
+            });
+        }
+        Ok(id)
+    }
+
+    pub fn quick_play(
+        &mut self,
+        display_name: &str,
+        role: Option<Role>,
+        layout: HouseLayoutKind,
+    ) -> Result<PlayerId, LobbyRejection> {
+        self.quick_play_as(display_name, role, layout, None)
+    }
+
+    // Queued players get their id right away so matchmaking can reach them
+    // through the outbox before they sit in a room.
+    pub fn quick_play_as(
+        &mut self,
+        display_name: &str,
+        role: Option<Role>,
+        layout: HouseLayoutKind,
+        account: Option<&str>,
+    ) -> Result<PlayerId, LobbyRejection> {
+        if self.draining {
+            return Err(LobbyRejection::ShuttingDown);
+        }
+        let display_name = validate_name(display_name)?;
+        if role == Some(Role::Spectator) {
+            return Err(LobbyRejection::InvalidRole);
+        }
+        let id = self.next_player_id;
+        self.next_player_id += 1;
+        self.queue.push(QueueEntry {
+            id,
+            display_name,
+            account: account.map(str::to_string),
+            role,
+            layout,
+            joined: self.queue_clock,
+        });
+        Ok(id)
+    }
+
+    pub fn leave_queue(&mut self, player: PlayerId) -> bool {
+        let before = self.queue.len();
+        self.queue.retain(|entry| entry.id != player);
+        self.queue.len() != before
+    }
+
+    pub fn is_queued(&self, player: PlayerId) -> bool {
+        self.queue.iter().any(|entry| entry.id == player)
+    }
+
+    pub fn queue_status(&self, player: PlayerId) -> Option<QueueStatus> {
+        let entry = self.queue.iter().find(|entry| entry.id == player)?;
+        let same_layout = || {
+            self.queue
+                .iter()
+                .filter(|other| other.layout == entry.layout)
+        };
+        let position = same_layout().take_while(|other| other.id != player).count() + 1;
+        let waited = self.queue_clock - entry.joined;
+        Some(QueueStatus {
+            position,
+            waiting: same_layout().count(),
+            estimated_wait_ms: self.queue_wait.map(|wait| seconds_to_ms(wait - waited)),
+        })
+    }
+
+    pub fn leave(&mut self, player: PlayerId) {
+        self.leave_queue(player);
+        let Some(code) = self.player_rooms.remove(&player) else {
+            return;
+        };
+        let Some(room) = self.rooms.get_mut(&code) else {
+            return;
+        };
+        room.members.remove(&player);
+        self.violations.remove(&player);
+        if let Some(game) = room.game.as_mut() {
+            game.remove_player(player);
+        }
+        if room.members.is_empty() {
+            self.rooms.remove(&code);
+            info!("lobby: closed empty room {}", code);
+        } else {
+            self.queue_room_update(&code);
+        }
+    }
+
+    pub fn choose_role(&mut self, player: PlayerId, role: Role) -> Result<(), LobbyRejection> {
+        let code = self.lobby_room_code(player)?;
+        let room = self.rooms.get_mut(&code).ok_or(LobbyRejection::NotInRoom)?;
+        let ghost_taken = room
+            .members
+            .iter()
+            .any(|(id, member)| *id != player && member.role == Some(Role::Ghost));
+        if role == Role::Ghost && ghost_taken {
+            return Err(LobbyRejection::GhostTaken);
+        }
+        let current = room.members.get(&player).and_then(|member| member.role);
+        let seat_needed = match role {
+            Role::Spectator => {
+                current != Some(Role::Spectator) && room.spectator_count() >= room.max_spectators
+            }
+            _ => current == Some(Role::Spectator) && room.is_full(),
+        };
+        if seat_needed {
+            return Err(LobbyRejection::RoomFull);
+        }
+        if let Some(member) = room.members.get_mut(&player) {
+            member.role = Some(role);
+        }
+        self.queue_room_update(&code);
+        Ok(())
+    }
+
+    pub fn choose_ghost_type(
+        &mut self,
+        player: PlayerId,
+        ghost_type: GhostType,
+    ) -> Result<(), LobbyRejection> {
+        let room = self.ghost_room_mut(player)?;
+        room.ghost_type = ghost_type;
+        Ok(())
+    }
+
+    pub fn choose_layout(
+        &mut self,
+        player: PlayerId,
+        layout: HouseLayoutKind,
+    ) -> Result<(), LobbyRejection> {
+        let room = self.ghost_room_mut(player)?;
+        room.layout = layout;
+        let code = room.code.clone();
+        self.queue_room_update(&code);
+        Ok(())
+    }
+
+    pub fn start_round(&mut self, player: PlayerId) -> Result<(), LobbyRejection> {
+        if self.draining {
+            return Err(LobbyRejection::ShuttingDown);
+        }
+        let code = self.lobby_room_code(player)?;
+        self.begin_round(&code)
+    }
+
+    fn begin_round(&mut self, code: &str) -> Result<(), LobbyRejection> {
+        let room = self.rooms.get_mut(code).ok_or(LobbyRejection::NotInRoom)?;
+        if !room.roles_ready() {
+            return Err(LobbyRejection::RolesIncomplete);
+        }
+        // Once started, a quick-play room behaves like any other room.
+        room.quick_play = false;
+        room.countdown = None;
+        let mut game = MatchSimulation::new(room.layout);
+        let (team_starts, ghost_start) = game
+            .layout()
+            .random_team_start_positions(room.role_count(Role::Investigator));
+        let mut team_starts = team_starts.into_iter();
+        let anchors = game.layout().exorcism.banshee_anchors.len();
+        let banshee_order = random_banshee_order(
+            anchors,
+            self.settings.exorcism.banshee.sequence_len_for(anchors),
+        );
+        room.hunt = HuntPhase::new(&self.settings.hunt);
+        room.eliminated.clear();
+        for (id, member) in &room.members {
+            let Some(role) = member.role else {
+                continue;
+            };
+            let spawn = match role {
+                Role::Spectator => game.spawn_point(role),
+                Role::Ghost => game.add_player_at(*id, role, ghost_start),
+                Role::Investigator => match team_starts.next() {
+                    Some(start) => game.add_player_at(*id, role, start),
+                    None => game.add_player(*id, role),
+                },
+            };
+            self.outbox.push(Outbound {
+                recipients: vec![*id],
+                message: ServerMessage::RoundStarted {
+                    layout: room.layout,
+                    spawn,
+                    banshee_order: banshee_order.clone(),
+                },
+            });
+            if role == Role::Ghost {
+                self.outbox.push(Outbound {
+                    recipients: vec![*id],
+                    message: hunt_message(room.hunt),
+                });
+            }
+        }
+        room.game = Some(game);
+        room.referee = Some(
+            Referee::with_rules(
+                room.ghost_type,
+                self.settings.evidence.clone(),
+                self.settings.exorcism.clone(),
+            )
+            .with_banshee_order(banshee_order),
+        );
+        info!("lobby: round started in room {}", code);
+        self.queue_room_update(code);
+        Ok(())
+    }
+
+    pub fn rejoin_round(&mut self, player: PlayerId) -> Option<ServerMessage> {
+        let code = self.player_rooms.get(&player)?;
+        let room = self.rooms.get_mut(code)?;
+        if room.member(player)?.role == Some(Role::Spectator) {
+            return room.spectator_view();
+        }
+        let spawn = room.game.as_mut()?.rejoin(player)?;
+        Some(ServerMessage::RoundStarted {
+            layout: room.layout,
+            spawn,
+            banshee_order: room.banshee_order(),
+        })
+    }
+
+    pub fn apply_input(&mut self, player: PlayerId, command: InputCommand) -> bool {
+        let Some(code) = self.player_rooms.get(&player) else {
+            return false;
+        };
+        let Some(game) = self.rooms.get_mut(code).and_then(|room| room.game.as_mut()) else {
+            return false;
+        };
+        let applied = game.apply_input(player, command);
+        for (id, reason) in game.take_violations() {
+            self.flag(id, reason);
+        }
+        applied
+    }
+
+    pub fn journal_guess(
+        &mut self,
+        player: PlayerId,
+        ghost_type: GhostType,
+    ) -> Result<ServerMessage, ClaimRejection> {
+        self.judge(player, |game, referee| {
+            referee.guess(game, player, ghost_type)
+        })
+    }
+
+    pub fn journal_confirm(&mut self, player: PlayerId) -> Result<ServerMessage, ClaimRejection> {
+        self.judge(player, |game, referee| referee.confirm(game, player))
+    }
+
+    pub fn claim_evidence(
+        &mut self,
+        player: PlayerId,
+        kind: EvidenceKind,
+    ) -> Result<ServerMessage, ClaimRejection> {
+        let message = ServerMessage::Evidence(EvidenceEvent::Collected(kind));
+        let claimed = self.judge(player, |game, referee| {
+            referee.claim_evidence(game, player, kind)
+        });
+        let misses = self
+            .player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get_mut(code))
+            .and_then(|room| room.referee.as_mut())
+            .map(Referee::take_misses)
+            .unwrap_or_default();
+        for miss in misses {
+            *self.evidence_misses.entry(miss).or_insert(0) += 1;
+        }
+        claimed?;
+        // The journal is shared: teammates and spectators log it too.
+        if let Some(room) = self.room_of(player) {
+            let recipients: Vec<PlayerId> = room
+                .members
+                .iter()
+                .filter(|(id, member)| {
+                    **id != player
+                        && matches!(member.role, Some(Role::Investigator | Role::Spectator))
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            if !recipients.is_empty() {
+                self.outbox.push(Outbound {
+                    recipients,
+                    message: message.clone(),
+                });
+            }
+        }
+        Ok(message)
+    }
+
+    pub fn use_equipment(
+        &mut self,
+        player: PlayerId,
+    ) -> Result<Option<ServerMessage>, ClaimRejection> {
+        self.judge(player, |game, referee| referee.use_equipment(game, player))
+    }
+
+    pub fn press_banshee_node(
+        &mut self,
+        player: PlayerId,
+        node: u8,
+    ) -> Result<ServerMessage, ClaimRejection> {
+        self.judge(player, |game, referee| {
+            referee
+                .press_banshee_node(game, player, node)
+                .map(ServerMessage::Exorcism)
+        })
+    }
+
+    pub fn interact(&mut self, player: PlayerId) -> Result<ServerMessage, ClaimRejection> {
+        let reply = self.judge(player, |game, referee| {
+            referee.interact(game, player).map(ServerMessage::Exorcism)
+        })?;
+        let failed = self
+            .room_of(player)
+            .and_then(|room| Some((room.code.clone(), room.referee()?.failed_exorcism()?)));
+        if let Some((code, summary)) = failed {
+            self.end_round(&code, summary);
+        }
+        Ok(reply)
+    }
+
+    pub fn claim_exorcism(&mut self, player: PlayerId) -> Result<(), ClaimRejection> {
+        let summary = self.judge(player, |game, referee| referee.claim_exorcism(game, player))?;
+        let Some(code) = self.player_rooms.get(&player).cloned() else {
+            return Err(ClaimRejection::NotInRound);
+        };
+        self.end_round(&code, summary);
+        Ok(())
+    }
+
+    // Only the ghost hunts, and only once the cooldown has run out.
+    pub fn start_hunt(&mut self, player: PlayerId) -> Result<(), ClaimRejection> {
+        let room = self
+            .player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get_mut(code))
+            .filter(|room| room.in_round())
+            .ok_or(ClaimRejection::NotInRound)?;
+        if room.member(player).and_then(|member| member.role) != Some(Role::Ghost) {
+            return Err(ClaimRejection::WrongRole);
+        }
+        if !room.hunt.trigger(&self.settings.hunt) {
+            return Err(ClaimRejection::HuntNotReady);
+        }
+        self.outbox.push(Outbound {
+            recipients: room.recipients(),
+            message: hunt_message(room.hunt),
+        });
+        info!("lobby: hunt started in room {}", room.code);
+        Ok(())
+    }
+
+    fn end_round(&mut self, code: &str, summary: RoundSummary) {
+        if let Some(room) = self.rooms.get_mut(code) {
+            // Caught investigators rejoin the team before the results are
+            // recorded, so the round counts for them too.
+            for id in room.eliminated.drain(..) {
+                if let Some(member) = room.members.get_mut(&id) {
+                    member.role = Some(Role::Investigator);
+                }
+            }
+            let seconds = room.game.as_ref().map_or(0.0, MatchSimulation::server_time);
+            for member in room.members.values() {
+                let Some(role) = member.role else {
+                    continue;
+                };
+                self.profiles.record(RoundRecord {
+                    display_name: &member.display_name,
+                    account: member.account.as_deref(),
+                    role,
+                    ghost_type: summary.ghost_type,
+                    layout: room.layout,
+                    outcome: summary.outcome,
+                    seconds,
+                });
+            }
+            room.game = None;
+            room.referee = None;
+            self.outbox.push(Outbound {
+                recipients: room.recipients(),
+                message: ServerMessage::RoundEnded(summary),
+            });
+        }
+        info!(
+            "lobby: round ended in room {} ({:?})",
+            code, summary.outcome
+        );
+        self.queue_room_update(code);
+    }
+
+    pub fn chat(
+        &mut self,
+        player: PlayerId,
+        channel: ChatChannel,
+        text: &str,
+    ) -> Result<(), ChatRejection> {
+        let room = self
+            .player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get(code))
+            .ok_or(ChatRejection::NotInRoom)?;
+        let member = room.member(player).ok_or(ChatRejection::NotInRoom)?;
+        let text = validate_chat(text)?;
+        if ChatChannel::for_sender(member.role, room.in_round()) != Some(channel) {
+            return Err(ChatRejection::ChannelClosed);
+        }
+        let (recipients, overhearing) =
+            room.chat_listeners(player, channel, self.settings.chat_range);
+        let message = ChatMessage {
+            channel,
+            from: player,
+            display_name: member.display_name.clone(),
+            text,
+        };
+        if !overhearing.is_empty() {
+            self.outbox.push(Outbound {
+                recipients: overhearing,
+                message: ServerMessage::Chat(ChatMessage {
+                    channel: ChatChannel::Overheard,
+                    ..message.clone()
+                }),
+            });
+        }
+        self.outbox.push(Outbound {
+            recipients,
+            message: ServerMessage::Chat(message),
+        });
+        Ok(())
+    }
+
+    pub fn select_equipment(&mut self, player: PlayerId, equipment: Equipment) -> bool {
+        self.player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get_mut(code))
+            .and_then(|room| room.game.as_mut())
+            .is_some_and(|game| game.select_equipment(player, equipment))
+    }
+
+    pub fn place_player(&mut self, player: PlayerId, position: Vec3) -> bool {
+        self.player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get_mut(code))
+            .and_then(|room| room.game.as_mut())
+            .is_some_and(|game| game.place_player(player, position))
+    }
+
+    pub fn violations(&self, player: PlayerId) -> u32 {
+        self.violations.get(&player).copied().unwrap_or(0)
+    }
+
+    pub fn step(&mut self, dt: f32) {
+        self.close_unused_rooms(dt);
+        self.run_quick_play(dt);
+        let mut flagged = Vec::new();
+        let mut changed = Vec::new();
+        let mut ended = Vec::new();
+        for room in self.rooms.values_mut() {
+            let investigators = room.recipients_with(Role::Investigator);
+            let Some(game) = room.game.as_mut() else {
+                continue;
+            };
+            game.step(dt);
+            flagged.extend(game.take_violations());
+            if let Some(referee) = room.referee.as_mut() {
+                referee.step(game, dt);
+                if let Some(summary) = referee.failed_exorcism() {
+                    ended.push((room.code.clone(), summary));
+                    continue;
+                }
+                for (player, level) in referee.emf_readings(game, &investigators) {
+                    self.outbox.push(Outbound {
+                        recipients: vec![player],
+                        message: ServerMessage::Evidence(EvidenceEvent::EmfLevel { level }),
+                    });
+                }
+            }
+            if room.hunt.advance(dt, &self.settings.hunt) {
+                self.outbox.push(Outbound {
+                    recipients: room.recipients(),
+                    message: hunt_message(room.hunt),
+                });
+            }
+            if room.hunt.is_hunting() {
+                let caught = room.catch_investigators(&self.settings.hunt);
+                for player in &caught {
+                    info!("lobby: player {} caught in room {}", player, room.code);
+                    self.outbox.push(Outbound {
+                        recipients: room.recipients(),
+                        message: ServerMessage::Eliminated { player: *player },
+                    });
+                }
+                if !caught.is_empty() && room.role_count(Role::Investigator) == 0 {
+                    ended.push((
+                        room.code.clone(),
+                        RoundSummary {
+                            outcome: RoundOutcome::InvestigatorsDead,
+                            ghost_type: room.ghost_type,
+                            guess: None,
+                        },
+                    ));
+                    continue;
+                }
+                if !caught.is_empty() {
+                    changed.push(room.code.clone());
+                }
+            }
+            let Some(game) = room.game.as_ref() else {
+                continue;
+            };
+            let snapshot = game.snapshot();
+            let (eliminated, spectators): (Vec<PlayerId>, Vec<PlayerId>) = room
+                .recipients_with(Role::Spectator)
+                .into_iter()
+                .partition(|id| room.eliminated.contains(id));
+            if !spectators.is_empty() {
+                self.outbox.push(Outbound {
+                    recipients: spectators,
+                    message: ServerMessage::Snapshot(MatchSnapshot {
+                        ghost_type: Some(room.ghost_type),
+                        ..snapshot.clone()
+                    }),
+                });
+            }
+            // Investigators only see the ghost while it hunts them. Caught
+            // investigators keep their view: the ghost type stays hidden
+            // from them until `RoundEnded`.
+            let mut hidden = snapshot.clone();
+            if !room.hunt.is_hunting() {
+                hidden.players.retain(|player| player.role != Role::Ghost);
+            }
+            let mut investigators = room.recipients_with(Role::Investigator);
+            investigators.extend(eliminated);
+            self.outbox.push(Outbound {
+                recipients: investigators,
+                message: ServerMessage::Snapshot(hidden),
+            });
+            self.outbox.push(Outbound {
+                recipients: room.recipients_with(Role::Ghost),
+                message: ServerMessage::Snapshot(snapshot),
+            });
+        }
+        for (player, reason) in flagged {
+            self.flag(player, reason);
+        }
+        for code in changed {
+            self.queue_room_update(&code);
+        }
+        for (code, summary) in ended {
+            self.end_round(&code, summary);
+        }
+    }
+
+    // Players hear about the shutdown from their socket, not the outbox.
+    pub fn begin_shutdown(&mut self) {
+        self.draining = true;
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining
+    }
+
+    pub fn active_rounds(&self) -> usize {
+        self.rooms.values().filter(|room| room.in_round()).count()
+    }
+
+    pub fn stats(&self) -> LobbyStats {
+        let mut stats = LobbyStats {
+            rooms: self.rooms.len(),
+            rooms_full: self.rooms.values().filter(|room| room.is_full()).count(),
+            rounds: self.active_rounds(),
+            draining: self.draining,
+            queued: self.queue.len(),
+            violations: self.violations_total,
+            evidence_wrong_ghost: self.evidence_miss_count(EvidenceMiss::WrongGhost),
+            evidence_out_of_range: self.evidence_miss_count(EvidenceMiss::OutOfRange),
+            ..LobbyStats::default()
+        };
+        for member in self.rooms.values().flat_map(|room| room.members.values()) {
+            match member.role {
+                Some(Role::Ghost) => stats.ghosts += 1,
+                Some(Role::Investigator) => stats.investigators += 1,
+                Some(Role::Spectator) => stats.spectators += 1,
+                None => stats.unassigned += 1,
+            }
+        }
+        stats
+    }
+
+    fn evidence_miss_count(&self, miss: EvidenceMiss) -> u64 {
+        self.evidence_misses.get(&miss).copied().unwrap_or(0)
+    }
+
+    pub fn drain_outbound(&mut self) -> Vec<Outbound> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    // A room nobody joins would hold one of the `max_rooms` slots for good,
+    // so it closes once it has sat empty for `empty_room_ttl_ms`.
+    fn close_unused_rooms(&mut self, dt: f32) {
+        let ttl = self.settings.empty_room_ttl_ms as f32 / 1000.0;
+        self.rooms.retain(|code, room| {
+            if !room.members.is_empty() {
+                room.empty_for = 0.0;
+                return true;
+            }
+            room.empty_for += dt;
+            if room.empty_for < ttl {
+                return true;
+            }
+            info!("lobby: closed unused room {}", code);
+            false
+        });
+    }
+
+    fn run_quick_play(&mut self, dt: f32) {
+        self.queue_clock += dt;
+        if self.draining {
+            return;
+        }
+        let forming: Vec<String> = self
+            .rooms
+            .values()
+            .filter(|room| room.quick_play)
+            .map(|room| room.code.clone())
+            .collect();
+        for code in &forming {
+            self.fill_quick_play_room(code);
+        }
+        self.form_quick_play_rooms();
+        self.tick_countdowns(dt);
+
+        self.queue_report += dt;
+        if self.queue_report >= QUEUE_REPORT_INTERVAL {
+            self.queue_report = 0.0;
+            let reports: Vec<Outbound> = self
+                .queue
+                .iter()
+                .filter_map(|entry| {
+                    Some(Outbound {
+                        recipients: vec![entry.id],
+                        message: ServerMessage::QueueStatus(self.queue_status(entry.id)?),
+                    })
+                })
+                .collect();
+            self.outbox.extend(reports);
+        }
+    }
+
+    // A room only forms once the queue can cover the ghost and at least one
+    // investigator; later arrivals top it up while the countdown runs.
+    fn form_quick_play_rooms(&mut self) {
+        let layouts: Vec<HouseLayoutKind> = self.queue.iter().map(|entry| entry.layout).collect();
+        for layout in layouts {
+            while let Some(ghost) = self.queue_pick(layout, Role::Ghost, None) {
+                if self
+                    .queue_pick(layout, Role::Investigator, Some(ghost))
+                    .is_none()
+                {
+                    break;
+                }
+                let Ok(info) = self.create_room(QUICK_PLAY_ROOM_NAME) else {
+                    return;
+                };
+                if let Some(room) = self.rooms.get_mut(&info.code) {
+                    room.layout = layout;
+                    room.quick_play = true;
+                }
+                self.fill_quick_play_room(&info.code);
+            }
+        }
+    }
+
+    fn fill_quick_play_room(&mut self, code: &str) {
+        let Some(room) = self.rooms.get(code) else {
+            return;
+        };
+        let layout = room.layout;
+        let investigators = room.role_count(Role::Investigator);
+        let mut seats = room.max_players.saturating_sub(room.player_count());
+        let mut seated = false;
+        if room.role_count(Role::Ghost) == 0 && seats > 0 {
+            if let Some(index) = self.queue_pick(layout, Role::Ghost, None) {
+                self.seat_from_queue(code, index, Role::Ghost);
+                seats -= 1;
+                seated = true;
+            }
+        }
+        let mut investigators_open = self
+            .settings
+            .quick_play
+            .investigators
+            .saturating_sub(investigators)
+            .min(seats);
+        while investigators_open > 0 {
+            let Some(index) = self.queue_pick(layout, Role::Investigator, None) else {
+                break;
+            };
+            self.seat_from_queue(code, index, Role::Investigator);
+            investigators_open -= 1;
+            seated = true;
+        }
+        if seated {
+            self.queue_room_update(code);
+        }
+    }
+
+    // Players who asked for the role go first, then anyone without a
+    // preference, oldest entries first.
+    fn queue_pick(
+        &self,
+        layout: HouseLayoutKind,
+        role: Role,
+        skip: Option<usize>,
+    ) -> Option<usize> {
+        let candidates = || {
+            self.queue
+                .iter()
+                .enumerate()
+                .filter(|(index, entry)| Some(*index) != skip && entry.layout == layout)
+        };
+        candidates()
+            .find(|(_, entry)| entry.role == Some(role))
+            .or_else(|| candidates().find(|(_, entry)| entry.role.is_none()))
+            .map(|(index, _)| index)
+    }
+
+    fn seat_from_queue(&mut self, code: &str, index: usize, role: Role) {
+        let entry = self.queue.remove(index);
+        let waited = self.queue_clock - entry.joined;
+        self.queue_wait = Some(match self.queue_wait {
+            Some(wait) => wait + (waited - wait) * QUEUE_WAIT_SMOOTHING,
+            None => waited,
+        });
+        let Some(room) = self.rooms.get_mut(code) else {
+            return;
+        };
+        room.members.insert(
+            entry.id,
+            Member {
+                display_name: entry.display_name,
+                account: entry.account,
+                role: Some(role),
+            },
+        );
+        let countdown = room.countdown;
+        self.player_rooms.insert(entry.id, code.to_string());
+        self.outbox.push(Outbound {
+            recipients: vec![entry.id],
+            message: ServerMessage::RoomJoined {
+                player_id: entry.id,
+                room_code: code.to_string(),
+            },
+        });
+        if let Some(remaining) = countdown {
+            self.outbox.push(Outbound {
+                recipients: vec![entry.id],
+                message: ServerMessage::RoundCountdown {
+                    starts_in_ms: Some(seconds_to_ms(remaining)),
+                },
+            });
+        }
+        info!(
+            "lobby: quick play seated player {} in room {}",
+            entry.id, code
+        );
+    }
+
+    fn tick_countdowns(&mut self, dt: f32) {
+        let countdown = self.settings.quick_play.countdown_ms as f32 / 1000.0;
+        let mut ready = Vec::new();
+        for room in self.rooms.values_mut().filter(|room| room.quick_play) {
+            let starts_in_ms = match (room.countdown, room.roles_ready()) {
+                (None, true) => {
+                    room.countdown = Some(countdown);
+                    Some(seconds_to_ms(countdown))
+                }
+                (Some(_), false) => {
+                    room.countdown = None;
+                    None
+                }
+                (Some(remaining), true) => {
+                    let remaining = remaining - dt;
+                    room.countdown = Some(remaining);
+                    if remaining <= 0.0 {
+                        ready.push(room.code.clone());
+                    }
+                    continue;
+                }
+                (None, false) => continue,
+            };
+            self.outbox.push(Outbound {
+                recipients: room.recipients(),
+                message: ServerMessage::RoundCountdown { starts_in_ms },
+            });
+        }
+        for code in ready {
+            let _ = self.begin_round(&code);
+        }
+    }
+
+    fn lobby_room_code(&self, player: PlayerId) -> Result<String, LobbyRejection> {
+        let code = self
+            .player_rooms
+            .get(&player)
+            .ok_or(LobbyRejection::NotInRoom)?;
+        match self.rooms.get(code) {
+            Some(room) if room.in_round() => Err(LobbyRejection::RoundInProgress),
+            Some(_) => Ok(code.clone()),
+            None => Err(LobbyRejection::NotInRoom),
+        }
+    }
+
+    fn ghost_room_mut(&mut self, player: PlayerId) -> Result<&mut Room, LobbyRejection> {
+        let code = self.lobby_room_code(player)?;
+        let room = self.rooms.get_mut(&code).ok_or(LobbyRejection::NotInRoom)?;
+        match room.members.get(&player) {
+            Some(member) if member.role == Some(Role::Ghost) => Ok(room),
+            _ => Err(LobbyRejection::NotGhost),
+        }
+    }
+
+    fn judge<T>(
+        &mut self,
+        player: PlayerId,
+        rule: impl FnOnce(&MatchSimulation, &mut Referee) -> Result<T, ClaimRejection>,
+    ) -> Result<T, ClaimRejection> {
+        let round = self
+            .player_rooms
+            .get(&player)
+            .and_then(|code| self.rooms.get_mut(code))
+            .and_then(|room| room.game.as_ref().zip(room.referee.as_mut()));
+        let result = match round {
+            Some((game, referee)) => rule(game, referee),
+            None => Err(ClaimRejection::NotInRound),
+        };
+        if let Err(reason) = result {
+            self.flag(player, reason);
+        }
+        result
+    }
+
+    fn flag(&mut self, player: PlayerId, reason: ClaimRejection) {
+        self.violations_total += 1;
+        let count = self.violations.entry(player).or_insert(0);
+        *count += 1;
+        warn!(
+            "anticheat: rejected {:?} from player {} ({} total)",
+            reason, player, count
+        );
+    }
+
+    fn queue_room_update(&mut self, code: &str) {
+        if let Some(room) = self.rooms.get(code) {
+            self.outbox.push(Outbound {
+                recipients: room.recipients(),
+                message: ServerMessage::RoomUpdate(room.state()),
+            });
+        }
+    }
+
+    fn next_room_code(&mut self) -> String {
+        (0..ROOM_CODE_LEN)
+            .map(|_| {
+                self.code_seed ^= self.code_seed << 13;
+                self.code_seed ^= self.code_seed >> 7;
+                self.code_seed ^= self.code_seed << 17;
+                let index = (self.code_seed % ROOM_CODE_ALPHABET.len() as u64) as usize;
+                ROOM_CODE_ALPHABET[index] as char
+            })
+            .collect()
+    }
+}
+
+fn hunt_message(hunt: HuntPhase) -> ServerMessage {
+    ServerMessage::Hunt {
+        active: hunt.is_hunting(),
+        remaining_ms: seconds_to_ms(hunt.remaining()),
+    }
+}
+
+fn seconds_to_ms(seconds: f32) -> u64 {
+    (seconds.max(0.0) * 1000.0).round() as u64
+}
+
+pub fn normalize_code(code: &str) -> String {
+    code.trim().to_ascii_uppercase()
+}
+
+pub fn validate_name(name: &str) -> Result<String, LobbyRejection> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(LobbyRejection::InvalidName);
+    }
+    Ok(name.to_string())
+}
@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::prelude::*;
+use crate::services::lobby::LobbyStats;
+
+pub const TICK_BUCKETS: [f64; 8] = [0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1];
+
+#[derive(Default)]
+pub struct Metrics {
+    connections_active: u64,
+    connections_total: u64,
+    lagged_sockets: u64,
+    missed_broadcasts: u64,
+    messages_in: BTreeMap<&'static str, u64>,
+    messages_out: BTreeMap<&'static str, u64>,
+    rejected: BTreeMap<(&'static str, String), u64>,
+    tick_buckets: [u64; TICK_BUCKETS.len()],
+    tick_count: u64,
+    tick_sum: f64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connection_opened(&mut self) {
+        self.connections_active += 1;
+        self.connections_total += 1;
+    }
+
+    pub fn connection_closed(&mut self) {
+        self.connections_active = self.connections_active.saturating_sub(1);
+    }
+
+    pub fn socket_lagged(&mut self, missed: u64) {
+        self.lagged_sockets += 1;
+        self.missed_broadcasts += missed;
+    }
+
+    pub fn connections_active(&self) -> u64 {
+        self.connections_active
+    }
+
+    pub fn message_in(&mut self, kind: &'static str) {
+        *self.messages_in.entry(kind).or_insert(0) += 1;
+    }
+
+    pub fn message_out(&mut self, message: &ServerMessage) {
+        *self.messages_out.entry(message.kind()).or_insert(0) += 1;
+        match message {
+            ServerMessage::LobbyRejected(rejection) => {
+                self.rejected("lobby", format!("{:?}", rejection));
+            }
+            ServerMessage::ClaimRejected(rejection) => {
+                self.rejected("claim", format!("{:?}", rejection));
+            }
+            _ => {}
+        }
+    }
+
+    pub fn rejected(&mut self, kind: &'static str, reason: String) {
+        *self.rejected.entry((kind, reason)).or_insert(0) += 1;
+    }
+
+    pub fn observe_tick(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, bound) in self.tick_buckets.iter_mut().zip(TICK_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.tick_count += 1;
+        self.tick_sum += seconds;
+    }
+
+    pub fn render(&self, lobby: &LobbyStats, sessions: usize, resumed: u64) -> String {
+        let mut out = String::new();
+        gauge(
+            &mut out,
+            "mmf_ws_connections",
+            "Open websocket connections.",
+            self.connections_active,
+        );
+        counter(
+            &mut out,
+            "mmf_ws_connections_total",
+            "Websocket connections accepted.",
+            self.connections_total,
+        );
+        counter(
+            &mut out,
+            "mmf_ws_lagged_total",
+            "Sockets closed for falling behind the broadcast channel.",
+            self.lagged_sockets,
+        );
+        counter(
+            &mut out,
+            "mmf_ws_missed_broadcasts_total",
+            "Broadcasts lagging sockets never received.",
+            self.missed_broadcasts,
+        );
+        gauge(
+            &mut out,
+            "mmf_sessions",
+            "Live or resumable sessions.",
+            sessions,
+        );
+        counter(
+            &mut out,
+            "mmf_session_resumes_total",
+            "Sessions resumed after a reconnect.",
+            resumed,
+        );
+        gauge(&mut out, "mmf_rooms", "Open rooms.", lobby.rooms);
+        gauge(
+            &mut out,
+            "mmf_rounds",
+            "Rooms with a running round.",
+            lobby.rounds,
+        );
+        gauge(
+            &mut out,
+            "mmf_quick_play_queued",
+            "Players waiting in the quick-play queue.",
+            lobby.queued,
+        );
+
+        header(
+            &mut out,
+            "mmf_players",
+            "Players in rooms by role.",
+            "gauge",
+        );
+        for (role, count) in [
+            ("ghost", lobby.ghosts),
+            ("investigator", lobby.investigators),
+            ("spectator", lobby.spectators),
+            ("none", lobby.unassigned),
+        ] {
+            let _ = writeln!(out, "mmf_players{{role=\"{}\"}} {}", role, count);
+        }
+
+        header(
+            &mut out,
+            "mmf_messages_in_total",
+            "Client messages received by variant.",
+            "counter",
+        );
+        for (kind, count) in &self.messages_in {
+            let _ = writeln!(out, "mmf_messages_in_total{{kind=\"{}\"}} {}", kind, count);
+        }
+        header(
+            &mut out,
+            "mmf_messages_out_total",
+            "Server messages sent by variant.",
+            "counter",
+        );
+        for (kind, count) in &self.messages_out {
+            let _ = writeln!(out, "mmf_messages_out_total{{kind=\"{}\"}} {}", kind, count);
+        }
+
+        header(
+            &mut out,
+            "mmf_rejected_messages_total",
+            "Client messages rejected by the server.",
+            "counter",
+        );
+        for ((kind, reason), count) in &self.rejected {
+            let _ = writeln!(
+                out,
+                "mmf_rejected_messages_total{{kind=\"{}\",reason=\"{}\"}} {}",
+                kind, reason, count
+            );
+        }
+        counter(
+            &mut out,
+            "mmf_anticheat_flags_total",
+            "Claims and inputs flagged by the referee.",
+            lobby.violations,
+        );
+        header(
+            &mut out,
+            "mmf_evidence_misses_total",
+            "Evidence claims the referee turned down, by reason.",
+            "counter",
+        );
+        for (reason, count) in [
+            ("wrong_ghost", lobby.evidence_wrong_ghost),
+            ("out_of_range", lobby.evidence_out_of_range),
+        ] {
+            let _ = writeln!(
+                out,
+                "mmf_evidence_misses_total{{reason=\"{}\"}} {}",
+                reason, count
+            );
+        }
+
+        header(
+            &mut out,
+            "mmf_tick_duration_seconds",
+            "Time spent simulating one server tick.",
+            "histogram",
+        );
+        for (bound, count) in TICK_BUCKETS.iter().zip(self.tick_buckets) {
+            let _ = writeln!(
+                out,
+                "mmf_tick_duration_seconds_bucket{{le=\"{}\"}} {}",
+                bound, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "mmf_tick_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            self.tick_count
+        );
+        let _ = writeln!(out, "mmf_tick_duration_seconds_sum {}", self.tick_sum);
+        let _ = writeln!(out, "mmf_tick_duration_seconds_count {}", self.tick_count);
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    header(out, name, help, "gauge");
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    header(out, name, help, "counter");
+    let _ = writeln!(out, "{} {}", name, value);
+}
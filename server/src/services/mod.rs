@@ -1,2 +1,11 @@
+pub mod auth;
 pub mod health;
+pub mod lobby;
+pub mod metrics;
+pub mod persistence;
+pub mod profiles;
+pub mod ratelimit;
+pub mod referee;
+pub mod session;
+pub mod simulation;
 pub mod ws;
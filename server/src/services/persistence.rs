@@ -0,0 +1,204 @@
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+use crate::services::referee::{OnryoContainment, SpiritVigil};
+
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub version: u32,
+    pub lobby: LobbyCheckpoint,
+    pub sessions: Vec<SessionCheckpoint>,
+    #[serde(default)]
+    pub auth_secret: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LobbyCheckpoint {
+    pub next_player_id: PlayerId,
+    pub rooms: Vec<RoomCheckpoint>,
+    #[serde(default)]
+    pub profiles: Vec<SavedProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedProfile {
+    #[serde(default)]
+    pub key: String,
+    #[serde(flatten)]
+    pub profile: PlayerProfile,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomCheckpoint {
+    pub code: String,
+    pub name: String,
+    pub layout: HouseLayoutKind,
+    pub ghost_type: GhostType,
+    pub max_players: usize,
+    pub members: Vec<MemberCheckpoint>,
+    pub round: Option<RoundCheckpoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberCheckpoint {
+    pub id: PlayerId,
+    pub display_name: String,
+    #[serde(default)]
+    pub account: Option<String>,
+    pub role: Option<Role>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoundCheckpoint {
+    pub tick: u64,
+    pub elapsed: f64,
+    pub players: Vec<PlayerCheckpoint>,
+    pub investigations: Vec<InvestigationCheckpoint>,
+    #[serde(default)]
+    pub eliminated: Vec<PlayerId>,
+    #[serde(default)]
+    pub banshee_order: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerCheckpoint {
+    pub id: PlayerId,
+    pub role: Role,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub last_input_seq: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InvestigationCheckpoint {
+    pub player: PlayerId,
+    pub guess: Option<GhostType>,
+    pub confirmed_at: Option<f64>,
+    pub evidence: Vec<EvidenceKind>,
+    pub banshee_stage: u8,
+    pub last_press: Option<f64>,
+    pub failed_until: f64,
+    #[serde(default)]
+    pub spirit: SpiritVigil,
+    #[serde(default)]
+    pub onryo: OnryoContainment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionCheckpoint {
+    pub token: String,
+    pub player: Option<PlayerId>,
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PersistError {
+    Io { path: PathBuf, reason: String },
+    Corrupt { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, reason } => {
+                write!(f, "cannot access checkpoint {}: {}", path.display(), reason)
+            }
+            Self::Corrupt { path, reason } => {
+                write!(f, "unreadable checkpoint {}: {}", path.display(), reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PersistError {}
+
+#[derive(Debug, Clone)]
+pub struct CheckpointStore {
+    path: PathBuf,
+}
+
+impl CheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn load(&self) -> Result<Option<Checkpoint>, PersistError> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(self.io_error(err)),
+        };
+        let checkpoint: Checkpoint =
+            serde_json::from_str(&text).map_err(|err| PersistError::Corrupt {
+                path: self.path.clone(),
+                reason: err.to_string(),
+            })?;
+        if checkpoint.version != CHECKPOINT_VERSION {
+            return Err(PersistError::Corrupt {
+                path: self.path.clone(),
+                reason: format!("unsupported version {}", checkpoint.version),
+            });
+        }
+        Ok(Some(checkpoint))
+    }
+
+    pub fn save(&self, checkpoint: &Checkpoint) -> Result<(), PersistError> {
+        let text = serde_json::to_string(checkpoint).map_err(|err| PersistError::Corrupt {
+            path: self.path.clone(),
+            reason: err.to_string(),
+        })?;
+        if let Some(parent) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|err| self.io_error(err))?;
+        }
+        // Write beside the real file and rename so a crash mid-write never
+        // leaves a truncated checkpoint behind.
+        let staging = self.sibling("tmp");
+        fs::write(&staging, text).map_err(|err| self.io_error(err))?;
+        owner_only(&staging).map_err(|err| self.io_error(err))?;
+        fs::rename(&staging, &self.path).map_err(|err| self.io_error(err))
+    }
+
+    pub fn quarantine(&self) -> Result<PathBuf, PersistError> {
+        let target = self.sibling("corrupt");
+        fs::rename(&self.path, &target).map_err(|err| self.io_error(err))?;
+        Ok(target)
+    }
+
+    fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(".");
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+
+    fn io_error(&self, err: std::io::Error) -> PersistError {
+        PersistError::Io {
+            path: self.path.clone(),
+            reason: err.to_string(),
+        }
+    }
+}
+
+// The checkpoint can hold the token signing key.
+#[cfg(unix)]
+fn owner_only(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn owner_only(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
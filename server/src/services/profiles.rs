@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use crate::prelude::*;
+use crate::services::persistence::SavedProfile;
+
+pub struct RoundRecord<'a> {
+    pub display_name: &'a str,
+    pub account: Option<&'a str>,
+    pub role: Role,
+    pub ghost_type: GhostType,
+    pub layout: HouseLayoutKind,
+    pub outcome: RoundOutcome,
+    pub seconds: f64,
+}
+
+#[derive(Default)]
+pub struct ProfileBook {
+    profiles: BTreeMap<String, PlayerProfile>,
+}
+
+impl ProfileBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn restore(saved: &[SavedProfile]) -> Self {
+        let mut book = Self::new();
+        for saved in saved {
+            let key = match saved.key.is_empty() {
+                true => profile_key(&saved.profile.display_name),
+                false => saved.key.clone(),
+            };
+            book.profiles.insert(key, saved.profile.clone());
+        }
+        book
+    }
+
+    pub fn checkpoint(&self) -> Vec<SavedProfile> {
+        self.profiles
+            .iter()
+            .map(|(key, profile)| SavedProfile {
+                key: key.clone(),
+                profile: profile.clone(),
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    // Keyed by account id; only players without an account are stored by
+    // their display name.
+    pub fn get(&self, key: &str) -> Option<&PlayerProfile> {
+        self.profiles.get(&profile_key(key))
+    }
+
+    pub fn record(&mut self, round: RoundRecord) {
+        if round.role == Role::Spectator {
+            return;
+        }
+        let key = profile_key(round.account.unwrap_or(round.display_name));
+        let profile = self
+            .profiles
+            .entry(key)
+            .or_insert_with(|| PlayerProfile::new(round.display_name.trim()));
+        if round.account.is_some() {
+            profile.display_name = round.display_name.trim().to_string();
+        }
+        let outcomes = match round.role {
+            Role::Ghost => {
+                profile.games_as_ghost += 1;
+                &mut profile.outcomes_as_ghost
+            }
+            Role::Investigator => {
+                profile.games_as_investigator += 1;
+                &mut profile.outcomes_as_investigator
+            }
+            Role::Spectator => return,
+        };
+        match round.outcome {
+            RoundOutcome::SuccessfulExorcism => outcomes.successful_exorcism += 1,
+            RoundOutcome::WrongGhost => outcomes.wrong_ghost += 1,
+            RoundOutcome::FailedExorcism => outcomes.failed_exorcism += 1,
+            RoundOutcome::InvestigatorsDead => outcomes.investigators_dead += 1,
+        }
+        match round.ghost_type {
+            GhostType::Spirit => profile.ghost_types.spirit += 1,
+            GhostType::Banshee => profile.ghost_types.banshee += 1,
+            GhostType::Onryo => profile.ghost_types.onryo += 1,
+        }
+        profile.favourite_ghost = favourite_ghost(&profile.ghost_types, profile.favourite_ghost);
+
+        if round.role != Role::Investigator || round.outcome != RoundOutcome::SuccessfulExorcism {
+            return;
+        }
+        match profile
+            .fastest_exorcisms
+            .iter_mut()
+            .find(|record| record.puzzle == round.ghost_type && record.layout == round.layout)
+        {
+            Some(record) => record.seconds = record.seconds.min(round.seconds),
+            None => profile.fastest_exorcisms.push(FastestExorcism {
+                puzzle: round.ghost_type,
+                layout: round.layout,
+                seconds: round.seconds,
+            }),
+        }
+    }
+}
+
+pub fn profile_key(display_name: &str) -> String {
+    display_name.trim().to_lowercase()
+}
+
+// Ties keep the current favourite so it does not flip back and forth.
+fn favourite_ghost(tally: &GhostTypeTally, current: Option<GhostType>) -> Option<GhostType> {
+    let mut best = current.filter(|ghost_type| tally.count(*ghost_type) > 0);
+    for ghost_type in [GhostType::Spirit, GhostType::Banshee, GhostType::Onryo] {
+        let count = tally.count(ghost_type);
+        if count > best.map_or(0, |best| tally.count(best)) {
+            best = Some(ghost_type);
+        }
+    }
+    best
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Instant;
+
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+pub const MAX_MESSAGE_BYTES: usize = 16 * 1024;
+pub const FRAME_CEILING: usize = 1024 * 1024;
+pub const CLOSE_POLICY_VIOLATION: u16 = 1008;
+pub const CLOSE_MESSAGE_TOO_BIG: u16 = 1009;
+// Addresses whose bucket has refilled are forgotten once this many are tracked.
+const MAX_TRACKED_ADDRESSES: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageClass {
+    Movement,
+    Interaction,
+    Chat,
+    Control,
+}
+
+impl MessageClass {
+    pub fn of(message: &ClientMessage) -> Self {
+        match message {
+            ClientMessage::Input(_) => Self::Movement,
+            ClientMessage::Chat { .. } => Self::Chat,
+            ClientMessage::Ping
+            | ClientMessage::Hello { .. }
+            | ClientMessage::HeartbeatAck { .. } => Self::Control,
+            _ => Self::Interaction,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Movement => "movement",
+            Self::Interaction => "interaction",
+            Self::Chat => "chat",
+            Self::Control => "control",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BucketLimit {
+    pub per_second: f64,
+    pub burst: f64,
+}
+
+impl BucketLimit {
+    pub const fn new(per_second: f64, burst: f64) -> Self {
+        Self { per_second, burst }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimits {
+    pub movement: BucketLimit,
+    pub interaction: BucketLimit,
+    pub chat: BucketLimit,
+    pub control: BucketLimit,
+    // Every rejected message costs a strike; running out of strikes means the
+    // client is flooding and gets disconnected.
+    pub strikes: BucketLimit,
+    // Guest tokens minted per client address on `/auth/guest`.
+    pub guest_tokens: BucketLimit,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            movement: BucketLimit::new(240.0, 480.0),
+            interaction: BucketLimit::new(20.0, 40.0),
+            chat: BucketLimit::new(2.0, 5.0),
+            control: BucketLimit::new(10.0, 20.0),
+            strikes: BucketLimit::new(2.0, 50.0),
+            guest_tokens: BucketLimit::new(0.05, 5.0),
+        }
+    }
+}
+
+impl RateLimits {
+    pub fn buckets(&self) -> [(&'static str, BucketLimit); 6] {
+        [
+            ("movement", self.movement),
+            ("interaction", self.interaction),
+            ("chat", self.chat),
+            ("control", self.control),
+            ("strikes", self.strikes),
+            ("guest_tokens", self.guest_tokens),
+        ]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    limit: BucketLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(limit: BucketLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst,
+            updated: now,
+        }
+    }
+
+    pub fn take(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.per_second).min(self.limit.burst);
+        self.updated = now;
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+
+    pub fn is_full(&self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens + elapsed * self.limit.per_second >= self.limit.burst
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Allow,
+    Reject,
+    Disconnect,
+}
+
+pub struct ConnectionLimiter {
+    movement: TokenBucket,
+    interaction: TokenBucket,
+    chat: TokenBucket,
+    control: TokenBucket,
+    strikes: TokenBucket,
+}
+
+impl ConnectionLimiter {
+    pub fn new(limits: &RateLimits, now: Instant) -> Self {
+        Self {
+            movement: TokenBucket::new(limits.movement, now),
+            interaction: TokenBucket::new(limits.interaction, now),
+            chat: TokenBucket::new(limits.chat, now),
+            control: TokenBucket::new(limits.control, now),
+            strikes: TokenBucket::new(limits.strikes, now),
+        }
+    }
+
+    pub fn check(&mut self, class: MessageClass, now: Instant) -> Verdict {
+        let bucket = match class {
+            MessageClass::Movement => &mut self.movement,
+            MessageClass::Interaction => &mut self.interaction,
+            MessageClass::Chat => &mut self.chat,
+            MessageClass::Control => &mut self.control,
+        };
+        if bucket.take(now) {
+            return Verdict::Allow;
+        }
+        self.strike(now)
+    }
+
+    pub fn strike(&mut self, now: Instant) -> Verdict {
+        if self.strikes.take(now) {
+            Verdict::Reject
+        } else {
+            Verdict::Disconnect
+        }
+    }
+}
+
+pub struct AddressLimiter {
+    limit: BucketLimit,
+    buckets: HashMap<IpAddr, TokenBucket>,
+}
+
+impl AddressLimiter {
+    pub fn new(limit: BucketLimit) -> Self {
+        Self {
+            limit,
+            buckets: HashMap::new(),
+        }
+    }
+
+    pub fn check(&mut self, address: IpAddr, now: Instant) -> bool {
+        if self.buckets.len() >= MAX_TRACKED_ADDRESSES {
+            self.buckets.retain(|_, bucket| !bucket.is_full(now));
+        }
+        let limit = self.limit;
+        self.buckets
+            .entry(address)
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .take(now)
+    }
+
+    pub fn tracked(&self) -> usize {
+        self.buckets.len()
+    }
+}
@@ -0,0 +1,613 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use shared::evidence::{
+    emf_five_candidate, emf_level, overlap_distance, spiritbox_bearing, spiritbox_is_evidence,
+    spiritbox_reply, EvidenceTuning, SpiritboxReply,
+};
+use shared::exorcism::{
+    banshee_advance, onryo_stack_tick, spirit_progress, ExorcismTables, OnryoConfig, SpiritConfig,
+};
+use shared::map::room_id_in_house;
+
+use crate::prelude::*;
+use crate::services::persistence::InvestigationCheckpoint;
+use crate::services::simulation::{MatchSimulation, SimPlayer};
+
+pub const REACH_TOLERANCE: f32 = 1.0;
+pub const TIMING_TOLERANCE: f64 = 0.3;
+
+// The Spirit vigil as the server sees it, rebuilt every tick from the
+// investigator's simulated position and facing. Room lights are not
+// simulated, so an anchor in a dark room still counts as watched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpiritVigil {
+    pub progress: f32,
+    pub last_seen: Vec<f32>,
+}
+
+// The Onryo containment: which cursed object is carried, where each one
+// lies, and how many stacks carrying them has cost so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OnryoContainment {
+    pub stage: u8,
+    pub stacks: f32,
+    pub carrying: Option<u8>,
+    pub placed: Vec<u8>,
+    pub objects: Vec<Vec3>,
+}
+
+#[derive(Default)]
+struct Investigation {
+    guess: Option<GhostType>,
+    confirmed_at: Option<f64>,
+    evidence: Vec<EvidenceKind>,
+    banshee_stage: u8,
+    last_press: Option<f64>,
+    failed_until: f64,
+    spirit: SpiritVigil,
+    onryo: OnryoContainment,
+    emf_shown: Option<u8>,
+}
+
+// Why an evidence claim failed. Players only ever hear `ImpossibleEvidence`:
+// telling a wrong ghost apart from a bad position would give the type away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvidenceMiss {
+    WrongGhost,
+    OutOfRange,
+}
+
+impl Investigation {
+    fn working_on(&self, puzzle: GhostType) -> bool {
+        self.guess == Some(puzzle) && self.confirmed_at.is_some()
+    }
+}
+
+pub struct Referee {
+    ghost_type: GhostType,
+    tuning: EvidenceTuning,
+    tables: ExorcismTables,
+    banshee_order: Vec<u8>,
+    investigations: HashMap<PlayerId, Investigation>,
+    misses: Vec<EvidenceMiss>,
+}
+
+impl Referee {
+    pub fn new(ghost_type: GhostType) -> Self {
+        Self::with_rules(
+            ghost_type,
+            EvidenceTuning::default(),
+            ExorcismTables::default(),
+        )
+    }
+
+    pub fn with_rules(
+        ghost_type: GhostType,
+        tuning: EvidenceTuning,
+        tables: ExorcismTables,
+    ) -> Self {
+        Self {
+            ghost_type,
+            tuning,
+            tables,
+            banshee_order: Vec::new(),
+            investigations: HashMap::new(),
+            misses: Vec::new(),
+        }
+    }
+
+    // Without an order the Banshee nodes go in layout order.
+    pub fn with_banshee_order(mut self, order: Vec<u8>) -> Self {
+        self.banshee_order = order;
+        self
+    }
+
+    pub fn banshee_order(&self) -> &[u8] {
+        &self.banshee_order
+    }
+
+    pub fn restore(&mut self, investigations: &[InvestigationCheckpoint]) {
+        for saved in investigations {
+            self.investigations.insert(
+                saved.player,
+                Investigation {
+                    guess: saved.guess,
+                    confirmed_at: saved.confirmed_at,
+                    evidence: saved.evidence.clone(),
+                    banshee_stage: saved.banshee_stage,
+                    last_press: saved.last_press,
+                    failed_until: saved.failed_until,
+                    spirit: saved.spirit.clone(),
+                    onryo: saved.onryo.clone(),
+                    emf_shown: None,
+                },
+            );
+        }
+    }
+
+    pub fn checkpoint(&self) -> Vec<InvestigationCheckpoint> {
+        let mut saved: Vec<_> = self
+            .investigations
+            .iter()
+            .map(|(player, investigation)| InvestigationCheckpoint {
+                player: *player,
+                guess: investigation.guess,
+                confirmed_at: investigation.confirmed_at,
+                evidence: investigation.evidence.clone(),
+                banshee_stage: investigation.banshee_stage,
+                last_press: investigation.last_press,
+                failed_until: investigation.failed_until,
+                spirit: investigation.spirit.clone(),
+                onryo: investigation.onryo.clone(),
+            })
+            .collect();
+        saved.sort_by_key(|investigation| investigation.player);
+        saved
+    }
+
+    pub fn ghost_type(&self) -> GhostType {
+        self.ghost_type
+    }
+
+    pub fn evidence(&self, player: PlayerId) -> &[EvidenceKind] {
+        self.investigations
+            .get(&player)
+            .map_or(&[], |investigation| investigation.evidence.as_slice())
+    }
+
+    // Advances the puzzles that run on time rather than on claims.
+    pub fn step(&mut self, game: &MatchSimulation, dt: f32) {
+        let exorcism = &game.layout().exorcism;
+        for (player, investigation) in &mut self.investigations {
+            let Some(sim) = game.player(*player) else {
+                continue;
+            };
+            if investigation.working_on(GhostType::Spirit) {
+                watch_spirit_anchors(
+                    &mut investigation.spirit,
+                    sim,
+                    &exorcism.spirit_anchors,
+                    &self.tables.spirit,
+                    dt,
+                );
+            }
+            let onryo = &mut investigation.onryo;
+            if investigation.guess == Some(GhostType::Onryo) && onryo.carrying.is_some() {
+                let config = &self.tables.onryo;
+                let (stacks, _) =
+                    onryo_stack_tick(onryo.stacks, dt, true, config.max_stacks, config.stack_rate);
+                onryo.stacks = stacks;
+            }
+        }
+    }
+
+    // The EMF level each investigator's meter shows, for those whose level
+    // changed since the last call. Clients never work this out themselves:
+    // they do not know the ghost type.
+    pub fn emf_readings(
+        &mut self,
+        game: &MatchSimulation,
+        players: &[PlayerId],
+    ) -> Vec<(PlayerId, u8)> {
+        let mut changed = Vec::new();
+        for player in players {
+            let Ok(sim) = investigator(game, *player) else {
+                continue;
+            };
+            let level = self.emf_reading(game, sim);
+            let investigation = self.investigations.entry(*player).or_default();
+            if investigation.emf_shown != Some(level) {
+                investigation.emf_shown = Some(level);
+                changed.push((*player, level));
+            }
+        }
+        changed
+    }
+
+    // Only the spiritbox answers on use; the other tools read continuously.
+    pub fn use_equipment(
+        &mut self,
+        game: &MatchSimulation,
+        player: PlayerId,
+    ) -> Result<Option<ServerMessage>, ClaimRejection> {
+        let sim = investigator(game, player)?;
+        if sim.equipment != Equipment::Spiritbox {
+            return Ok(None);
+        }
+        let reply = game
+            .ghost_position()
+            .map_or(SpiritboxReply::Static, |ghost| {
+                self.spiritbox_answer(game, sim, ghost, sim.position.distance(ghost))
+            });
+        Ok(Some(ServerMessage::Evidence(
+            EvidenceEvent::SpiritboxReply {
+                message: reply.as_str().to_string(),
+                evidence: spiritbox_is_evidence(reply),
+            },
+        )))
+    }
+
+    // An exorcism that can no longer be finished ends the round. Only the
+    // Onryo's stacks run out for good: a Banshee mis-press resets the
+    // sequence after `fail_reset_seconds`.
+    pub fn failed_exorcism(&self) -> Option<RoundSummary> {
+        let max_stacks = self.tables.onryo.max_stacks;
+        let failed = self.investigations.values().find(|investigation| {
+            investigation.working_on(GhostType::Onryo) && investigation.onryo.stacks >= max_stacks
+        })?;
+        let outcome = if failed.guess == Some(self.ghost_type) {
+            RoundOutcome::FailedExorcism
+        } else {
+            RoundOutcome::WrongGhost
+        };
+        Some(RoundSummary {
+            outcome,
+            ghost_type: self.ghost_type,
+            guess: failed.guess,
+        })
+    }
+
+    pub fn guess(
+        &mut self,
+        game: &MatchSimulation,
+        player: PlayerId,
+        ghost_type: GhostType,
+    ) -> Result<ServerMessage, ClaimRejection> {
+        investigator(game, player)?;
+        let investigation = self.investigations.entry(player).or_default();
+        if investigation.confirmed_at.is_none() {
+            investigation.guess = Some(ghost_type);
+        }
+        Ok(investigation_message(investigation))
+    }
+
+    pub fn confirm(
+        &mut self,
+        game: &MatchSimulation,
+        player: PlayerId,
+    ) -> Result<ServerMessage, ClaimRejection> {
+        investigator(game, player)?;
+        let investigation = self.investigations.entry(player).or_default();
+        if investigation.guess.is_some() && investigation.confirmed_at.is_none() {
+            investigation.confirmed_at = Some(game.server_time());
+        }
+        Ok(investigation_message(investigation))
+    }
+
+    pub fn claim_evidence(
+        &mut self,
+        game: &MatchSimulation,
+        player: PlayerId,
+        kind: EvidenceKind,
+    ) -> Result<(), ClaimRejection> {
+        let sim = investigator(game, player)?;
+        if let Err(miss) = self.check_evidence(game, sim, kind) {
+            info!(
+                "referee: {:?} from player {} missed ({:?})",
+                kind, player, miss
+            );
+            self.misses.push(miss);
+            return Err(ClaimRejection::ImpossibleEvidence);
+        }
+        let investigation = self.investigations.entry(player).or_default();
+        if !investigation.evidence.contains(&kind) {
+            investigation.evidence.push(kind);
+        }
+        Ok(())
+    }
+
+    // The detailed reasons behind rejected evidence claims since the last
+    // call, for the server's own metrics.
+    pub fn take_misses(&mut self) -> Vec<EvidenceMiss> {
+        std::mem::take(&mut self.misses)
+    }
+
+    fn check_evidence(
+        &self,
+        game: &MatchSimulation,
+        sim: &SimPlayer,
+        kind: EvidenceKind,
+    ) -> Result<(), EvidenceMiss> {
+        let position = sim.position;
+        let ghost = game.ghost_position().ok_or(EvidenceMiss::OutOfRange)?;
+        let distance = (position.distance(ghost) - REACH_TOLERANCE).max(0.0);
+        match kind {
+            EvidenceKind::EmfFive => {
+                if self.ghost_type != GhostType::Spirit {
+                    return Err(EvidenceMiss::WrongGhost);
+                }
+                if !emf_five_candidate(self.ghost_type, distance, &self.tuning) {
+                    return Err(EvidenceMiss::OutOfRange);
+                }
+            }
+            EvidenceKind::SpiritboxResponse => {
+                if self.ghost_type != GhostType::Banshee {
+                    return Err(EvidenceMiss::WrongGhost);
+                }
+                let reply = self.spiritbox_answer(game, sim, ghost, distance);
+                if !spiritbox_is_evidence(reply) {
+                    return Err(EvidenceMiss::OutOfRange);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn press_banshee_node(
+        &mut self,
+        game: &MatchSimulation,
+        player: PlayerId,
+        node: u8,
+    ) -> Result<ExorcismUpdate, ClaimRejection> {
+        let position = investigator(game, player)?.position;
+        let anchor = *game
+            .layout()
+            .exorcism
+            .banshee_anchors
+            .get(usize::from(node))
+            .ok_or(ClaimRejection::UnknownNode)?;
+        let config = &self.tables.banshee;
+        let sequence_len = self.banshee_sequence_len(game);
+        let investigation = self.investigations.entry(player).or_default();
+        if !investigation.working_on(GhostType::Banshee) {
+            return Err(ClaimRejection::PuzzleNotStarted);
+        }
+        if position.distance(anchor) > config.interact_distance + REACH_TOLERANCE {
+            return Err(ClaimRejection::OutOfRange);
+        }
+
+        let now = game.server_time();
+        if now < investigation.failed_until || investigation.banshee_stage >= sequence_len {
+            return Ok(banshee_update(investigation.banshee_stage, sequence_len));
+        }
+        let timing_ok = match investigation.last_press {
+            Some(last) if investigation.banshee_stage > 0 => {
+                let waited = now - last;
+                waited >= f64::from(config.timing_min) - TIMING_TOLERANCE
+                    && waited <= f64::from(config.timing_max) + TIMING_TOLERANCE
+            }
+            _ => true,
+        };
+        let stage = investigation.banshee_stage;
+        let expected = self.banshee_order.get(usize::from(stage)).copied();
+        let order_ok = node == expected.unwrap_or(stage);
+        investigation.last_press = Some(now);
+        let state = banshee_advance(stage, sequence_len, timing_ok, order_ok);
+        match state {
+            ExorcismState::Stage(stage) => investigation.banshee_stage = stage,
+            ExorcismState::Complete => investigation.banshee_stage = sequence_len,
+            _ => {
+                investigation.banshee_stage = 0;
+                investigation.failed_until = now + f64::from(config.fail_reset_seconds);
+            }
+        }
+        Ok(ExorcismUpdate {
+            state,
+            ..banshee_update(investigation.banshee_stage, sequence_len)
+        })
+    }
+
+    // Picks up the cursed object in reach, or lays the carried one on the
+    // ritual spot in reach.
+    pub fn interact(
+        &mut self,
+        game: &MatchSimulation,
+        player: PlayerId,
+    ) -> Result<ExorcismUpdate, ClaimRejection> {
+        let position = investigator(game, player)?.position;
+        let exorcism = &game.layout().exorcism;
+        let config = &self.tables.onryo;
+        let rituals = onryo_ritual_count(game);
+        let investigation = self.investigations.entry(player).or_default();
+        if !investigation.working_on(GhostType::Onryo) {
+            return Err(ClaimRejection::PuzzleNotStarted);
+        }
+        let onryo = &mut investigation.onryo;
+        if onryo.stage >= rituals || onryo.stacks >= config.max_stacks {
+            return Ok(onryo_update(onryo, rituals, config));
+        }
+        if onryo.objects.is_empty() {
+            onryo.objects = exorcism.onryo_cursed_positions.clone();
+        }
+        let reach = config.interact_distance + REACH_TOLERANCE;
+        let Some(carried) = onryo.carrying else {
+            let object = (0..onryo.objects.len())
+                .find(|index| {
+                    !onryo.placed.contains(&(*index as u8))
+                        && position.distance(onryo.objects[*index]) <= reach
+                })
+                .ok_or(ClaimRejection::OutOfRange)?;
+            onryo.carrying = Some(object as u8);
+            onryo.stacks += config.stack_penalty_wrong;
+            return Ok(onryo_update(onryo, rituals, config));
+        };
+
+        let spots = &exorcism.onryo_ritual_positions;
+        let in_reach = |spot: &Vec3| position.distance(*spot) <= reach;
+        if spots.get(usize::from(onryo.stage)).is_some_and(in_reach) {
+            onryo.placed.push(carried);
+            onryo.stage += 1;
+            onryo.stacks = (onryo.stacks - config.stack_reward_correct).max(0.0);
+        } else if spots.iter().any(in_reach) {
+            // A wrong spot costs stacks and leaves the object where it fell.
+            onryo.objects[usize::from(carried)] =
+                position + Vec3::new(0.0, config.carry_height, 0.0);
+            onryo.stacks += config.stack_penalty_wrong;
+        } else {
+            return Err(ClaimRejection::OutOfRange);
+        }
+        onryo.carrying = None;
+        Ok(onryo_update(onryo, rituals, config))
+    }
+
+    pub fn claim_exorcism(
+        &self,
+        game: &MatchSimulation,
+        player: PlayerId,
+    ) -> Result<RoundSummary, ClaimRejection> {
+        investigator(game, player)?;
+        let investigation = self
+            .investigations
+            .get(&player)
+            .ok_or(ClaimRejection::PuzzleNotStarted)?;
+        let (Some(guess), Some(_)) = (investigation.guess, investigation.confirmed_at) else {
+            return Err(ClaimRejection::PuzzleNotStarted);
+        };
+        let complete = match guess {
+            // The client may be up to the timing tolerance ahead of us.
+            GhostType::Spirit => {
+                investigation.spirit.progress + self.tables.spirit.rate_up * TIMING_TOLERANCE as f32
+                    >= 1.0
+            }
+            GhostType::Banshee => investigation.banshee_stage >= self.banshee_sequence_len(game),
+            GhostType::Onryo => investigation.onryo.stage >= onryo_ritual_count(game),
+        };
+        if !complete {
+            return Err(ClaimRejection::PuzzleIncomplete);
+        }
+        let outcome = if guess == self.ghost_type {
+            RoundOutcome::SuccessfulExorcism
+        } else {
+            RoundOutcome::WrongGhost
+        };
+        Ok(RoundSummary {
+            outcome,
+            ghost_type: self.ghost_type,
+            guess: Some(guess),
+        })
+    }
+
+    // Level 5 needs a Spirit within reach and the investigator facing it.
+    fn emf_reading(&self, game: &MatchSimulation, sim: &SimPlayer) -> u8 {
+        let Some(ghost) = game.ghost_position() else {
+            return 0;
+        };
+        let to_ghost = ghost - sim.position;
+        let distance = to_ghost.length();
+        let flat = Vec3::new(to_ghost.x, 0.0, to_ghost.z).normalize_or_zero();
+        let facing_ghost =
+            flat == Vec3::ZERO || facing(sim.yaw).dot(flat) >= self.tuning.emf_facing_dot;
+        if facing_ghost
+            && distance <= overlap_distance(&self.tuning)
+            && emf_five_candidate(self.ghost_type, distance, &self.tuning)
+        {
+            return 5;
+        }
+        let same_room = same_room(game, sim.position, ghost);
+        emf_level(self.ghost_type, distance, same_room, &self.tuning)
+    }
+
+    fn spiritbox_answer(
+        &self,
+        game: &MatchSimulation,
+        sim: &SimPlayer,
+        ghost: Vec3,
+        distance: f32,
+    ) -> SpiritboxReply {
+        let same_room = same_room(game, sim.position, ghost);
+        let bearing = spiritbox_bearing(facing(sim.yaw), sim.position, ghost);
+        spiritbox_reply(self.ghost_type, same_room, distance, &self.tuning, bearing)
+    }
+
+    fn banshee_sequence_len(&self, game: &MatchSimulation) -> u8 {
+        match self.banshee_order.len() {
+            0 => self
+                .tables
+                .banshee
+                .sequence_len_for(game.layout().exorcism.banshee_anchors.len()),
+            len => len.min(usize::from(u8::MAX)) as u8,
+        }
+    }
+}
+
+fn investigator(game: &MatchSimulation, player: PlayerId) -> Result<&SimPlayer, ClaimRejection> {
+    match game.player(player) {
+        Some(sim) if sim.role == Role::Investigator => Ok(sim),
+        Some(_) => Err(ClaimRejection::WrongRole),
+        None => Err(ClaimRejection::NotInRound),
+    }
+}
+
+fn onryo_ritual_count(game: &MatchSimulation) -> u8 {
+    let spots = game.layout().exorcism.onryo_ritual_positions.len();
+    spots.clamp(1, usize::from(u8::MAX)) as u8
+}
+
+fn same_room(game: &MatchSimulation, a: Vec3, b: Vec3) -> bool {
+    let layout = game.layout();
+    room_id_in_house(layout, a).is_some_and(|room| room_id_in_house(layout, b) == Some(room))
+}
+
+// The simulation only knows yaw, so anchors are judged on the floor plane.
+fn facing(yaw: f32) -> Vec3 {
+    Vec3::new(yaw.sin(), 0.0, yaw.cos())
+}
+
+fn watch_spirit_anchors(
+    vigil: &mut SpiritVigil,
+    sim: &SimPlayer,
+    anchors: &[Vec3],
+    config: &SpiritConfig,
+    dt: f32,
+) {
+    vigil.last_seen.resize(anchors.len(), f32::MAX);
+    let forward = facing(sim.yaw);
+    let mut recent = 0usize;
+    for (anchor, last_seen) in anchors.iter().zip(&mut vigil.last_seen) {
+        let to_anchor = Vec3::new(anchor.x - sim.position.x, 0.0, anchor.z - sim.position.z);
+        let seen = to_anchor.length() <= config.watch_distance
+            && forward.dot(to_anchor.normalize_or_zero()) >= config.watch_cos;
+        *last_seen = if seen { 0.0 } else { *last_seen + dt };
+        if *last_seen <= config.grace_seconds {
+            recent += 1;
+        }
+    }
+    let required = anchors.len().clamp(1, 2);
+    vigil.progress = spirit_progress(
+        vigil.progress,
+        recent as f32 / required as f32,
+        dt,
+        config.rate_up,
+        config.rate_down,
+    );
+}
+
+fn investigation_message(investigation: &Investigation) -> ServerMessage {
+    ServerMessage::Investigation {
+        guess: investigation.guess,
+        confirmed: investigation.confirmed_at.is_some(),
+    }
+}
+
+fn onryo_update(onryo: &OnryoContainment, rituals: u8, config: &OnryoConfig) -> ExorcismUpdate {
+    let state = if onryo.stage >= rituals {
+        ExorcismState::Complete
+    } else if onryo.stacks >= config.max_stacks {
+        ExorcismState::Failed
+    } else {
+        ExorcismState::Stage(onryo.stage)
+    };
+    ExorcismUpdate {
+        state,
+        progress: f32::from(onryo.stage) / f32::from(rituals.max(1)),
+        stage: onryo.stage,
+        stacks: onryo.stacks,
+        max_stacks: config.max_stacks,
+    }
+}
+
+fn banshee_update(stage: u8, sequence_len: u8) -> ExorcismUpdate {
+    let state = if stage >= sequence_len {
+        ExorcismState::Complete
+    } else {
+        ExorcismState::Stage(stage)
+    };
+    ExorcismUpdate {
+        state,
+        progress: f32::from(stage) / f32::from(sequence_len.max(1)),
+        stage,
+        stacks: 0.0,
+        max_stacks: 0.0,
+    }
+}
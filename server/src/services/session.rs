@@ -0,0 +1,247 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Notify;
+
+use crate::prelude::*;
+use crate::services::persistence::SessionCheckpoint;
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(15);
+pub const RESUME_GRACE: Duration = Duration::from_secs(30);
+// Sent to a socket whose session was resumed by a newer connection.
+pub const CLOSE_SESSION_TAKEN_OVER: u16 = 4000;
+// Sent to a socket that missed broadcasts; it resumes and is sent the room again.
+pub const CLOSE_FELL_BEHIND: u16 = 4001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionTimeouts {
+    pub heartbeat: Duration,
+    pub idle: Duration,
+    pub resume_grace: Duration,
+}
+
+impl Default for SessionTimeouts {
+    fn default() -> Self {
+        Self {
+            heartbeat: HEARTBEAT_INTERVAL,
+            idle: IDLE_TIMEOUT,
+            resume_grace: RESUME_GRACE,
+        }
+    }
+}
+
+struct Session {
+    player: Option<PlayerId>,
+    account: Option<String>,
+    connection: u64,
+    disconnected_at: Option<Instant>,
+    takeover: Option<Arc<Notify>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionHandle {
+    pub token: String,
+    pub connection: u64,
+    pub player: Option<PlayerId>,
+}
+
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: HashMap<String, Session>,
+    next_connection: u64,
+    issued: u64,
+    entropy: RandomState,
+    timeouts: SessionTimeouts,
+    resumed: u64,
+}
+
+impl SessionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timeouts(timeouts: SessionTimeouts) -> Self {
+        Self {
+            timeouts,
+            ..Self::default()
+        }
+    }
+
+    // Restored sessions count as disconnected from `now`, so clients get the
+    // usual resume grace to come back after a restart.
+    pub fn restore(timeouts: SessionTimeouts, saved: &[SessionCheckpoint], now: Instant) -> Self {
+        let mut registry = Self::with_timeouts(timeouts);
+        for session in saved {
+            let connection = registry.next_connection();
+            registry.sessions.insert(
+                session.token.clone(),
+                Session {
+                    player: session.player,
+                    account: session.account.clone(),
+                    connection,
+                    disconnected_at: Some(now),
+                    takeover: None,
+                },
+            );
+        }
+        registry
+    }
+
+    pub fn checkpoint(&self) -> Vec<SessionCheckpoint> {
+        let mut saved: Vec<_> = self
+            .sessions
+            .iter()
+            .map(|(token, session)| SessionCheckpoint {
+                token: token.clone(),
+                player: session.player,
+                account: session.account.clone(),
+            })
+            .collect();
+        saved.sort_by(|a, b| a.token.cmp(&b.token));
+        saved
+    }
+
+    pub fn timeouts(&self) -> SessionTimeouts {
+        self.timeouts
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn resumed_total(&self) -> u64 {
+        self.resumed
+    }
+
+    pub fn open(&mut self) -> SessionHandle {
+        let token = loop {
+            let token = self.next_token();
+            if !self.sessions.contains_key(&token) {
+                break token;
+            }
+        };
+        let connection = self.next_connection();
+        self.sessions.insert(
+            token.clone(),
+            Session {
+                player: None,
+                account: None,
+                connection,
+                disconnected_at: None,
+                takeover: None,
+            },
+        );
+        SessionHandle {
+            token,
+            connection,
+            player: None,
+        }
+    }
+
+    pub fn resume(&mut self, token: &str, now: Instant) -> Option<SessionHandle> {
+        let connection = self.next_connection();
+        let grace = self.timeouts.resume_grace;
+        let session = self.sessions.get_mut(token)?;
+        if session
+            .disconnected_at
+            .is_some_and(|at| now.duration_since(at) > grace)
+        {
+            return None;
+        }
+        // A socket that still holds the session is told to close, so two
+        // connections never act for the same player.
+        if let Some(takeover) = session.takeover.take() {
+            takeover.notify_one();
+        }
+        session.connection = connection;
+        session.disconnected_at = None;
+        self.resumed += 1;
+        Some(SessionHandle {
+            token: token.to_string(),
+            connection,
+            player: session.player,
+        })
+    }
+
+    pub fn is_current(&self, handle: &SessionHandle) -> bool {
+        self.sessions
+            .get(&handle.token)
+            .is_some_and(|session| session.connection == handle.connection)
+    }
+
+    // `signal` fires once another connection resumes this session.
+    pub fn watch_takeover(&mut self, handle: &SessionHandle, signal: Arc<Notify>) {
+        match self.sessions.get_mut(&handle.token) {
+            Some(session) if session.connection == handle.connection => {
+                session.takeover = Some(signal);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn bind_player(&mut self, token: &str, player: Option<PlayerId>) {
+        if let Some(session) = self.sessions.get_mut(token) {
+            session.player = player;
+        }
+    }
+
+    pub fn bind_account(&mut self, token: &str, account: &str) {
+        if let Some(session) = self.sessions.get_mut(token) {
+            session.account = Some(account.to_string());
+        }
+    }
+
+    pub fn account(&self, token: &str) -> Option<&str> {
+        self.sessions.get(token)?.account.as_deref()
+    }
+
+    pub fn disconnect(&mut self, handle: &SessionHandle, now: Instant) -> bool {
+        match self.sessions.get_mut(&handle.token) {
+            Some(session) if session.connection == handle.connection => {
+                session.disconnected_at = Some(now);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn expire(&mut self, now: Instant) -> Vec<PlayerId> {
+        let mut expired = Vec::new();
+        let grace = self.timeouts.resume_grace;
+        self.sessions.retain(|_, session| {
+            let keep = session
+                .disconnected_at
+                .is_none_or(|at| now.duration_since(at) <= grace);
+            if !keep {
+                expired.extend(session.player);
+            }
+            keep
+        });
+        expired
+    }
+
+    fn next_connection(&mut self) -> u64 {
+        self.next_connection += 1;
+        self.next_connection
+    }
+
+    fn next_token(&mut self) -> String {
+        self.issued += 1;
+        let mut token = String::new();
+        for part in 0..2u64 {
+            let mut hasher = self.entropy.build_hasher();
+            hasher.write_u64(self.issued);
+            hasher.write_u64(part);
+            token.push_str(&format!("{:016x}", hasher.finish()));
+        }
+        token
+    }
+}
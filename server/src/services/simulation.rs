@@ -0,0 +1,256 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use shared::map::CollisionWorld;
+use shared::movement::{apply_command, MAX_INPUT_DT};
+
+use crate::prelude::*;
+use crate::services::persistence::PlayerCheckpoint;
+
+const MAX_PENDING_INPUTS: usize = 64;
+const INPUT_BUDGET_START: f32 = 0.25;
+const MAX_INPUT_BUDGET: f32 = 0.5;
+const INPUT_EPSILON: f32 = 1e-3;
+
+pub struct SimPlayer {
+    pub role: Role,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub last_input_seq: u32,
+    pub equipment: Equipment,
+    pending: VecDeque<InputCommand>,
+    input_budget: f32,
+}
+
+pub struct MatchSimulation {
+    layout_kind: HouseLayoutKind,
+    layout: HouseLayout,
+    world: CollisionWorld,
+    players: BTreeMap<PlayerId, SimPlayer>,
+    tick: u64,
+    elapsed: f64,
+    violations: Vec<(PlayerId, ClaimRejection)>,
+}
+
+impl MatchSimulation {
+    pub fn new(layout_kind: HouseLayoutKind) -> Self {
+        let layout = HouseLayout::for_kind(layout_kind);
+        let world = layout.collision_world();
+        Self {
+            layout_kind,
+            layout,
+            world,
+            players: BTreeMap::new(),
+            tick: 0,
+            elapsed: 0.0,
+            violations: Vec::new(),
+        }
+    }
+
+    pub fn restore(
+        layout_kind: HouseLayoutKind,
+        tick: u64,
+        elapsed: f64,
+        players: &[PlayerCheckpoint],
+    ) -> Self {
+        let mut game = Self::new(layout_kind);
+        game.tick = tick;
+        game.elapsed = elapsed;
+        for saved in players {
+            game.players.insert(
+                saved.id,
+                SimPlayer {
+                    role: saved.role,
+                    position: saved.position,
+                    yaw: saved.yaw,
+                    last_input_seq: saved.last_input_seq,
+                    equipment: Equipment::Emf,
+                    pending: VecDeque::new(),
+                    input_budget: INPUT_BUDGET_START,
+                },
+            );
+        }
+        game
+    }
+
+    pub fn checkpoint(&self) -> Vec<PlayerCheckpoint> {
+        self.players
+            .iter()
+            .map(|(id, player)| PlayerCheckpoint {
+                id: *id,
+                role: player.role,
+                position: player.position,
+                yaw: player.yaw,
+                last_input_seq: player.last_input_seq,
+            })
+            .collect()
+    }
+
+    pub fn layout_kind(&self) -> HouseLayoutKind {
+        self.layout_kind
+    }
+
+    pub fn layout(&self) -> &HouseLayout {
+        &self.layout
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn server_time(&self) -> f64 {
+        self.elapsed
+    }
+
+    pub fn player(&self, id: PlayerId) -> Option<&SimPlayer> {
+        self.players.get(&id)
+    }
+
+    pub fn ghost_position(&self) -> Option<Vec3> {
+        self.players
+            .values()
+            .find(|player| player.role == Role::Ghost)
+            .map(|player| player.position)
+    }
+
+    pub fn spawn_point(&self, role: Role) -> Vec3 {
+        match role {
+            Role::Ghost => self.layout.random_ghost_spawn(),
+            Role::Investigator => self.layout.random_investigator_spawn(),
+            Role::Spectator => self.layout.initial_investigator_spawn(),
+        }
+    }
+
+    pub fn add_player(&mut self, id: PlayerId, role: Role) -> Vec3 {
+        self.add_player_at(id, role, self.spawn_point(role))
+    }
+
+    pub fn add_player_at(&mut self, id: PlayerId, role: Role, position: Vec3) -> Vec3 {
+        self.players.insert(
+            id,
+            SimPlayer {
+                role,
+                position,
+                yaw: 0.0,
+                last_input_seq: 0,
+                equipment: Equipment::Emf,
+                pending: VecDeque::new(),
+                input_budget: INPUT_BUDGET_START,
+            },
+        );
+        position
+    }
+
+    pub fn place_player(&mut self, id: PlayerId, position: Vec3) -> bool {
+        let Some(player) = self.players.get_mut(&id) else {
+            return false;
+        };
+        player.position = position;
+        true
+    }
+
+    pub fn turn_player(&mut self, id: PlayerId, yaw: f32) -> bool {
+        let Some(player) = self.players.get_mut(&id) else {
+            return false;
+        };
+        player.yaw = yaw;
+        true
+    }
+
+    pub fn rejoin(&mut self, id: PlayerId) -> Option<Vec3> {
+        let player = self.players.get_mut(&id)?;
+        player.pending.clear();
+        player.last_input_seq = 0;
+        player.input_budget = INPUT_BUDGET_START;
+        Some(player.position)
+    }
+
+    pub fn select_equipment(&mut self, id: PlayerId, equipment: Equipment) -> bool {
+        match self.players.get_mut(&id) {
+            Some(player) if player.role == Role::Investigator => {
+                player.equipment = equipment;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn remove_player(&mut self, id: PlayerId) -> bool {
+        self.players.remove(&id).is_some()
+    }
+
+    pub fn apply_input(&mut self, id: PlayerId, command: InputCommand) -> bool {
+        let Some(player) = self.players.get_mut(&id) else {
+            return false;
+        };
+        let newest = player
+            .pending
+            .back()
+            .map_or(player.last_input_seq, |queued| queued.seq);
+        if command.seq <= newest || player.pending.len() >= MAX_PENDING_INPUTS {
+            return false;
+        }
+        if !command_is_valid(&command) {
+            self.violations.push((id, ClaimRejection::InvalidInput));
+            return false;
+        }
+        player.pending.push_back(command);
+        true
+    }
+
+    pub fn step(&mut self, dt: f32) {
+        for (id, player) in &mut self.players {
+            player.input_budget = (player.input_budget + dt).min(MAX_INPUT_BUDGET);
+            let mut too_fast = false;
+            while let Some(command) = player.pending.pop_front() {
+                player.last_input_seq = command.seq;
+                if command.dt > player.input_budget + INPUT_EPSILON {
+                    too_fast = true;
+                    continue;
+                }
+                player.input_budget = (player.input_budget - command.dt).max(0.0);
+                apply_command(&mut player.position, &command, player.role, &self.world);
+                player.yaw = command.input.yaw;
+            }
+            if too_fast {
+                self.violations.push((*id, ClaimRejection::TooFast));
+            }
+        }
+        self.tick += 1;
+        self.elapsed += f64::from(dt);
+    }
+
+    pub fn take_violations(&mut self) -> Vec<(PlayerId, ClaimRejection)> {
+        std::mem::take(&mut self.violations)
+    }
+
+    pub fn snapshot(&self) -> MatchSnapshot {
+        MatchSnapshot {
+            tick: self.tick,
+            server_time: self.elapsed,
+            players: self
+                .players
+                .iter()
+                .map(|(id, player)| PlayerSnapshot {
+                    id: *id,
+                    role: player.role,
+                    position: player.position,
+                    yaw: player.yaw,
+                    last_input_seq: player.last_input_seq,
+                })
+                .collect(),
+            ghost_type: None,
+        }
+    }
+}
+
+fn command_is_valid(command: &InputCommand) -> bool {
+    let input = &command.input;
+    let axis_ok = |value: f32| value.is_finite() && value.abs() <= 1.0 + INPUT_EPSILON;
+    command.dt.is_finite()
+        && command.dt >= 0.0
+        && command.dt <= MAX_INPUT_DT + INPUT_EPSILON
+        && axis_ok(input.forward)
+        && axis_ok(input.strafe)
+        && input.yaw.is_finite()
+        && input.pitch.is_finite()
+}
@@ -1,29 +1,370 @@
+use std::time::Instant;
+
 use crate::net::protocol;
 use crate::prelude::*;
+use crate::services::auth::GuestAccount;
+use crate::services::lobby::{self, Lobby, Room};
+use crate::services::ratelimit::{ConnectionLimiter, MessageClass, Verdict, CLOSE_MESSAGE_TOO_BIG};
+use crate::services::session::{SessionHandle, SessionRegistry};
 
 pub enum SocketAction {
     Send(Message),
+    Dispatch(ClientMessage),
+    Reject(MessageRejection),
+    Disconnect { code: u16, reason: &'static str },
     Close,
     Ignore,
 }
 
 pub fn handle_message(message: Message) -> SocketAction {
+    classify_message(message).0
+}
+
+pub fn classify_message(message: Message) -> (SocketAction, &'static str) {
     match message {
         Message::Text(text) => {
             if let Some(client_msg) = protocol::parse_client_message(&text) {
-                match client_msg {
+                let kind = client_msg.kind();
+                return match client_msg {
                     ClientMessage::Ping => {
-                        if let Some(payload) = protocol::server_message_text(&ServerMessage::Pong) {
-                            return SocketAction::Send(Message::Text(payload));
+                        match protocol::server_message_text(&ServerMessage::Pong) {
+                            Some(payload) => (SocketAction::Send(Message::Text(payload)), kind),
+                            None => (SocketAction::Ignore, kind),
                         }
-                        return SocketAction::Ignore;
                     }
+                    other => (SocketAction::Dispatch(other), kind),
+                };
+            }
+            (
+                SocketAction::Reject(MessageRejection::Malformed),
+                "Unparsed",
+            )
+        }
+        Message::Binary(bytes) => match protocol::parse_client_binary(&bytes) {
+            Some(client_msg) => {
+                let kind = client_msg.kind();
+                (SocketAction::Dispatch(client_msg), kind)
+            }
+            None => (
+                SocketAction::Reject(MessageRejection::Malformed),
+                "Unparsed",
+            ),
+        },
+        Message::Close(_) => (SocketAction::Close, "Close"),
+        _ => (SocketAction::Ignore, "Control"),
+    }
+}
+
+// Binary frames are only accepted once the handshake settled on the binary
+// codec; JSON text frames are always understood.
+pub fn classify_frame(
+    message: Message,
+    max_bytes: usize,
+    codec: WireCodec,
+) -> (SocketAction, &'static str) {
+    let size = match &message {
+        Message::Text(text) => text.len(),
+        Message::Binary(bytes) => bytes.len(),
+        _ => 0,
+    };
+    if size > max_bytes {
+        let action = SocketAction::Disconnect {
+            code: CLOSE_MESSAGE_TOO_BIG,
+            reason: "message too large",
+        };
+        return (action, "Oversized");
+    }
+    if matches!(message, Message::Binary(_)) && codec != WireCodec::Binary {
+        return (
+            SocketAction::Reject(MessageRejection::Unsupported),
+            "Binary",
+        );
+    }
+    classify_message(message)
+}
+
+// Malformed frames cost a strike just like rate-limited ones, so garbage
+// floods end in a disconnect too.
+pub fn admit(limiter: &mut ConnectionLimiter, action: &SocketAction, now: Instant) -> Verdict {
+    match action {
+        SocketAction::Send(_) => limiter.check(MessageClass::Control, now),
+        SocketAction::Dispatch(message) => limiter.check(MessageClass::of(message), now),
+        SocketAction::Reject(_) => limiter.strike(now),
+        SocketAction::Disconnect { .. } | SocketAction::Close | SocketAction::Ignore => {
+            Verdict::Allow
+        }
+    }
+}
+
+pub fn handle_hello(
+    sessions: &mut SessionRegistry,
+    lobby: &Lobby,
+    version: u32,
+    resume_token: Option<&str>,
+    now: Instant,
+) -> Option<(SessionHandle, ServerMessage)> {
+    negotiate_hello(sessions, lobby, version, resume_token, &[], now).ok()
+}
+
+pub fn negotiate_hello(
+    sessions: &mut SessionRegistry,
+    lobby: &Lobby,
+    version: u32,
+    resume_token: Option<&str>,
+    codecs: &[WireCodec],
+    now: Instant,
+) -> Result<(SessionHandle, ServerMessage), VersionMismatch> {
+    let protocol_version = VersionMismatch::check(version)?;
+    let mut handle = resume_token
+        .and_then(|token| sessions.resume(token, now))
+        .unwrap_or_else(|| sessions.open());
+    let room = handle
+        .player
+        .and_then(|id| lobby.room_of(id))
+        .map(Room::state);
+    let queued = handle.player.is_some_and(|id| lobby.is_queued(id));
+    if room.is_none() && handle.player.is_some() && !queued {
+        handle.player = None;
+        sessions.bind_player(&handle.token, None);
+    }
+    let welcome = ServerMessage::Welcome {
+        protocol_version,
+        resume_token: handle.token.clone(),
+        heartbeat_ms: sessions.timeouts().heartbeat.as_millis() as u64,
+        player_id: handle.player,
+        room,
+        codec: WireCodec::negotiate(codecs),
+    };
+    Ok((handle, welcome))
+}
+
+// A resume token only counts for the account that opened it, so a leaked
+// token cannot be used to take over someone else's seat.
+pub fn handle_authenticated_hello(
+    sessions: &mut SessionRegistry,
+    lobby: &Lobby,
+    account: &GuestAccount,
+    version: u32,
+    resume_token: Option<&str>,
+    codecs: &[WireCodec],
+    now: Instant,
+) -> Result<(SessionHandle, ServerMessage), VersionMismatch> {
+    let resume_token = resume_token.filter(|token| {
+        sessions
+            .account(token)
+            .is_none_or(|owner| owner == account.account_id)
+    });
+    let (handle, welcome) = negotiate_hello(sessions, lobby, version, resume_token, codecs, now)?;
+    sessions.bind_account(&handle.token, &account.account_id);
+    Ok((handle, welcome))
+}
+
+pub fn handle_authenticated_message(
+    lobby: &mut Lobby,
+    player: &mut Option<PlayerId>,
+    account: &GuestAccount,
+    message: ClientMessage,
+) -> Option<ServerMessage> {
+    match message {
+        ClientMessage::JoinLobby { room_code, .. } => {
+            if let Some(previous) = player.take() {
+                lobby.leave(previous);
+            }
+            let joined =
+                lobby.join_as(&room_code, &account.display_name, Some(&account.account_id));
+            Some(joined_reply(player, &room_code, joined))
+        }
+        ClientMessage::SpectateRoom { room_code, .. } => {
+            if let Some(previous) = player.take() {
+                lobby.leave(previous);
+            }
+            let joined =
+                lobby.spectate_as(&room_code, &account.display_name, Some(&account.account_id));
+            Some(joined_reply(player, &room_code, joined))
+        }
+        ClientMessage::QuickPlay { role, layout, .. } => {
+            if let Some(previous) = player.take() {
+                lobby.leave(previous);
+            }
+            let queued = lobby.quick_play_as(
+                &account.display_name,
+                role,
+                layout,
+                Some(&account.account_id),
+            );
+            Some(queued_reply(lobby, player, queued))
+        }
+        // Players only see their own record; the name in the request is ignored.
+        ClientMessage::RequestProfile { .. } => {
+            let profile = lobby
+                .profile(&account.account_id)
+                .cloned()
+                .unwrap_or_else(|| PlayerProfile::new(&account.display_name));
+            Some(ServerMessage::Profile(profile))
+        }
+        other => handle_client_message(lobby, player, other),
+    }
+}
+
+pub fn handle_client_message(
+    lobby: &mut Lobby,
+    player: &mut Option<PlayerId>,
+    message: ClientMessage,
+) -> Option<ServerMessage> {
+    let result = match message {
+        ClientMessage::Ping => return Some(ServerMessage::Pong),
+        ClientMessage::Hello { .. } | ClientMessage::HeartbeatAck { .. } => Ok(None),
+        ClientMessage::JoinLobby {
+            display_name,
+            room_code,
+        } => {
+            if let Some(previous) = player.take() {
+                lobby.leave(previous);
+            }
+            let joined = lobby.join(&room_code, &display_name);
+            return Some(joined_reply(player, &room_code, joined));
+        }
+        ClientMessage::SpectateRoom {
+            display_name,
+            room_code,
+        } => {
+            if let Some(previous) = player.take() {
+                lobby.leave(previous);
+            }
+            let joined = lobby.spectate(&room_code, &display_name);
+            return Some(joined_reply(player, &room_code, joined));
+        }
+        ClientMessage::QuickPlay {
+            display_name,
+            role,
+            layout,
+        } => {
+            if let Some(previous) = player.take() {
+                lobby.leave(previous);
+            }
+            let queued = lobby.quick_play(&display_name, role, layout);
+            return Some(queued_reply(lobby, player, queued));
+        }
+        ClientMessage::LeaveQueue => {
+            if let Some(id) = *player {
+                if lobby.leave_queue(id) {
+                    *player = None;
                 }
             }
-            SocketAction::Send(Message::Text(text))
+            Ok(None)
+        }
+        ClientMessage::ChooseRole { role } => {
+            with_player(*player, |id| lobby.choose_role(id, role))
+        }
+        ClientMessage::ChooseGhostType { ghost_type } => {
+            with_player(*player, |id| lobby.choose_ghost_type(id, ghost_type))
+        }
+        ClientMessage::ChooseLayout { layout } => {
+            with_player(*player, |id| lobby.choose_layout(id, layout))
+        }
+        ClientMessage::StartRound => with_player(*player, |id| lobby.start_round(id)),
+        ClientMessage::Input(command) => {
+            if let Some(id) = *player {
+                lobby.apply_input(id, command);
+            }
+            Ok(None)
+        }
+        ClientMessage::JournalGuess { ghost_type } => {
+            return claim(*player, |id| lobby.journal_guess(id, ghost_type));
+        }
+        ClientMessage::JournalConfirm => return claim(*player, |id| lobby.journal_confirm(id)),
+        ClientMessage::ClaimEvidence(kind) => {
+            return claim(*player, |id| lobby.claim_evidence(id, kind));
+        }
+        ClientMessage::PressBansheeNode { node } => {
+            return claim(*player, |id| lobby.press_banshee_node(id, node));
+        }
+        ClientMessage::Interact => {
+            return claim(*player, |id| lobby.interact(id));
+        }
+        ClientMessage::ClaimExorcism => {
+            return claim(*player, |id| lobby.claim_exorcism(id).map(|_| None));
+        }
+        ClientMessage::StartHunt => {
+            return claim(*player, |id| lobby.start_hunt(id).map(|_| None));
+        }
+        ClientMessage::RequestProfile { display_name } => {
+            let profile = lobby
+                .profile(&display_name)
+                .cloned()
+                .unwrap_or_else(|| PlayerProfile::new(display_name.trim()));
+            return Some(ServerMessage::Profile(profile));
+        }
+        ClientMessage::Chat { channel, text } => {
+            let Some(id) = *player else {
+                return Some(ServerMessage::ChatRejected(ChatRejection::NotInRoom));
+            };
+            return lobby
+                .chat(id, channel, &text)
+                .err()
+                .map(ServerMessage::ChatRejected);
+        }
+        ClientMessage::SelectEquipment { equipment } => {
+            if let Some(id) = *player {
+                lobby.select_equipment(id, equipment);
+            }
+            Ok(None)
+        }
+        ClientMessage::UseEquipment => return claim(*player, |id| lobby.use_equipment(id)),
+        ClientMessage::ToggleRoomLight => Ok(None),
+    };
+    result.unwrap_or_else(|rejection| Some(ServerMessage::LobbyRejected(rejection)))
+}
+
+fn joined_reply(
+    player: &mut Option<PlayerId>,
+    room_code: &str,
+    joined: Result<PlayerId, LobbyRejection>,
+) -> ServerMessage {
+    match joined {
+        Ok(player_id) => {
+            *player = Some(player_id);
+            ServerMessage::RoomJoined {
+                player_id,
+                room_code: lobby::normalize_code(room_code),
+            }
         }
-        Message::Binary(bytes) => SocketAction::Send(Message::Binary(bytes)),
-        Message::Close(_) => SocketAction::Close,
-        _ => SocketAction::Ignore,
+        Err(rejection) => ServerMessage::LobbyRejected(rejection),
     }
 }
+
+fn queued_reply(
+    lobby: &Lobby,
+    player: &mut Option<PlayerId>,
+    queued: Result<PlayerId, LobbyRejection>,
+) -> ServerMessage {
+    let status = queued.and_then(|id| {
+        *player = Some(id);
+        lobby.queue_status(id).ok_or(LobbyRejection::NotInRoom)
+    });
+    match status {
+        Ok(status) => ServerMessage::QueueStatus(status),
+        Err(rejection) => ServerMessage::LobbyRejected(rejection),
+    }
+}
+
+fn claim<T: Into<Option<ServerMessage>>>(
+    player: Option<PlayerId>,
+    action: impl FnOnce(PlayerId) -> Result<T, ClaimRejection>,
+) -> Option<ServerMessage> {
+    let Some(id) = player else {
+        return Some(ServerMessage::LobbyRejected(LobbyRejection::NotInRoom));
+    };
+    match action(id) {
+        Ok(reply) => reply.into(),
+        Err(rejection) => Some(ServerMessage::ClaimRejected(rejection)),
+    }
+}
+
+fn with_player(
+    player: Option<PlayerId>,
+    action: impl FnOnce(PlayerId) -> Result<(), LobbyRejection>,
+) -> Result<Option<ServerMessage>, LobbyRejection> {
+    let id = player.ok_or(LobbyRejection::NotInRoom)?;
+    action(id).map(|_| None)
+}
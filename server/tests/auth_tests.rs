@@ -0,0 +1,264 @@
+use std::time::{Duration, Instant};
+
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::Json;
+use server::core::{AppConfig, AppState};
+use server::net::http;
+use server::services::auth::{AuthError, TokenSigner};
+use server::services::lobby::Lobby;
+use server::services::persistence::Checkpoint;
+use server::services::session::SessionRegistry;
+use server::services::ws;
+use shared::prelude::{
+    ClientMessage, CreateRoomRequest, GhostType, LobbyRejection, Role, ServerMessage, WireCodec,
+    PROTOCOL_VERSION,
+};
+
+const NOW_MS: u64 = 1_700_000_000_000;
+
+fn signer() -> TokenSigner {
+    TokenSigner::new(b"test-secret", Duration::from_secs(60))
+}
+
+#[test]
+fn issued_tokens_verify_back_to_the_account() {
+    let signer = signer();
+    let issued = signer.issue("  Ray ", NOW_MS).unwrap();
+    assert_eq!(issued.display_name, "Ray");
+    assert_eq!(issued.expires_ms, NOW_MS + 60_000);
+    assert!(issued.account_id.starts_with("guest-"));
+
+    let account = signer.verify(Some(&issued.token), NOW_MS + 1).unwrap();
+    assert_eq!(account.account_id, issued.account_id);
+    assert_eq!(account.display_name, "Ray");
+
+    let other = signer.issue("Ray", NOW_MS).unwrap();
+    assert_ne!(other.account_id, issued.account_id);
+    assert_eq!(signer.issue(" ", NOW_MS), Err(LobbyRejection::InvalidName));
+}
+
+#[test]
+fn missing_tampered_and_expired_tokens_are_refused() {
+    let signer = signer();
+    let issued = signer.issue("Ray", NOW_MS).unwrap();
+    assert_eq!(signer.verify(None, NOW_MS), Err(AuthError::Missing));
+    assert_eq!(signer.verify(Some(""), NOW_MS), Err(AuthError::Missing));
+    assert_eq!(
+        signer.verify(Some("not-a-token"), NOW_MS),
+        Err(AuthError::Malformed)
+    );
+
+    let (payload, signature) = issued.token.split_once('.').unwrap();
+    let mut forged = signature.to_string();
+    let last = if forged.ends_with('0') { "1" } else { "0" };
+    forged.replace_range(forged.len() - 1.., last);
+    assert_eq!(
+        signer.verify(Some(&format!("{}.{}", payload, forged)), NOW_MS),
+        Err(AuthError::BadSignature)
+    );
+
+    let stranger = TokenSigner::new(b"other-secret", Duration::from_secs(60));
+    assert_eq!(
+        stranger.verify(Some(&issued.token), NOW_MS),
+        Err(AuthError::BadSignature)
+    );
+    assert_eq!(
+        signer.verify(Some(&issued.token), NOW_MS + 60_000),
+        Err(AuthError::Expired)
+    );
+}
+
+#[test]
+fn generated_secrets_survive_a_checkpoint_but_configured_ones_do_not() {
+    let config = AppConfig::default();
+    let state = AppState::from_config(&config);
+    let issued = state.auth().issue("Ray", NOW_MS).unwrap();
+    let checkpoint = state.checkpoint();
+    assert!(checkpoint.auth_secret.is_some());
+
+    let restored = AppState::restore(&config, &checkpoint, Instant::now());
+    assert!(restored.auth().verify(Some(&issued.token), NOW_MS).is_ok());
+
+    let configured = AppConfig {
+        auth_secret: "from-config".to_string(),
+        ..AppConfig::default()
+    };
+    let state = AppState::from_config(&configured);
+    assert_eq!(state.checkpoint().auth_secret, None);
+    let fresh = AppState::restore(
+        &AppConfig::default(),
+        &Checkpoint {
+            auth_secret: None,
+            ..state.checkpoint()
+        },
+        Instant::now(),
+    );
+    let issued = state.auth().issue("Ray", NOW_MS).unwrap();
+    assert!(fresh.auth().verify(Some(&issued.token), NOW_MS).is_err());
+}
+
+#[tokio::test]
+async fn only_guests_with_a_token_create_rooms() {
+    let state = AppState::new();
+    let request = || {
+        Json(CreateRoomRequest {
+            name: "Haunted".to_string(),
+        })
+    };
+    let refused = http::create_room(State(state.clone()), HeaderMap::new(), request()).await;
+    assert_eq!(refused.status(), StatusCode::UNAUTHORIZED);
+    assert!(state.lobby().open_rooms().is_empty());
+
+    let issued = state
+        .auth()
+        .issue("Ray", server::services::auth::unix_ms())
+        .unwrap();
+    let mut headers = HeaderMap::new();
+    let bearer = format!("Bearer {}", issued.token);
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_str(&bearer).unwrap(),
+    );
+    let created = http::create_room(State(state.clone()), headers, request()).await;
+    assert_eq!(created.status(), StatusCode::OK);
+    assert_eq!(state.lobby().open_rooms().len(), 1);
+}
+
+#[test]
+fn resume_tokens_only_resume_for_their_own_account() {
+    let signer = signer();
+    let ray = signer
+        .verify(Some(&signer.issue("Ray", NOW_MS).unwrap().token), NOW_MS)
+        .unwrap();
+    let egon = signer
+        .verify(Some(&signer.issue("Egon", NOW_MS).unwrap().token), NOW_MS)
+        .unwrap();
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let mut sessions = SessionRegistry::new();
+    let now = Instant::now();
+
+    let (handle, _) = ws::handle_authenticated_hello(
+        &mut sessions,
+        &lobby,
+        &ray,
+        PROTOCOL_VERSION,
+        None,
+        &[],
+        now,
+    )
+    .unwrap();
+    let mut player = None;
+    ws::handle_authenticated_message(
+        &mut lobby,
+        &mut player,
+        &ray,
+        ClientMessage::JoinLobby {
+            display_name: "Somebody Else".to_string(),
+            room_code: code.clone(),
+        },
+    );
+    sessions.bind_player(&handle.token, player);
+    sessions.disconnect(&handle, now);
+    let room = lobby.room(&code).unwrap().state();
+    assert_eq!(room.members[0].display_name, "Ray");
+
+    let (hijack, _) = ws::handle_authenticated_hello(
+        &mut sessions,
+        &lobby,
+        &egon,
+        PROTOCOL_VERSION,
+        Some(&handle.token),
+        &[],
+        now + Duration::from_secs(1),
+    )
+    .unwrap();
+    assert_ne!(hijack.token, handle.token);
+    assert_eq!(hijack.player, None);
+
+    let (resumed, welcome) = ws::handle_authenticated_hello(
+        &mut sessions,
+        &lobby,
+        &ray,
+        PROTOCOL_VERSION,
+        Some(&handle.token),
+        &[WireCodec::Binary],
+        now + Duration::from_secs(1),
+    )
+    .unwrap();
+    assert_eq!(resumed.player, player);
+    assert!(matches!(
+        welcome,
+        ServerMessage::Welcome { player_id, codec: WireCodec::Binary, .. } if player_id == player
+    ));
+    assert_eq!(
+        sessions.account(&resumed.token),
+        Some(ray.account_id.as_str())
+    );
+}
+
+// Carries each cursed object to its ritual spot, in order.
+fn contain_the_onryo(lobby: &mut Lobby, investigator: u32) {
+    let exorcism = lobby
+        .room_of(investigator)
+        .and_then(|room| room.game())
+        .map(|game| game.layout().exorcism.clone())
+        .unwrap();
+    for (object, spot) in exorcism
+        .onryo_cursed_positions
+        .iter()
+        .zip(&exorcism.onryo_ritual_positions)
+    {
+        assert!(lobby.place_player(investigator, *object));
+        lobby.interact(investigator).unwrap();
+        assert!(lobby.place_player(investigator, *spot));
+        lobby.interact(investigator).unwrap();
+    }
+}
+
+#[test]
+fn career_stats_follow_the_account() {
+    let signer = signer();
+    let ray = signer
+        .verify(Some(&signer.issue("Ray", NOW_MS).unwrap().token), NOW_MS)
+        .unwrap();
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let mut player = None;
+    ws::handle_authenticated_message(
+        &mut lobby,
+        &mut player,
+        &ray,
+        ClientMessage::JoinLobby {
+            display_name: "Ray".to_string(),
+            room_code: code,
+        },
+    );
+    let investigator = player.unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Onryo).unwrap();
+    lobby.start_round(ghost).unwrap();
+    lobby.step(0.5);
+    lobby.journal_guess(investigator, GhostType::Onryo).unwrap();
+    lobby.journal_confirm(investigator).unwrap();
+    contain_the_onryo(&mut lobby, investigator);
+    lobby.claim_exorcism(investigator).unwrap();
+
+    assert!(lobby.profile(&ray.account_id).is_some());
+    assert!(lobby.profile("Ray").is_none());
+    let reply = ws::handle_authenticated_message(
+        &mut lobby,
+        &mut player,
+        &ray,
+        ClientMessage::RequestProfile {
+            display_name: "Casper".to_string(),
+        },
+    );
+    assert!(matches!(
+        reply,
+        Some(ServerMessage::Profile(profile)) if profile.games_as_investigator == 1
+    ));
+}
@@ -0,0 +1,258 @@
+use server::services::lobby::{Lobby, Outbound};
+use server::services::ws::handle_client_message;
+use shared::prelude::{
+    ChatChannel, ChatRejection, ClientMessage, Equipment, GhostType, PlayerId, Role, ServerMessage,
+    Vec3,
+};
+
+const MAIN_ROOM_WEST: Vec3 = Vec3::new(-6.0, 0.9, -6.0);
+const MAIN_ROOM_SOUTH: Vec3 = Vec3::new(-6.0, 0.9, 6.0);
+const SIDE_ROOM_NORTH: Vec3 = Vec3::new(6.0, 1.6, -6.0);
+const SIDE_ROOM_SOUTH: Vec3 = Vec3::new(6.0, 0.9, 6.0);
+
+struct Table {
+    code: String,
+    ghost: PlayerId,
+    ray: PlayerId,
+    egon: PlayerId,
+}
+
+fn lobby_with_roles() -> (Lobby, Table) {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let ray = lobby.join(&code, "Ray").unwrap();
+    let egon = lobby.join(&code, "Egon").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Spirit).unwrap();
+    lobby.choose_role(ray, Role::Investigator).unwrap();
+    lobby.choose_role(egon, Role::Investigator).unwrap();
+    lobby.drain_outbound();
+    (
+        lobby,
+        Table {
+            code,
+            ghost,
+            ray,
+            egon,
+        },
+    )
+}
+
+fn chat_recipients(outbound: &[Outbound]) -> Vec<PlayerId> {
+    channel_recipients(outbound, |_| true)
+}
+
+fn channel_recipients(
+    outbound: &[Outbound],
+    channel: impl Fn(ChatChannel) -> bool,
+) -> Vec<PlayerId> {
+    let mut recipients: Vec<PlayerId> = outbound
+        .iter()
+        .filter(
+            |out| matches!(&out.message, ServerMessage::Chat(message) if channel(message.channel)),
+        )
+        .flat_map(|out| out.recipients.clone())
+        .collect();
+    recipients.sort_unstable();
+    recipients
+}
+
+fn start_in_positions(lobby: &mut Lobby, table: &Table, ghost: Vec3, ray: Vec3, egon: Vec3) {
+    lobby.start_round(table.ghost).unwrap();
+    for (player, position) in [(table.ghost, ghost), (table.ray, ray), (table.egon, egon)] {
+        assert!(lobby.place_player(player, position));
+    }
+    lobby.drain_outbound();
+}
+
+#[test]
+fn the_lobby_channel_reaches_everyone_before_the_round() {
+    let (mut lobby, table) = lobby_with_roles();
+    lobby
+        .chat(table.ghost, ChatChannel::Lobby, "  boo  ")
+        .unwrap();
+
+    let outbound = lobby.drain_outbound();
+    let mut recipients = chat_recipients(&outbound);
+    recipients.sort_unstable();
+    assert_eq!(recipients, vec![table.ghost, table.ray, table.egon]);
+    let Some(ServerMessage::Chat(message)) = outbound.first().map(|out| &out.message) else {
+        panic!("expected a chat message");
+    };
+    assert_eq!(message.text, "boo");
+    assert_eq!(message.display_name, "Casper");
+    assert_eq!(message.from, table.ghost);
+
+    assert_eq!(
+        lobby.chat(table.ray, ChatChannel::Team, "psst"),
+        Err(ChatRejection::ChannelClosed)
+    );
+}
+
+#[test]
+fn the_ghost_cannot_read_or_use_the_team_channel() {
+    let (mut lobby, table) = lobby_with_roles();
+    start_in_positions(
+        &mut lobby,
+        &table,
+        SIDE_ROOM_NORTH,
+        MAIN_ROOM_WEST,
+        MAIN_ROOM_SOUTH,
+    );
+    let watcher = lobby.spectate(&table.code, "Stream").unwrap();
+    lobby.drain_outbound();
+
+    lobby
+        .chat(table.ray, ChatChannel::Team, "EMF 5 upstairs")
+        .unwrap();
+    let recipients = chat_recipients(&lobby.drain_outbound());
+    assert_eq!(recipients, vec![table.ray, table.egon, watcher]);
+
+    assert_eq!(
+        lobby.chat(table.ghost, ChatChannel::Team, "hello"),
+        Err(ChatRejection::ChannelClosed)
+    );
+    assert_eq!(
+        lobby.chat(table.ray, ChatChannel::Lobby, "hello"),
+        Err(ChatRejection::ChannelClosed)
+    );
+    assert_eq!(
+        lobby.chat(watcher, ChatChannel::Team, "hello"),
+        Err(ChatRejection::ChannelClosed)
+    );
+    assert!(chat_recipients(&lobby.drain_outbound()).is_empty());
+}
+
+#[test]
+fn chat_messages_are_validated() {
+    let (mut lobby, table) = lobby_with_roles();
+    assert_eq!(
+        lobby.chat(table.ray, ChatChannel::Lobby, "   "),
+        Err(ChatRejection::Empty)
+    );
+    assert_eq!(
+        lobby.chat(table.ray, ChatChannel::Lobby, &"x".repeat(500)),
+        Err(ChatRejection::TooLong)
+    );
+    assert_eq!(
+        lobby.chat(999, ChatChannel::Lobby, "hi"),
+        Err(ChatRejection::NotInRoom)
+    );
+}
+
+#[test]
+fn chat_message_replies_only_on_rejection() {
+    let (mut lobby, table) = lobby_with_roles();
+    let mut player = Some(table.ray);
+    let reply = handle_client_message(
+        &mut lobby,
+        &mut player,
+        ClientMessage::Chat {
+            channel: ChatChannel::Lobby,
+            text: "ready".to_string(),
+        },
+    );
+    assert!(reply.is_none());
+
+    let mut nobody = None;
+    let reply = handle_client_message(
+        &mut lobby,
+        &mut nobody,
+        ClientMessage::Chat {
+            channel: ChatChannel::Lobby,
+            text: "ready".to_string(),
+        },
+    );
+    assert!(matches!(
+        reply,
+        Some(ServerMessage::ChatRejected(ChatRejection::NotInRoom))
+    ));
+}
+
+#[test]
+fn team_chat_needs_the_same_room_range_or_a_radio() {
+    let (mut lobby, table) = lobby_with_roles();
+    start_in_positions(
+        &mut lobby,
+        &table,
+        SIDE_ROOM_NORTH,
+        MAIN_ROOM_WEST,
+        SIDE_ROOM_SOUTH,
+    );
+
+    lobby.chat(table.ray, ChatChannel::Team, "anyone?").unwrap();
+    assert_eq!(chat_recipients(&lobby.drain_outbound()), vec![table.ray]);
+
+    assert!(lobby.place_player(table.ray, Vec3::new(-1.0, 0.9, -4.0)));
+    assert!(lobby.place_player(table.egon, Vec3::new(2.5, 0.9, -4.0)));
+    lobby.chat(table.ray, ChatChannel::Team, "closer").unwrap();
+    let mut expected = vec![table.ray, table.egon];
+    expected.sort_unstable();
+    assert_eq!(
+        channel_recipients(&lobby.drain_outbound(), |channel| channel
+            == ChatChannel::Team),
+        expected
+    );
+
+    assert!(lobby.place_player(table.egon, SIDE_ROOM_SOUTH));
+    assert!(lobby.select_equipment(table.ray, Equipment::Radio));
+    assert!(!lobby.select_equipment(table.ghost, Equipment::Radio));
+    lobby.chat(table.ray, ChatChannel::Team, "over").unwrap();
+    assert_eq!(
+        channel_recipients(&lobby.drain_outbound(), |channel| channel
+            == ChatChannel::Team),
+        expected
+    );
+}
+
+#[test]
+fn the_ghost_overhears_its_own_room() {
+    let (mut lobby, table) = lobby_with_roles();
+    start_in_positions(
+        &mut lobby,
+        &table,
+        SIDE_ROOM_NORTH,
+        MAIN_ROOM_WEST,
+        SIDE_ROOM_SOUTH,
+    );
+
+    lobby
+        .chat(table.egon, ChatChannel::Team, "cold spot")
+        .unwrap();
+    let outbound = lobby.drain_outbound();
+    assert_eq!(
+        channel_recipients(&outbound, |channel| channel == ChatChannel::Overheard),
+        vec![table.ghost]
+    );
+    assert_eq!(
+        channel_recipients(&outbound, |channel| channel == ChatChannel::Team),
+        vec![table.egon]
+    );
+
+    assert!(lobby.select_equipment(table.ray, Equipment::Radio));
+    lobby
+        .chat(table.ray, ChatChannel::Team, "on my way")
+        .unwrap();
+    let outbound = lobby.drain_outbound();
+    assert!(channel_recipients(&outbound, |channel| channel == ChatChannel::Overheard).is_empty());
+
+    let mut player = Some(table.egon);
+    let reply = handle_client_message(
+        &mut lobby,
+        &mut player,
+        ClientMessage::SelectEquipment {
+            equipment: Equipment::Radio,
+        },
+    );
+    assert!(reply.is_none());
+    lobby
+        .chat(table.egon, ChatChannel::Team, "still here")
+        .unwrap();
+    let outbound = lobby.drain_outbound();
+    assert_eq!(
+        channel_recipients(&outbound, |channel| channel == ChatChannel::Overheard),
+        vec![table.ghost]
+    );
+    assert_eq!(chat_recipients(&outbound).len(), 3);
+}
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use server::core::config::{ConfigError, LogFormat};
+use server::core::AppConfig;
+
+fn no_env(_: &str) -> Option<String> {
+    None
+}
+
+fn from_toml(text: &str) -> Result<AppConfig, ConfigError> {
+    AppConfig::from_sources(Some((Path::new("server.toml"), text)), no_env)
+}
+
+#[test]
+fn defaults_apply_without_a_file() {
+    let config = AppConfig::from_sources(None, no_env).unwrap();
+    assert_eq!(config, AppConfig::default());
+    assert_eq!(config.tick_rate, 20);
+}
+
+#[test]
+fn file_values_and_partial_tuning_overrides_are_read() {
+    let config = from_toml(
+        r#"
+        addr = "127.0.0.1:9000"
+        tick_rate = 30
+        max_players_per_room = 4
+        log_format = "compact"
+
+        [evidence]
+        emf_range_4 = 2.0
+
+        [exorcism.banshee]
+        interact_distance = 2.0
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.addr.port(), 9000);
+    assert_eq!(config.tick_rate, 30);
+    assert_eq!(config.max_players_per_room, 4);
+    assert_eq!(config.log_format, LogFormat::Compact);
+    assert_eq!(config.evidence.emf_range_4, 2.0);
+    assert_eq!(config.evidence.emf_range_3, 4.5);
+    assert_eq!(config.exorcism.banshee.interact_distance, 2.0);
+    assert_eq!(config.exorcism.banshee.timing_max, 3.5);
+    assert_eq!(config.lobby_settings().max_players_per_room, 4);
+}
+
+#[test]
+fn env_overrides_the_file() {
+    let env = |name: &str| match name {
+        "TICK_RATE" => Some("40".to_string()),
+        "LOG_FORMAT" => Some("pretty".to_string()),
+        _ => None,
+    };
+    let config =
+        AppConfig::from_sources(Some((Path::new("server.toml"), "tick_rate = 30")), env).unwrap();
+    assert_eq!(config.tick_rate, 40);
+    assert_eq!(config.log_format, LogFormat::Pretty);
+}
+
+#[test]
+fn bad_env_values_are_reported() {
+    let env = |name: &str| (name == "TICK_RATE").then(|| "fast".to_string());
+    assert_eq!(
+        AppConfig::from_sources(None, env),
+        Err(ConfigError::Env {
+            name: "TICK_RATE",
+            value: "fast".to_string()
+        })
+    );
+}
+
+#[test]
+fn unknown_keys_and_invalid_values_are_rejected() {
+    assert!(matches!(
+        from_toml("tick_rat = 30"),
+        Err(ConfigError::Parse { .. })
+    ));
+    assert!(matches!(
+        from_toml("tick_rate = 0"),
+        Err(ConfigError::Invalid {
+            field: "tick_rate",
+            ..
+        })
+    ));
+    assert!(matches!(
+        from_toml("heartbeat_ms = 20000\nidle_timeout_ms = 10000"),
+        Err(ConfigError::Invalid {
+            field: "idle_timeout_ms",
+            ..
+        })
+    ));
+    assert!(matches!(
+        from_toml("max_players_per_room = 3\n[quick_play]\ninvestigators = 3"),
+        Err(ConfigError::Invalid {
+            field: "quick_play.investigators",
+            ..
+        })
+    ));
+    assert!(matches!(
+        from_toml("max_players_per_room = 6"),
+        Err(ConfigError::Invalid {
+            field: "max_players_per_room",
+            ..
+        })
+    ));
+    assert!(matches!(
+        from_toml("chat_range = -1.0"),
+        Err(ConfigError::Invalid {
+            field: "chat_range",
+            ..
+        })
+    ));
+    assert!(matches!(
+        from_toml("[hunt]\nkill_radius = 0.0"),
+        Err(ConfigError::Invalid { field: "hunt", .. })
+    ));
+    assert!(matches!(
+        from_toml("[exorcism.banshee]\ntiming_min = 4.0"),
+        Err(ConfigError::Invalid { .. })
+    ));
+}
+
+#[test]
+fn errors_read_clearly() {
+    let err = from_toml("tick_rate = 500").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid tick_rate: must be between 1 and 120 (got 500)"
+    );
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use server::services::health::{health, readiness};
+use server::services::lobby::LobbyStats;
+use shared::prelude::PROTOCOL_VERSION;
+
+const PERIOD: Duration = Duration::from_millis(50);
+
+#[test]
+fn health_reports_build_info() {
+    let payload = health();
+    assert_eq!(payload.status, "ok");
+    assert_eq!(payload.version, env!("CARGO_PKG_VERSION"));
+    assert!(!payload.build.is_empty());
+    assert_eq!(payload.protocol_version, PROTOCOL_VERSION);
+}
+
+#[test]
+fn ready_while_the_scheduler_ticks() {
+    let stats = LobbyStats {
+        rooms: 3,
+        rooms_full: 1,
+        ..LobbyStats::default()
+    };
+    let report = readiness(Some(Duration::from_millis(20)), Some(PERIOD), &stats);
+    assert!(report.ready);
+    assert!(report.scheduler_on_time);
+    assert_eq!(report.rooms, 3);
+    assert_eq!(report.rooms_at_capacity, 1);
+    assert_eq!(report.tick_period_ms, Some(50));
+    assert_eq!(report.health.status, "ok");
+}
+
+#[test]
+fn not_ready_when_the_scheduler_stalls_or_never_ran() {
+    let stats = LobbyStats::default();
+    let stalled = readiness(Some(Duration::from_secs(1)), Some(PERIOD), &stats);
+    assert!(!stalled.ready);
+    assert!(!stalled.scheduler_on_time);
+    assert_eq!(stalled.last_tick_ms, Some(1000));
+
+    let idle = readiness(None, None, &stats);
+    assert!(!idle.ready);
+}
+
+#[test]
+fn not_ready_while_draining() {
+    let stats = LobbyStats {
+        draining: true,
+        ..LobbyStats::default()
+    };
+    let report = readiness(Some(Duration::ZERO), Some(PERIOD), &stats);
+    assert!(report.scheduler_on_time);
+    assert!(!report.ready);
+    assert!(report.draining);
+    assert_eq!(report.health.status, "draining");
+}
@@ -0,0 +1,193 @@
+use server::services::lobby::{Lobby, LobbySettings, Outbound};
+use shared::hunt::HuntTuning;
+use shared::prelude::{
+    ChatChannel, ChatRejection, ClaimRejection, GhostType, LobbyRejection, MatchSnapshot, PlayerId,
+    Role, RoundOutcome, ServerMessage, Vec3,
+};
+
+fn hunting_lobby(cooldown_seconds: f32) -> (Lobby, PlayerId, PlayerId, PlayerId) {
+    let mut lobby = Lobby::with_settings(LobbySettings {
+        hunt: HuntTuning {
+            cooldown_seconds,
+            duration_seconds: 2.0,
+            kill_radius: 1.2,
+        },
+        ..LobbySettings::default()
+    });
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let ray = lobby.join(&code, "Ray").unwrap();
+    let egon = lobby.join(&code, "Egon").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Banshee).unwrap();
+    lobby.choose_role(ray, Role::Investigator).unwrap();
+    lobby.choose_role(egon, Role::Investigator).unwrap();
+    lobby.start_round(ghost).unwrap();
+    assert!(lobby.place_player(ghost, Vec3::new(0.0, 1.6, 0.0)));
+    assert!(lobby.place_player(ray, Vec3::new(0.5, 0.9, 0.0)));
+    assert!(lobby.place_player(egon, Vec3::new(4.0, 0.9, 0.0)));
+    (lobby, ghost, ray, egon)
+}
+
+fn hunt_updates(outbound: &[Outbound]) -> Vec<bool> {
+    outbound
+        .iter()
+        .filter_map(|out| match out.message {
+            ServerMessage::Hunt { active, .. } => Some(active),
+            _ => None,
+        })
+        .collect()
+}
+
+fn eliminated(outbound: &[Outbound]) -> Vec<PlayerId> {
+    outbound
+        .iter()
+        .filter_map(|out| match out.message {
+            ServerMessage::Eliminated { player } => Some(player),
+            _ => None,
+        })
+        .collect()
+}
+
+fn snapshot_for(outbound: &[Outbound], player: PlayerId) -> &MatchSnapshot {
+    outbound
+        .iter()
+        .filter(|out| out.recipients.contains(&player))
+        .find_map(|out| match &out.message {
+            ServerMessage::Snapshot(snapshot) => Some(snapshot),
+            _ => None,
+        })
+        .expect("snapshot")
+}
+
+#[test]
+fn investigators_only_see_the_ghost_while_it_hunts() {
+    let (mut lobby, ghost, ray, _) = hunting_lobby(1.0);
+    let shows_ghost = |snapshot: &MatchSnapshot| snapshot.players.iter().any(|p| p.id == ghost);
+    lobby.drain_outbound();
+    lobby.step(1.0);
+    let outbound = lobby.drain_outbound();
+    assert!(!shows_ghost(snapshot_for(&outbound, ray)));
+    assert!(snapshot_for(&outbound, ray)
+        .players
+        .iter()
+        .any(|player| player.id == ray));
+    assert!(shows_ghost(snapshot_for(&outbound, ghost)));
+
+    // Keep the ghost out of reach so the hunt catches nobody.
+    assert!(lobby.place_player(ghost, Vec3::new(-6.0, 1.6, 6.0)));
+    lobby.start_hunt(ghost).unwrap();
+    lobby.step(0.05);
+    assert!(shows_ghost(snapshot_for(&lobby.drain_outbound(), ray)));
+}
+
+#[test]
+fn only_the_ghost_hunts_and_only_after_the_cooldown() {
+    let (mut lobby, ghost, ray, _) = hunting_lobby(1.0);
+    let outbound = lobby.drain_outbound();
+    assert!(outbound.iter().any(|out| out.recipients == vec![ghost]
+        && matches!(
+            out.message,
+            ServerMessage::Hunt {
+                active: false,
+                remaining_ms: 1_000
+            }
+        )));
+
+    assert_eq!(lobby.start_hunt(ghost), Err(ClaimRejection::HuntNotReady));
+    assert_eq!(lobby.start_hunt(ray), Err(ClaimRejection::WrongRole));
+    lobby.step(1.0);
+    assert_eq!(lobby.start_hunt(ray), Err(ClaimRejection::WrongRole));
+    lobby.drain_outbound();
+
+    assert_eq!(lobby.start_hunt(ghost), Ok(()));
+    assert_eq!(lobby.start_hunt(ghost), Err(ClaimRejection::HuntNotReady));
+    let outbound = lobby.drain_outbound();
+    assert_eq!(hunt_updates(&outbound), vec![true]);
+    assert_eq!(outbound[0].recipients.len(), 3);
+
+    lobby.step(1.0);
+    assert!(hunt_updates(&lobby.drain_outbound()).is_empty());
+    lobby.step(1.0);
+    assert_eq!(hunt_updates(&lobby.drain_outbound()), vec![false]);
+    assert!(!lobby.room_of(ghost).unwrap().hunt().is_hunting());
+}
+
+#[test]
+fn caught_investigators_spectate_until_the_last_one_ends_the_round() {
+    let (mut lobby, ghost, ray, egon) = hunting_lobby(0.0);
+    lobby.start_hunt(ghost).unwrap();
+    lobby.drain_outbound();
+
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+    assert_eq!(eliminated(&outbound), vec![ray]);
+    let room = lobby.room_of(ghost).unwrap();
+    assert_eq!(room.member(ray).unwrap().role, Some(Role::Spectator));
+    assert_eq!(room.eliminated(), &[ray]);
+    assert!(room.game().unwrap().player(ray).is_none());
+    assert!(room.in_round());
+    assert_eq!(
+        lobby.chat(ray, ChatChannel::Team, "help"),
+        Err(ChatRejection::ChannelClosed)
+    );
+
+    assert!(lobby.place_player(egon, Vec3::new(-0.8, 0.9, 0.4)));
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+    assert_eq!(eliminated(&outbound), vec![egon]);
+    assert!(outbound.iter().any(|out| matches!(
+        out.message,
+        ServerMessage::RoundEnded(summary)
+            if summary.outcome == RoundOutcome::InvestigatorsDead && summary.guess.is_none()
+    )));
+
+    let room = lobby.room_of(ghost).unwrap();
+    assert!(!room.in_round());
+    assert!(room.eliminated().is_empty());
+    assert_eq!(room.member(ray).unwrap().role, Some(Role::Investigator));
+    assert_eq!(room.member(egon).unwrap().role, Some(Role::Investigator));
+    let profile = lobby.profile("Ray").unwrap();
+    assert_eq!(profile.games_as_investigator, 1);
+    assert_eq!(profile.outcomes_as_investigator.investigators_dead, 1);
+}
+
+#[test]
+fn caught_investigators_keep_their_player_seat() {
+    let (mut lobby, ghost, ray, _) = hunting_lobby(0.0);
+    lobby.start_hunt(ghost).unwrap();
+    lobby.step(0.05);
+    let code = lobby.room_of(ghost).unwrap().code().to_string();
+    let info = lobby.room_of(ghost).unwrap().info();
+    assert_eq!(info.players, 3);
+    assert_eq!(info.spectators, 0);
+
+    for name in ["Peter", "Winston", "Janine", "Louis"] {
+        lobby.spectate(&code, name).unwrap();
+    }
+    assert_eq!(
+        lobby.spectate(&code, "Slimer"),
+        Err(LobbyRejection::RoomFull)
+    );
+    let room = lobby.room_of(ghost).unwrap();
+    assert_eq!(room.eliminated(), &[ray]);
+    assert_eq!(room.info().spectators, 4);
+    assert_eq!(room.player_count(), 3);
+}
+
+#[test]
+fn caught_investigators_learn_the_ghost_type_only_when_the_round_ends() {
+    let (mut lobby, ghost, ray, _) = hunting_lobby(0.0);
+    let code = lobby.room_of(ghost).unwrap().code().to_string();
+    let peter = lobby.spectate(&code, "Peter").unwrap();
+    lobby.start_hunt(ghost).unwrap();
+    lobby.step(0.05);
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+    assert_eq!(lobby.room_of(ghost).unwrap().eliminated(), &[ray]);
+    assert_eq!(snapshot_for(&outbound, ray).ghost_type, None);
+    assert_eq!(
+        snapshot_for(&outbound, peter).ghost_type,
+        Some(GhostType::Banshee)
+    );
+}
@@ -0,0 +1,191 @@
+use server::services::lobby::{Lobby, LobbySettings, MAX_PLAYERS_PER_ROOM};
+use shared::prelude::{GhostType, HouseLayoutKind, LobbyRejection, Role, ServerMessage};
+
+fn lobby_with_room() -> (Lobby, String) {
+    let mut lobby = Lobby::new();
+    let room = lobby.create_room("Haunted").expect("create room");
+    (lobby, room.code)
+}
+
+#[test]
+fn created_rooms_are_listed_until_the_round_starts() {
+    let (mut lobby, code) = lobby_with_room();
+    assert_eq!(lobby.open_rooms().len(), 1);
+    assert_eq!(lobby.open_rooms()[0].name, "Haunted");
+
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.start_round(ghost).unwrap();
+
+    assert!(lobby.open_rooms().is_empty());
+    assert_eq!(
+        lobby
+            .room(&code)
+            .unwrap()
+            .game()
+            .unwrap()
+            .snapshot()
+            .players
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn room_names_are_validated() {
+    let mut lobby = Lobby::new();
+    assert_eq!(lobby.create_room("   "), Err(LobbyRejection::InvalidName));
+    assert_eq!(
+        lobby.create_room(&"x".repeat(64)),
+        Err(LobbyRejection::InvalidName)
+    );
+}
+
+#[test]
+fn joining_unknown_or_full_rooms_fails() {
+    let (mut lobby, code) = lobby_with_room();
+    assert_eq!(
+        lobby.join("ZZZZ9", "Ray"),
+        Err(LobbyRejection::RoomNotFound)
+    );
+    for index in 0..MAX_PLAYERS_PER_ROOM {
+        lobby.join(&code, &format!("player {index}")).unwrap();
+    }
+    assert_eq!(lobby.join(&code, "late"), Err(LobbyRejection::RoomFull));
+    assert!(lobby.open_rooms().is_empty());
+}
+
+#[test]
+fn only_one_ghost_per_room() {
+    let (mut lobby, code) = lobby_with_room();
+    let first = lobby.join(&code, "Casper").unwrap();
+    let second = lobby.join(&code, "Slimer").unwrap();
+    lobby.choose_role(first, Role::Ghost).unwrap();
+    assert_eq!(
+        lobby.choose_role(second, Role::Ghost),
+        Err(LobbyRejection::GhostTaken)
+    );
+
+    lobby.choose_role(first, Role::Investigator).unwrap();
+    assert_eq!(lobby.choose_role(second, Role::Ghost), Ok(()));
+}
+
+#[test]
+fn round_needs_a_ghost_and_an_investigator() {
+    let (mut lobby, code) = lobby_with_room();
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    assert_eq!(
+        lobby.start_round(ghost),
+        Err(LobbyRejection::RolesIncomplete)
+    );
+
+    let undecided = lobby.join(&code, "Ray").unwrap();
+    assert_eq!(
+        lobby.start_round(ghost),
+        Err(LobbyRejection::RolesIncomplete)
+    );
+
+    lobby.choose_role(undecided, Role::Investigator).unwrap();
+    lobby.drain_outbound();
+    assert_eq!(lobby.start_round(undecided), Ok(()));
+
+    let started: Vec<_> = lobby
+        .drain_outbound()
+        .into_iter()
+        .filter(|outbound| matches!(outbound.message, ServerMessage::RoundStarted { .. }))
+        .collect();
+    assert_eq!(started.len(), 2);
+    assert!(started
+        .iter()
+        .all(|outbound| outbound.recipients.len() == 1));
+}
+
+#[test]
+fn only_the_ghost_configures_the_round() {
+    let (mut lobby, code) = lobby_with_room();
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+
+    assert_eq!(
+        lobby.choose_layout(investigator, HouseLayoutKind::ThreeRoom),
+        Err(LobbyRejection::NotGhost)
+    );
+    lobby
+        .choose_layout(ghost, HouseLayoutKind::ThreeRoom)
+        .unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Onryo).unwrap();
+
+    let room = lobby.room(&code).unwrap();
+    assert_eq!(room.state().layout, HouseLayoutKind::ThreeRoom);
+    assert_eq!(room.ghost_type(), GhostType::Onryo);
+}
+
+#[test]
+fn empty_rooms_close_when_the_last_player_leaves() {
+    let (mut lobby, code) = lobby_with_room();
+    let first = lobby.join(&code, "Casper").unwrap();
+    let second = lobby.join(&code, "Ray").unwrap();
+
+    lobby.leave(first);
+    assert_eq!(lobby.room(&code).unwrap().state().members.len(), 1);
+    lobby.leave(second);
+    assert!(lobby.room(&code).is_none());
+}
+
+#[test]
+fn room_updates_reach_every_member() {
+    let (mut lobby, code) = lobby_with_room();
+    let first = lobby.join(&code, "Casper").unwrap();
+    let second = lobby.join(&code, "Ray").unwrap();
+    lobby.drain_outbound();
+
+    lobby.choose_role(second, Role::Investigator).unwrap();
+    let outbound = lobby.drain_outbound();
+    assert_eq!(outbound.len(), 1);
+    assert_eq!(outbound[0].recipients, vec![first, second]);
+    assert!(matches!(outbound[0].message, ServerMessage::RoomUpdate(_)));
+}
+
+#[test]
+fn room_limits_follow_the_lobby_settings() {
+    let mut lobby = Lobby::with_settings(LobbySettings {
+        max_rooms: 1,
+        max_players_per_room: 2,
+        ..LobbySettings::default()
+    });
+    let code = lobby.create_room("Haunted").unwrap().code;
+    assert_eq!(
+        lobby.create_room("Other"),
+        Err(LobbyRejection::TooManyRooms)
+    );
+
+    lobby.join(&code, "Casper").unwrap();
+    lobby.join(&code, "Ray").unwrap();
+    assert_eq!(lobby.join(&code, "Egon"), Err(LobbyRejection::RoomFull));
+    assert_eq!(lobby.room(&code).unwrap().info().max_players, 2);
+    assert_eq!(lobby.stats().rooms_full, 1);
+}
+
+#[test]
+fn rooms_nobody_joins_close_after_the_ttl() {
+    let mut lobby = Lobby::with_settings(LobbySettings {
+        max_rooms: 1,
+        empty_room_ttl_ms: 1_000,
+        ..LobbySettings::default()
+    });
+    let unused = lobby.create_room("Haunted").unwrap().code;
+    lobby.step(0.6);
+    assert!(lobby.room(&unused).is_some());
+    lobby.step(0.6);
+    assert!(lobby.room(&unused).is_none());
+
+    let used = lobby.create_room("Haunted").unwrap().code;
+    lobby.join(&used, "Casper").unwrap();
+    lobby.step(2.0);
+    assert!(lobby.room(&used).is_some());
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use axum::extract::ws::Message;
+use server::services::lobby::Lobby;
+use server::services::metrics::Metrics;
+use server::services::ws::{classify_message, SocketAction};
+use shared::prelude::{ClaimRejection, LobbyRejection, Role, ServerMessage};
+
+#[test]
+fn inbound_messages_are_classified_by_variant() {
+    let (action, kind) = classify_message(Message::Text("ping".into()));
+    assert!(matches!(action, SocketAction::Send(_)));
+    assert_eq!(kind, "Ping");
+
+    let (_, kind) = classify_message(Message::Text(r#""StartRound""#.into()));
+    assert_eq!(kind, "StartRound");
+
+    let (_, kind) = classify_message(Message::Text("hello".into()));
+    assert_eq!(kind, "Unparsed");
+}
+
+#[test]
+fn lobby_stats_count_players_by_role() {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+
+    let stats = lobby.stats();
+    assert_eq!(stats.rooms, 1);
+    assert_eq!(stats.rounds, 0);
+    assert_eq!(stats.ghosts, 1);
+    assert_eq!(stats.investigators, 0);
+    assert_eq!(stats.unassigned, 1);
+}
+
+#[test]
+fn render_exposes_counters_and_tick_histogram() {
+    let mut metrics = Metrics::new();
+    metrics.connection_opened();
+    metrics.connection_opened();
+    metrics.connection_closed();
+    metrics.message_in("Input");
+    metrics.message_in("Input");
+    metrics.message_out(&ServerMessage::Pong);
+    metrics.message_out(&ServerMessage::LobbyRejected(LobbyRejection::RoomFull));
+    metrics.message_out(&ServerMessage::ClaimRejected(ClaimRejection::OutOfRange));
+    metrics.observe_tick(Duration::from_micros(800));
+    metrics.observe_tick(Duration::from_millis(30));
+    metrics.socket_lagged(12);
+
+    let mut lobby = Lobby::new();
+    lobby.create_room("Haunted").unwrap();
+    let text = metrics.render(&lobby.stats(), 3, 1);
+
+    for line in [
+        "mmf_ws_connections 1",
+        "mmf_ws_connections_total 2",
+        "mmf_ws_lagged_total 1",
+        "mmf_ws_missed_broadcasts_total 12",
+        "mmf_sessions 3",
+        "mmf_session_resumes_total 1",
+        "mmf_rooms 1",
+        "mmf_players{role=\"ghost\"} 0",
+        "mmf_messages_in_total{kind=\"Input\"} 2",
+        "mmf_messages_out_total{kind=\"Pong\"} 1",
+        "mmf_messages_out_total{kind=\"LobbyRejected\"} 1",
+        "mmf_rejected_messages_total{kind=\"lobby\",reason=\"RoomFull\"} 1",
+        "mmf_rejected_messages_total{kind=\"claim\",reason=\"OutOfRange\"} 1",
+        "mmf_evidence_misses_total{reason=\"wrong_ghost\"} 0",
+        "mmf_tick_duration_seconds_bucket{le=\"0.001\"} 1",
+        "mmf_tick_duration_seconds_bucket{le=\"0.05\"} 2",
+        "mmf_tick_duration_seconds_bucket{le=\"+Inf\"} 2",
+        "mmf_tick_duration_seconds_count 2",
+    ] {
+        assert!(
+            text.lines().any(|l| l == line),
+            "missing {:?} in\n{}",
+            line,
+            text
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use server::core::{AppConfig, AppState};
+use server::services::lobby::{Lobby, LobbySettings};
+use server::services::persistence::{CheckpointStore, PersistError};
+use server::services::referee::Referee;
+use server::services::simulation::MatchSimulation;
+use server::services::ws;
+use shared::prelude::{
+    EvidenceKind, GhostType, HouseLayoutKind, Role, RoundOutcome, ServerMessage, Vec3,
+    PROTOCOL_VERSION,
+};
+
+fn lobby_in_round() -> (Lobby, String, u32, u32) {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Onryo).unwrap();
+    lobby
+        .choose_layout(ghost, HouseLayoutKind::TwoRoom)
+        .unwrap();
+    lobby.start_round(ghost).unwrap();
+    lobby.drain_outbound();
+    (lobby, code, ghost, investigator)
+}
+
+fn scratch_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mmf-persistence-{}", std::process::id()));
+    dir.join(name)
+}
+
+#[test]
+fn rounds_survive_a_checkpoint_round_trip() {
+    let (mut lobby, code, _, investigator) = lobby_in_round();
+    lobby.step(0.05);
+    lobby.journal_guess(investigator, GhostType::Onryo).unwrap();
+    lobby.journal_confirm(investigator).unwrap();
+    let exorcism = lobby
+        .room(&code)
+        .and_then(|room| room.game())
+        .map(|game| game.layout().exorcism.clone())
+        .unwrap();
+    assert!(lobby.place_player(investigator, exorcism.onryo_cursed_positions[0]));
+    lobby.interact(investigator).unwrap();
+    let saved = lobby.checkpoint();
+
+    let mut restored = Lobby::restore(LobbySettings::default(), &saved);
+    assert_eq!(restored.checkpoint(), saved);
+    let room = restored.room(&code).expect("room restored");
+    assert_eq!(room.state(), lobby.room(&code).unwrap().state());
+    assert_eq!(room.ghost_type(), GhostType::Onryo);
+    assert_eq!(
+        room.game().unwrap().snapshot(),
+        lobby.room(&code).unwrap().game().unwrap().snapshot()
+    );
+
+    // The object picked up before the restart is still in hand.
+    for (stage, spot) in exorcism.onryo_ritual_positions.iter().enumerate() {
+        if stage > 0 {
+            let object = exorcism.onryo_cursed_positions[stage];
+            assert!(restored.place_player(investigator, object));
+            restored.interact(investigator).unwrap();
+        }
+        assert!(restored.place_player(investigator, *spot));
+        restored.interact(investigator).unwrap();
+    }
+    restored.claim_exorcism(investigator).unwrap();
+    let ended = restored.drain_outbound().into_iter().any(|outbound| {
+        matches!(
+            outbound.message,
+            ServerMessage::RoundEnded(summary) if summary.outcome == RoundOutcome::SuccessfulExorcism
+        )
+    });
+    assert!(ended);
+}
+
+#[test]
+fn new_players_never_reuse_restored_ids() {
+    let (lobby, code, _, investigator) = lobby_in_round();
+    let mut restored = Lobby::restore(LobbySettings::default(), &lobby.checkpoint());
+    restored.claim_exorcism(investigator).ok();
+    let other = restored.create_room("Other").unwrap().code;
+    let newcomer = restored.join(&other, "Egon").unwrap();
+    assert!(newcomer > investigator);
+    assert!(restored.room(&code).is_some());
+}
+
+#[test]
+fn collected_evidence_and_puzzle_progress_are_kept() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    game.add_player(1, Role::Ghost);
+    game.add_player(2, Role::Investigator);
+    game.place_player(1, Vec3::new(0.0, 1.6, 0.0));
+    game.place_player(2, Vec3::new(0.5, 0.9, 0.0));
+    let mut referee = Referee::new(GhostType::Spirit);
+    referee
+        .claim_evidence(&game, 2, EvidenceKind::EmfFive)
+        .unwrap();
+    referee.guess(&game, 2, GhostType::Spirit).unwrap();
+    referee.confirm(&game, 2).unwrap();
+
+    let mut restored = Referee::new(GhostType::Spirit);
+    restored.restore(&referee.checkpoint());
+    assert_eq!(restored.checkpoint(), referee.checkpoint());
+    assert_eq!(restored.evidence(2), &[EvidenceKind::EmfFive]);
+
+    let reloaded = MatchSimulation::restore(
+        HouseLayoutKind::TwoRoom,
+        game.tick(),
+        game.server_time(),
+        &game.checkpoint(),
+    );
+    assert_eq!(reloaded.snapshot(), game.snapshot());
+}
+
+#[test]
+fn restored_sessions_resume_into_their_round() {
+    let config = AppConfig::default();
+    let state = AppState::new();
+    let token = {
+        let mut lobby = state.lobby();
+        let code = lobby.create_room("Haunted").unwrap().code;
+        let ghost = lobby.join(&code, "Casper").unwrap();
+        let investigator = lobby.join(&code, "Ray").unwrap();
+        let stranger = lobby.join(&code, "Walter").unwrap();
+        lobby.choose_role(ghost, Role::Ghost).unwrap();
+        lobby.choose_role(investigator, Role::Investigator).unwrap();
+        lobby.choose_role(stranger, Role::Investigator).unwrap();
+        lobby.start_round(ghost).unwrap();
+        let mut sessions = state.sessions();
+        let handle = sessions.open();
+        sessions.bind_player(&handle.token, Some(investigator));
+        let ghost_session = sessions.open();
+        sessions.bind_player(&ghost_session.token, Some(ghost));
+        handle.token
+    };
+
+    let now = Instant::now();
+    let restored = AppState::restore(&config, &state.checkpoint(), now);
+    let stats = restored.lobby().stats();
+    assert_eq!(stats.rounds, 1);
+    assert_eq!(stats.ghosts + stats.investigators, 2);
+
+    let (handle, welcome) = ws::handle_hello(
+        &mut restored.sessions(),
+        &restored.lobby(),
+        PROTOCOL_VERSION,
+        Some(&token),
+        now + Duration::from_secs(1),
+    )
+    .expect("hello accepted");
+    let player = handle.player.expect("player kept");
+    assert!(matches!(
+        welcome,
+        ServerMessage::Welcome { room: Some(ref room), .. } if room.in_round
+    ));
+    assert!(matches!(
+        restored.lobby().rejoin_round(player),
+        Some(ServerMessage::RoundStarted { .. })
+    ));
+}
+
+#[test]
+fn store_round_trips_and_reports_missing_or_corrupt_files() {
+    let store = CheckpointStore::new(scratch_path("roundtrip.json"));
+    let _ = std::fs::remove_file(store.path());
+    assert_eq!(store.load(), Ok(None));
+
+    let state = AppState::new();
+    state.lobby().create_room("Haunted").unwrap();
+    let checkpoint = state.checkpoint();
+    store.save(&checkpoint).unwrap();
+    assert_eq!(store.load(), Ok(Some(checkpoint)));
+
+    std::fs::write(store.path(), "{ not json").unwrap();
+    assert!(matches!(store.load(), Err(PersistError::Corrupt { .. })));
+    let moved = store.quarantine().unwrap();
+    assert!(moved.exists());
+    assert_eq!(store.load(), Ok(None));
+    let _ = std::fs::remove_file(moved);
+}
+
+#[cfg(unix)]
+#[test]
+fn saved_checkpoints_are_readable_only_by_the_owner() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let store = CheckpointStore::new(scratch_path("owner-only.json"));
+    let checkpoint = AppState::new().checkpoint();
+    assert!(checkpoint.auth_secret.is_some());
+    store.save(&checkpoint).unwrap();
+    let mode = std::fs::metadata(store.path())
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+    let _ = std::fs::remove_file(store.path());
+}
@@ -0,0 +1,159 @@
+use server::services::lobby::{Lobby, LobbySettings};
+use server::services::profiles::{ProfileBook, RoundRecord};
+use server::services::ws;
+use shared::prelude::{
+    ClientMessage, GhostType, HouseLayoutKind, Role, RoundOutcome, ServerMessage,
+};
+
+fn round(
+    display_name: &str,
+    role: Role,
+    ghost_type: GhostType,
+    outcome: RoundOutcome,
+    seconds: f64,
+) -> RoundRecord<'_> {
+    RoundRecord {
+        display_name,
+        account: None,
+        role,
+        ghost_type,
+        layout: HouseLayoutKind::TwoRoom,
+        outcome,
+        seconds,
+    }
+}
+
+#[test]
+fn rounds_accumulate_into_career_stats() {
+    let mut book = ProfileBook::new();
+    book.record(round(
+        "Ray",
+        Role::Investigator,
+        GhostType::Banshee,
+        RoundOutcome::SuccessfulExorcism,
+        120.0,
+    ));
+    book.record(round(
+        "ray ",
+        Role::Investigator,
+        GhostType::Banshee,
+        RoundOutcome::SuccessfulExorcism,
+        80.0,
+    ));
+    book.record(round(
+        "RAY",
+        Role::Ghost,
+        GhostType::Spirit,
+        RoundOutcome::WrongGhost,
+        60.0,
+    ));
+
+    assert_eq!(book.len(), 1);
+    let profile = book.get("Ray").expect("profile kept");
+    assert_eq!(profile.display_name, "Ray");
+    assert_eq!(profile.games_as_investigator, 2);
+    assert_eq!(profile.games_as_ghost, 1);
+    assert_eq!(profile.outcomes_as_investigator.successful_exorcism, 2);
+    assert_eq!(profile.outcomes_as_investigator.wrong_ghost, 0);
+    assert_eq!(profile.outcomes_as_ghost.wrong_ghost, 1);
+    assert_eq!(profile.outcomes_as_ghost.successful_exorcism, 0);
+    assert_eq!(profile.favourite_ghost, Some(GhostType::Banshee));
+    assert_eq!(
+        profile.fastest(GhostType::Banshee, HouseLayoutKind::TwoRoom),
+        Some(80.0)
+    );
+    assert_eq!(
+        profile.fastest(GhostType::Spirit, HouseLayoutKind::TwoRoom),
+        None
+    );
+}
+
+#[test]
+fn ghosts_do_not_set_exorcism_records() {
+    let mut book = ProfileBook::new();
+    book.record(round(
+        "Casper",
+        Role::Ghost,
+        GhostType::Onryo,
+        RoundOutcome::SuccessfulExorcism,
+        30.0,
+    ));
+    let profile = book.get("casper").unwrap();
+    assert!(profile.fastest_exorcisms.is_empty());
+    assert_eq!(profile.favourite_ghost, Some(GhostType::Onryo));
+}
+
+// Carries each cursed object to its ritual spot, in order.
+fn contain_the_onryo(lobby: &mut Lobby, investigator: u32) {
+    let exorcism = lobby
+        .room_of(investigator)
+        .and_then(|room| room.game())
+        .map(|game| game.layout().exorcism.clone())
+        .unwrap();
+    for (object, spot) in exorcism
+        .onryo_cursed_positions
+        .iter()
+        .zip(&exorcism.onryo_ritual_positions)
+    {
+        assert!(lobby.place_player(investigator, *object));
+        lobby.interact(investigator).unwrap();
+        assert!(lobby.place_player(investigator, *spot));
+        lobby.interact(investigator).unwrap();
+    }
+}
+
+#[test]
+fn finished_rounds_update_every_member_and_survive_checkpoints() {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Onryo).unwrap();
+    lobby.start_round(ghost).unwrap();
+    lobby.step(0.5);
+    lobby.journal_guess(investigator, GhostType::Onryo).unwrap();
+    lobby.journal_confirm(investigator).unwrap();
+    contain_the_onryo(&mut lobby, investigator);
+    lobby.claim_exorcism(investigator).unwrap();
+
+    let ray = lobby.profile("Ray").expect("investigator profile");
+    assert_eq!(ray.games_as_investigator, 1);
+    assert_eq!(
+        ray.win_rate(Role::Investigator, RoundOutcome::SuccessfulExorcism),
+        1.0
+    );
+    assert_eq!(
+        ray.fastest(GhostType::Onryo, HouseLayoutKind::TwoRoom),
+        Some(0.5)
+    );
+    let casper = lobby.profile("Casper").unwrap();
+    assert_eq!(casper.games_as_ghost, 1);
+    // The banished ghost's record shows the loss, not the team's win.
+    assert_eq!(casper.outcomes_as_ghost.successful_exorcism, 1);
+    assert_eq!(casper.outcomes_as_investigator.total(), 0);
+
+    let restored = Lobby::restore(LobbySettings::default(), &lobby.checkpoint());
+    assert_eq!(restored.profile("Ray"), lobby.profile("Ray"));
+}
+
+#[test]
+fn profiles_are_served_over_the_socket() {
+    let mut lobby = Lobby::new();
+    let mut player = None;
+    let reply = ws::handle_client_message(
+        &mut lobby,
+        &mut player,
+        ClientMessage::RequestProfile {
+            display_name: " Egon ".to_string(),
+        },
+    );
+    match reply {
+        Some(ServerMessage::Profile(profile)) => {
+            assert_eq!(profile.display_name, "Egon");
+            assert_eq!(profile.games_played(), 0);
+        }
+        other => panic!("expected a profile, got {:?}", other),
+    }
+}
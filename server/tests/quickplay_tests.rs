@@ -0,0 +1,263 @@
+use server::services::lobby::{Lobby, LobbySettings, Outbound, QuickPlaySettings};
+use server::services::ws::handle_client_message;
+use shared::prelude::{
+    ClientMessage, HouseLayoutKind, LobbyRejection, PlayerId, Role, ServerMessage,
+};
+
+fn quick_lobby(investigators: usize, countdown_ms: u64) -> Lobby {
+    Lobby::with_settings(LobbySettings {
+        quick_play: QuickPlaySettings {
+            investigators,
+            countdown_ms,
+        },
+        ..LobbySettings::default()
+    })
+}
+
+fn sent_to(outbound: &[Outbound], player: PlayerId) -> Vec<&ServerMessage> {
+    outbound
+        .iter()
+        .filter(|out| out.recipients.contains(&player))
+        .map(|out| &out.message)
+        .collect()
+}
+
+#[test]
+fn players_are_grouped_by_layout_and_role() {
+    let mut lobby = quick_lobby(3, 10_000);
+    let ghost = lobby
+        .quick_play("Casper", Some(Role::Ghost), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    let other_house = lobby
+        .quick_play("Egon", None, HouseLayoutKind::ThreeRoom)
+        .unwrap();
+    let investigator = lobby
+        .quick_play("Ray", Some(Role::Investigator), HouseLayoutKind::TwoRoom)
+        .unwrap();
+
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+
+    let room = lobby.room_of(ghost).expect("ghost seated");
+    assert_eq!(room.member(ghost).unwrap().role, Some(Role::Ghost));
+    assert_eq!(
+        room.member(investigator).unwrap().role,
+        Some(Role::Investigator)
+    );
+    assert_eq!(room.state().layout, HouseLayoutKind::TwoRoom);
+    assert!(room.countdown().is_some());
+    assert!(sent_to(&outbound, investigator)
+        .iter()
+        .any(|message| matches!(
+            message,
+            ServerMessage::RoomJoined { player_id, .. } if *player_id == investigator
+        )));
+
+    assert!(lobby.room_of(other_house).is_none());
+    assert!(lobby.is_queued(other_house));
+    assert_eq!(lobby.stats().queued, 1);
+}
+
+#[test]
+fn the_countdown_starts_the_round() {
+    let mut lobby = quick_lobby(3, 1_000);
+    let ghost = lobby
+        .quick_play("Casper", None, HouseLayoutKind::ThreeRoom)
+        .unwrap();
+    let investigator = lobby
+        .quick_play("Ray", None, HouseLayoutKind::ThreeRoom)
+        .unwrap();
+
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+    assert!(sent_to(&outbound, investigator)
+        .iter()
+        .any(|message| matches!(
+            message,
+            ServerMessage::RoundCountdown {
+                starts_in_ms: Some(1_000)
+            }
+        )));
+
+    for _ in 0..19 {
+        lobby.step(0.05);
+    }
+    assert!(!lobby.room_of(ghost).unwrap().in_round());
+    lobby.drain_outbound();
+    lobby.step(0.05);
+
+    assert!(lobby.room_of(ghost).unwrap().in_round());
+    let outbound = lobby.drain_outbound();
+    for player in [ghost, investigator] {
+        assert!(sent_to(&outbound, player).iter().any(|message| matches!(
+            message,
+            ServerMessage::RoundStarted {
+                layout: HouseLayoutKind::ThreeRoom,
+                ..
+            }
+        )));
+    }
+}
+
+#[test]
+fn investigator_slots_are_capped() {
+    let mut lobby = quick_lobby(2, 10_000);
+    let ghost = lobby
+        .quick_play("Casper", Some(Role::Ghost), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    let flexible: Vec<PlayerId> = ["Ray", "Egon", "Peter"]
+        .into_iter()
+        .map(|name| {
+            lobby
+                .quick_play(name, None, HouseLayoutKind::TwoRoom)
+                .unwrap()
+        })
+        .collect();
+
+    lobby.step(0.05);
+    let room = lobby.room_of(ghost).unwrap();
+    assert_eq!(room.role_count(Role::Investigator), 2);
+    assert!(lobby.is_queued(flexible[2]));
+
+    let second_ghost = lobby
+        .quick_play("Slimer", Some(Role::Ghost), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    lobby.step(0.05);
+    let second = lobby.room_of(second_ghost).expect("second room formed");
+    assert_ne!(second.code(), lobby.room_of(ghost).unwrap().code());
+    assert_eq!(
+        second.member(flexible[2]).unwrap().role,
+        Some(Role::Investigator)
+    );
+    assert_eq!(lobby.stats().queued, 0);
+}
+
+#[test]
+fn the_countdown_stops_when_the_ghost_leaves() {
+    let mut lobby = quick_lobby(3, 10_000);
+    let ghost = lobby
+        .quick_play("Casper", Some(Role::Ghost), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    let investigator = lobby
+        .quick_play("Ray", Some(Role::Investigator), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    lobby.step(0.05);
+    lobby.drain_outbound();
+
+    lobby.leave(ghost);
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+    assert!(sent_to(&outbound, investigator)
+        .iter()
+        .any(|message| matches!(
+            message,
+            ServerMessage::RoundCountdown { starts_in_ms: None }
+        )));
+    assert!(lobby.room_of(investigator).unwrap().countdown().is_none());
+
+    let replacement = lobby
+        .quick_play("Slimer", None, HouseLayoutKind::TwoRoom)
+        .unwrap();
+    lobby.step(0.05);
+    let room = lobby.room_of(investigator).unwrap();
+    assert_eq!(room.member(replacement).unwrap().role, Some(Role::Ghost));
+    assert!(room.countdown().is_some());
+}
+
+#[test]
+fn queue_status_reports_position_and_estimated_wait() {
+    let mut lobby = quick_lobby(3, 10_000);
+    let first = lobby
+        .quick_play("Ray", Some(Role::Investigator), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    let second = lobby
+        .quick_play("Egon", Some(Role::Investigator), HouseLayoutKind::TwoRoom)
+        .unwrap();
+
+    let status = lobby.queue_status(second).unwrap();
+    assert_eq!((status.position, status.waiting), (2, 2));
+    assert_eq!(status.estimated_wait_ms, None);
+
+    lobby.step(1.0);
+    let outbound = lobby.drain_outbound();
+    assert!(sent_to(&outbound, first).iter().any(
+        |message| matches!(message, ServerMessage::QueueStatus(status) if status.position == 1)
+    ));
+
+    lobby
+        .quick_play("Casper", Some(Role::Ghost), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    let late = lobby
+        .quick_play("Peter", Some(Role::Investigator), HouseLayoutKind::TwoRoom)
+        .unwrap();
+    lobby.step(0.05);
+    assert!(lobby.room_of(first).is_some());
+    assert!(lobby.room_of(late).is_some());
+    assert!(lobby.queue_status(first).is_none());
+
+    let waiting = lobby
+        .quick_play(
+            "Winston",
+            Some(Role::Investigator),
+            HouseLayoutKind::ThreeRoom,
+        )
+        .unwrap();
+    let status = lobby.queue_status(waiting).unwrap();
+    assert_eq!((status.position, status.waiting), (1, 1));
+    assert!(status.estimated_wait_ms.is_some());
+}
+
+#[test]
+fn quick_play_messages_enter_and_leave_the_queue() {
+    let mut lobby = Lobby::new();
+    let mut player = None;
+
+    let reply = handle_client_message(
+        &mut lobby,
+        &mut player,
+        ClientMessage::QuickPlay {
+            display_name: "Ray".to_string(),
+            role: None,
+            layout: HouseLayoutKind::TwoRoom,
+        },
+    );
+    let Some(ServerMessage::QueueStatus(status)) = reply else {
+        panic!("expected QueueStatus, got {reply:?}");
+    };
+    assert_eq!(status.position, 1);
+    let id = player.expect("queued player id");
+    assert!(lobby.is_queued(id));
+
+    assert!(handle_client_message(&mut lobby, &mut player, ClientMessage::LeaveQueue).is_none());
+    assert_eq!(player, None);
+    assert!(!lobby.is_queued(id));
+
+    let reply = handle_client_message(
+        &mut lobby,
+        &mut player,
+        ClientMessage::QuickPlay {
+            display_name: "Ray".to_string(),
+            role: Some(Role::Spectator),
+            layout: HouseLayoutKind::TwoRoom,
+        },
+    );
+    assert!(matches!(
+        reply,
+        Some(ServerMessage::LobbyRejected(LobbyRejection::InvalidRole))
+    ));
+    assert_eq!(player, None);
+}
+
+#[test]
+fn draining_servers_close_the_queue() {
+    let mut lobby = Lobby::new();
+    lobby
+        .quick_play("Ray", None, HouseLayoutKind::TwoRoom)
+        .unwrap();
+    lobby.begin_shutdown();
+
+    assert_eq!(
+        lobby.quick_play("Egon", None, HouseLayoutKind::TwoRoom),
+        Err(LobbyRejection::ShuttingDown)
+    );
+}
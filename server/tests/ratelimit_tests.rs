@@ -0,0 +1,153 @@
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::Message;
+use server::core::AppConfig;
+use server::services::ratelimit::{
+    AddressLimiter, BucketLimit, ConnectionLimiter, MessageClass, RateLimits, TokenBucket, Verdict,
+    CLOSE_MESSAGE_TOO_BIG,
+};
+use server::services::ws::{self, SocketAction};
+use shared::prelude::{
+    ChatChannel, ClientMessage, InputCommand, MessageRejection, MoveInput, WireCodec,
+};
+
+fn tight_limits() -> RateLimits {
+    RateLimits {
+        interaction: BucketLimit::new(1.0, 2.0),
+        strikes: BucketLimit::new(1.0, 3.0),
+        ..RateLimits::default()
+    }
+}
+
+#[test]
+fn buckets_allow_a_burst_then_refill_over_time() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(BucketLimit::new(2.0, 3.0), start);
+    assert!((0..3).all(|_| bucket.take(start)));
+    assert!(!bucket.take(start));
+    assert!(bucket.take(start + Duration::from_millis(500)));
+    assert!(!bucket.take(start + Duration::from_millis(500)));
+    assert!(bucket.take(start + Duration::from_secs(60)));
+}
+
+#[test]
+fn messages_are_classified_for_limiting() {
+    let input = ClientMessage::Input(InputCommand {
+        seq: 1,
+        dt: 0.016,
+        input: MoveInput::default(),
+    });
+    assert_eq!(MessageClass::of(&input), MessageClass::Movement);
+    assert_eq!(
+        MessageClass::of(&ClientMessage::Ping),
+        MessageClass::Control
+    );
+    assert_eq!(
+        MessageClass::of(&ClientMessage::StartRound),
+        MessageClass::Interaction
+    );
+    let chat = ClientMessage::Chat {
+        channel: ChatChannel::Lobby,
+        text: "hi".to_string(),
+    };
+    assert_eq!(MessageClass::of(&chat), MessageClass::Chat);
+}
+
+#[test]
+fn classes_have_separate_budgets() {
+    let now = Instant::now();
+    let mut limiter = ConnectionLimiter::new(&tight_limits(), now);
+    assert_eq!(
+        limiter.check(MessageClass::Interaction, now),
+        Verdict::Allow
+    );
+    assert_eq!(
+        limiter.check(MessageClass::Interaction, now),
+        Verdict::Allow
+    );
+    assert_eq!(
+        limiter.check(MessageClass::Interaction, now),
+        Verdict::Reject
+    );
+    assert_eq!(limiter.check(MessageClass::Movement, now), Verdict::Allow);
+    assert_eq!(limiter.check(MessageClass::Control, now), Verdict::Allow);
+}
+
+#[test]
+fn flooding_ends_in_a_disconnect() {
+    let now = Instant::now();
+    let mut limiter = ConnectionLimiter::new(&tight_limits(), now);
+    let start = SocketAction::Dispatch(ClientMessage::StartRound);
+    let verdicts: Vec<_> = (0..6)
+        .map(|_| ws::admit(&mut limiter, &start, now))
+        .collect();
+    assert_eq!(
+        verdicts,
+        [
+            Verdict::Allow,
+            Verdict::Allow,
+            Verdict::Reject,
+            Verdict::Reject,
+            Verdict::Reject,
+            Verdict::Disconnect,
+        ]
+    );
+
+    let mut limiter = ConnectionLimiter::new(&tight_limits(), now);
+    let garbage = SocketAction::Reject(MessageRejection::Malformed);
+    assert_eq!(ws::admit(&mut limiter, &garbage, now), Verdict::Reject);
+    assert_eq!(ws::admit(&mut limiter, &garbage, now), Verdict::Reject);
+    assert_eq!(ws::admit(&mut limiter, &garbage, now), Verdict::Reject);
+    assert_eq!(ws::admit(&mut limiter, &garbage, now), Verdict::Disconnect);
+}
+
+#[test]
+fn guest_tokens_are_limited_per_address() {
+    let start = Instant::now();
+    let mut mints = AddressLimiter::new(BucketLimit::new(0.5, 2.0));
+    let client = IpAddr::from([203, 0, 113, 7]);
+    let neighbour = IpAddr::from([203, 0, 113, 8]);
+    assert!(mints.check(client, start));
+    assert!(mints.check(client, start));
+    assert!(!mints.check(client, start));
+    assert!(mints.check(neighbour, start));
+    assert!(mints.check(client, start + Duration::from_secs(2)));
+    assert_eq!(mints.tracked(), 2);
+}
+
+#[test]
+fn oversized_frames_disconnect_with_a_reason_code() {
+    let (action, kind) = ws::classify_frame(Message::Text("x".repeat(65)), 64, WireCodec::Json);
+    assert_eq!(kind, "Oversized");
+    assert!(matches!(
+        action,
+        SocketAction::Disconnect { code, .. } if code == CLOSE_MESSAGE_TOO_BIG
+    ));
+    let (action, _) = ws::classify_frame(Message::Text("ping".into()), 64, WireCodec::Json);
+    assert!(matches!(action, SocketAction::Send(_)));
+}
+
+#[test]
+fn limits_are_read_from_config() {
+    let text = r#"
+        max_message_bytes = 4096
+
+        [rate_limits]
+        chat = { per_second = 1.0, burst = 3.0 }
+    "#;
+    let config =
+        AppConfig::from_sources(Some((std::path::Path::new("server.toml"), text)), |_| None)
+            .unwrap();
+    assert_eq!(config.max_message_bytes, 4096);
+    assert_eq!(config.rate_limits.chat, BucketLimit::new(1.0, 3.0));
+    assert_eq!(config.rate_limits.movement, RateLimits::default().movement);
+
+    let broken = "[rate_limits]\nmovement = { per_second = 0.0, burst = 10.0 }";
+    assert!(
+        AppConfig::from_sources(Some((std::path::Path::new("server.toml"), broken)), |_| {
+            None
+        })
+        .is_err()
+    );
+}
@@ -0,0 +1,430 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use server::services::lobby::Lobby;
+use server::services::referee::{EvidenceMiss, Referee};
+use server::services::simulation::MatchSimulation;
+use shared::prelude::{
+    ClaimRejection, Equipment, EvidenceEvent, EvidenceKind, ExorcismState, GhostType,
+    HouseLayoutKind, InputCommand, MoveInput, Role, RoundOutcome, ServerMessage, Vec3,
+};
+
+const GHOST: u32 = 1;
+const INVESTIGATOR: u32 = 2;
+
+fn match_with(ghost: Vec3, investigator: Vec3) -> MatchSimulation {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    game.add_player(GHOST, Role::Ghost);
+    game.add_player(INVESTIGATOR, Role::Investigator);
+    game.place_player(GHOST, ghost);
+    game.place_player(INVESTIGATOR, investigator);
+    game
+}
+
+fn confirm(referee: &mut Referee, game: &MatchSimulation, guess: GhostType) {
+    referee.guess(game, INVESTIGATOR, guess).unwrap();
+    referee.confirm(game, INVESTIGATOR).unwrap();
+}
+
+#[test]
+fn emf_five_needs_the_ghost_close_by() {
+    let mut referee = Referee::new(GhostType::Spirit);
+    let far = match_with(Vec3::new(6.0, 1.6, -6.0), Vec3::new(-6.0, 0.9, 6.0));
+    assert_eq!(
+        referee.claim_evidence(&far, INVESTIGATOR, EvidenceKind::EmfFive),
+        Err(ClaimRejection::ImpossibleEvidence)
+    );
+    assert_eq!(referee.take_misses(), vec![EvidenceMiss::OutOfRange]);
+
+    let near = match_with(Vec3::new(0.0, 1.6, 0.0), Vec3::new(0.5, 0.9, 0.0));
+    assert_eq!(
+        referee.claim_evidence(&near, INVESTIGATOR, EvidenceKind::EmfFive),
+        Ok(())
+    );
+    assert_eq!(referee.evidence(INVESTIGATOR), &[EvidenceKind::EmfFive]);
+}
+
+#[test]
+fn evidence_the_ghost_cannot_produce_is_rejected() {
+    let mut referee = Referee::new(GhostType::Onryo);
+    let game = match_with(Vec3::new(0.0, 1.6, 0.0), Vec3::new(0.5, 0.9, 0.0));
+    assert_eq!(
+        referee.claim_evidence(&game, INVESTIGATOR, EvidenceKind::EmfFive),
+        Err(ClaimRejection::ImpossibleEvidence)
+    );
+    assert_eq!(
+        referee.claim_evidence(&game, INVESTIGATOR, EvidenceKind::SpiritboxResponse),
+        Err(ClaimRejection::ImpossibleEvidence)
+    );
+    assert_eq!(
+        referee.claim_evidence(&game, GHOST, EvidenceKind::EmfFive),
+        Err(ClaimRejection::WrongRole)
+    );
+    assert_eq!(
+        referee.take_misses(),
+        vec![EvidenceMiss::WrongGhost, EvidenceMiss::WrongGhost]
+    );
+}
+
+// A wrong ghost and a bad position must look the same to the player, or
+// the rejection would tell them the ghost type.
+#[test]
+fn failed_evidence_claims_all_get_the_same_rejection() {
+    let far = match_with(Vec3::new(6.0, 1.6, -6.0), Vec3::new(-6.0, 0.9, 6.0));
+    let wrong_ghost =
+        Referee::new(GhostType::Onryo).claim_evidence(&far, INVESTIGATOR, EvidenceKind::EmfFive);
+    let out_of_range =
+        Referee::new(GhostType::Spirit).claim_evidence(&far, INVESTIGATOR, EvidenceKind::EmfFive);
+    assert_eq!(wrong_ghost, out_of_range);
+
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Onryo).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.start_round(ghost).unwrap();
+    assert!(matches!(
+        lobby.claim_evidence(investigator, EvidenceKind::SpiritboxResponse),
+        Err(ClaimRejection::ImpossibleEvidence)
+    ));
+    let stats = lobby.stats();
+    assert_eq!(
+        (stats.evidence_wrong_ghost, stats.evidence_out_of_range),
+        (1, 0)
+    );
+}
+
+#[test]
+fn banshee_presses_must_be_within_reach() {
+    let mut referee = Referee::new(GhostType::Banshee);
+    let mut game = match_with(Vec3::new(6.0, 1.6, 6.0), Vec3::new(-6.0, 0.9, 6.0));
+    confirm(&mut referee, &game, GhostType::Banshee);
+
+    assert_eq!(
+        referee.press_banshee_node(&game, INVESTIGATOR, 0),
+        Err(ClaimRejection::OutOfRange)
+    );
+    assert_eq!(
+        referee.press_banshee_node(&game, INVESTIGATOR, 9),
+        Err(ClaimRejection::UnknownNode)
+    );
+
+    game.place_player(INVESTIGATOR, Vec3::new(-4.0, 0.9, -1.0));
+    let update = referee.press_banshee_node(&game, INVESTIGATOR, 0).unwrap();
+    assert_eq!(update.state, ExorcismState::Stage(1));
+}
+
+#[test]
+fn banshee_exorcism_is_only_accepted_after_the_full_sequence() {
+    let mut referee = Referee::new(GhostType::Banshee);
+    let mut game = match_with(Vec3::new(6.0, 1.6, 6.0), Vec3::new(-4.0, 0.9, -1.0));
+    assert_eq!(
+        referee.claim_exorcism(&game, INVESTIGATOR),
+        Err(ClaimRejection::PuzzleNotStarted)
+    );
+    confirm(&mut referee, &game, GhostType::Banshee);
+    assert_eq!(
+        referee.claim_exorcism(&game, INVESTIGATOR),
+        Err(ClaimRejection::PuzzleIncomplete)
+    );
+
+    let nodes = [
+        Vec3::new(-4.0, 0.9, -1.0),
+        Vec3::new(4.5, 0.9, -0.5),
+        Vec3::new(0.0, 0.9, 4.0),
+    ];
+    for (node, position) in nodes.into_iter().enumerate() {
+        for _ in 0..10 {
+            game.step(0.1);
+        }
+        game.place_player(INVESTIGATOR, position);
+        referee
+            .press_banshee_node(&game, INVESTIGATOR, node as u8)
+            .unwrap();
+    }
+
+    let summary = referee.claim_exorcism(&game, INVESTIGATOR).unwrap();
+    assert_eq!(summary.outcome, RoundOutcome::SuccessfulExorcism);
+    assert_eq!(summary.guess, Some(GhostType::Banshee));
+}
+
+#[test]
+fn banshee_nodes_must_follow_the_servers_order() {
+    let mut referee = Referee::new(GhostType::Banshee).with_banshee_order(vec![1, 0, 2]);
+    let mut game = match_with(Vec3::new(6.0, 1.6, 6.0), Vec3::new(-4.0, 0.9, -1.0));
+    confirm(&mut referee, &game, GhostType::Banshee);
+    let update = referee.press_banshee_node(&game, INVESTIGATOR, 0).unwrap();
+    assert_eq!(update.state, ExorcismState::Failed);
+
+    run(&mut game, &mut referee, 30);
+    game.place_player(INVESTIGATOR, Vec3::new(4.5, 0.9, -0.5));
+    let update = referee.press_banshee_node(&game, INVESTIGATOR, 1).unwrap();
+    assert_eq!(update.state, ExorcismState::Stage(1));
+    run(&mut game, &mut referee, 10);
+    let update = referee.press_banshee_node(&game, INVESTIGATOR, 1).unwrap();
+    assert_eq!(update.state, ExorcismState::Failed);
+}
+
+#[test]
+fn spiritbox_replies_need_the_ghost_in_the_same_room() {
+    let mut referee = Referee::new(GhostType::Banshee);
+    let through_wall = match_with(Vec3::new(2.6, 1.6, 0.0), Vec3::new(1.2, 0.9, 0.0));
+    assert_eq!(
+        referee.claim_evidence(&through_wall, INVESTIGATOR, EvidenceKind::SpiritboxResponse),
+        Err(ClaimRejection::ImpossibleEvidence)
+    );
+
+    let same_room = match_with(Vec3::new(2.6, 1.6, 0.0), Vec3::new(7.0, 0.9, 3.0));
+    assert_eq!(
+        referee.claim_evidence(&same_room, INVESTIGATOR, EvidenceKind::SpiritboxResponse),
+        Ok(())
+    );
+}
+
+#[test]
+fn tool_readings_come_from_the_room_ghost() {
+    let mut game = match_with(Vec3::new(0.0, 1.6, 0.0), Vec3::new(0.5, 0.9, 0.0));
+    game.turn_player(INVESTIGATOR, -FRAC_PI_2);
+    let players = [GHOST, INVESTIGATOR];
+
+    let mut spirit = Referee::new(GhostType::Spirit);
+    assert_eq!(
+        spirit.emf_readings(&game, &players),
+        vec![(INVESTIGATOR, 5)]
+    );
+    assert_eq!(spirit.emf_readings(&game, &players), vec![]);
+    game.turn_player(INVESTIGATOR, FRAC_PI_2);
+    assert_eq!(
+        spirit.emf_readings(&game, &players),
+        vec![(INVESTIGATOR, 4)]
+    );
+
+    let mut onryo = Referee::new(GhostType::Onryo);
+    assert_eq!(onryo.emf_readings(&game, &players), vec![(INVESTIGATOR, 1)]);
+
+    let mut banshee = Referee::new(GhostType::Banshee);
+    assert!(matches!(
+        banshee.use_equipment(&game, INVESTIGATOR),
+        Ok(None)
+    ));
+    game.select_equipment(INVESTIGATOR, Equipment::Spiritbox);
+    let Ok(Some(ServerMessage::Evidence(EvidenceEvent::SpiritboxReply { evidence, .. }))) =
+        banshee.use_equipment(&game, INVESTIGATOR)
+    else {
+        panic!("the spiritbox should answer");
+    };
+    assert!(evidence);
+    let Ok(Some(ServerMessage::Evidence(EvidenceEvent::SpiritboxReply { evidence, .. }))) =
+        onryo.use_equipment(&game, INVESTIGATOR)
+    else {
+        panic!("the spiritbox should answer");
+    };
+    assert!(!evidence);
+}
+
+fn run(game: &mut MatchSimulation, referee: &mut Referee, ticks: usize) {
+    for _ in 0..ticks {
+        game.step(0.1);
+        referee.step(game, 0.1);
+    }
+}
+
+#[test]
+fn spirit_exorcism_needs_the_anchors_watched() {
+    let mut referee = Referee::new(GhostType::Spirit);
+    let mut game = match_with(Vec3::new(6.0, 1.6, 6.0), Vec3::new(-6.0, 0.9, 0.0));
+    confirm(&mut referee, &game, GhostType::Spirit);
+
+    // Staring at the wall between the anchors never finishes the vigil.
+    game.turn_player(INVESTIGATOR, FRAC_PI_2);
+    run(&mut game, &mut referee, 100);
+    assert_eq!(
+        referee.claim_exorcism(&game, INVESTIGATOR),
+        Err(ClaimRejection::PuzzleIncomplete)
+    );
+
+    // Turning between the two anchors on this side of the house does.
+    for turn in 0..8 {
+        if turn == 4 {
+            assert_eq!(
+                referee.claim_exorcism(&game, INVESTIGATOR),
+                Err(ClaimRejection::PuzzleIncomplete)
+            );
+        }
+        game.turn_player(INVESTIGATOR, if turn % 2 == 0 { PI } else { 0.0 });
+        run(&mut game, &mut referee, 10);
+    }
+    assert!(referee.claim_exorcism(&game, INVESTIGATOR).is_ok());
+}
+
+#[test]
+fn onryo_exorcism_needs_every_object_laid_in_order() {
+    let mut referee = Referee::new(GhostType::Onryo);
+    let mut game = match_with(Vec3::new(6.0, 1.6, 6.0), Vec3::new(-6.5, 0.4, 0.0));
+    assert_eq!(
+        referee.interact(&game, INVESTIGATOR),
+        Err(ClaimRejection::PuzzleNotStarted)
+    );
+    confirm(&mut referee, &game, GhostType::Onryo);
+    assert_eq!(
+        referee.claim_exorcism(&game, INVESTIGATOR),
+        Err(ClaimRejection::PuzzleIncomplete)
+    );
+
+    let update = referee.interact(&game, INVESTIGATOR).unwrap();
+    assert_eq!(
+        (update.state, update.stacks),
+        (ExorcismState::Stage(0), 1.0)
+    );
+    game.place_player(INVESTIGATOR, Vec3::new(0.0, 0.9, -2.0));
+    assert_eq!(
+        referee.interact(&game, INVESTIGATOR),
+        Err(ClaimRejection::OutOfRange)
+    );
+
+    // The wrong spot costs a stack and the object stays where it fell.
+    game.place_player(INVESTIGATOR, Vec3::new(2.5, 0.1, 2.5));
+    let update = referee.interact(&game, INVESTIGATOR).unwrap();
+    assert_eq!((update.stage, update.stacks), (0, 2.0));
+    referee.interact(&game, INVESTIGATOR).unwrap();
+    game.place_player(INVESTIGATOR, Vec3::new(-2.5, 0.1, 2.5));
+    let update = referee.interact(&game, INVESTIGATOR).unwrap();
+    assert_eq!(
+        (update.state, update.stacks),
+        (ExorcismState::Stage(1), 1.0)
+    );
+
+    // Carrying the next object for too long fails the containment.
+    game.place_player(INVESTIGATOR, Vec3::new(6.5, 0.4, 0.0));
+    referee.interact(&game, INVESTIGATOR).unwrap();
+    run(&mut game, &mut referee, 60);
+    let update = referee.interact(&game, INVESTIGATOR).unwrap();
+    assert_eq!(update.state, ExorcismState::Failed);
+    assert_eq!(
+        referee.claim_exorcism(&game, INVESTIGATOR),
+        Err(ClaimRejection::PuzzleIncomplete)
+    );
+}
+
+// Carries each cursed object to its ritual spot, in order.
+fn contain_the_onryo(lobby: &mut Lobby, investigator: u32) {
+    let exorcism = lobby
+        .room_of(investigator)
+        .and_then(|room| room.game())
+        .map(|game| game.layout().exorcism.clone())
+        .unwrap();
+    for (object, spot) in exorcism
+        .onryo_cursed_positions
+        .iter()
+        .zip(&exorcism.onryo_ritual_positions)
+    {
+        assert!(lobby.place_player(investigator, *object));
+        lobby.interact(investigator).unwrap();
+        assert!(lobby.place_player(investigator, *spot));
+        lobby.interact(investigator).unwrap();
+    }
+}
+
+fn started_lobby() -> (Lobby, u32, u32) {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.start_round(ghost).unwrap();
+    lobby.drain_outbound();
+    (lobby, ghost, investigator)
+}
+
+#[test]
+fn rejected_claims_are_counted_per_player() {
+    let (mut lobby, ghost, investigator) = started_lobby();
+    assert_eq!(
+        lobby.claim_evidence(ghost, EvidenceKind::EmfFive).err(),
+        Some(ClaimRejection::WrongRole)
+    );
+    assert_eq!(
+        lobby.claim_exorcism(investigator),
+        Err(ClaimRejection::PuzzleNotStarted)
+    );
+    assert_eq!(
+        lobby.claim_exorcism(investigator),
+        Err(ClaimRejection::PuzzleNotStarted)
+    );
+    assert_eq!(lobby.violations(ghost), 1);
+    assert_eq!(lobby.violations(investigator), 2);
+}
+
+fn sprint(seq: u32, dt: f32) -> InputCommand {
+    InputCommand {
+        seq,
+        dt,
+        input: MoveInput {
+            forward: 1.0,
+            sprint: true,
+            ..MoveInput::default()
+        },
+    }
+}
+
+#[test]
+fn movement_faster_than_real_time_is_flagged() {
+    let (mut lobby, _, investigator) = started_lobby();
+    for seq in 1..=20 {
+        lobby.apply_input(investigator, sprint(seq, 0.1));
+    }
+    lobby.step(0.05);
+    assert_eq!(lobby.violations(investigator), 1);
+
+    assert!(!lobby.apply_input(investigator, sprint(21, 5.0)));
+    assert_eq!(lobby.violations(investigator), 2);
+}
+
+#[test]
+fn accepted_exorcism_ends_the_round_for_everyone() {
+    let (mut lobby, _, investigator) = started_lobby();
+    let message = lobby.journal_guess(investigator, GhostType::Onryo).unwrap();
+    assert!(matches!(
+        message,
+        ServerMessage::Investigation {
+            guess: Some(GhostType::Onryo),
+            confirmed: false
+        }
+    ));
+    lobby.journal_confirm(investigator).unwrap();
+    contain_the_onryo(&mut lobby, investigator);
+    lobby.claim_exorcism(investigator).unwrap();
+
+    let outbound = lobby.drain_outbound();
+    assert!(outbound.iter().any(|out| out.recipients.len() == 2
+        && matches!(out.message, ServerMessage::RoundEnded(summary) if summary.guess == Some(GhostType::Onryo))));
+    assert!(!lobby.room_of(investigator).unwrap().in_round());
+    assert_eq!(lobby.violations(investigator), 0);
+}
+
+#[test]
+fn a_failed_containment_ends_the_round() {
+    let (mut lobby, _, investigator) = started_lobby();
+    lobby.journal_guess(investigator, GhostType::Onryo).unwrap();
+    lobby.journal_confirm(investigator).unwrap();
+    let object = lobby
+        .room_of(investigator)
+        .and_then(|room| room.game())
+        .map(|game| game.layout().exorcism.onryo_cursed_positions[0])
+        .unwrap();
+    assert!(lobby.place_player(investigator, object));
+    lobby.interact(investigator).unwrap();
+    for _ in 0..100 {
+        lobby.step(0.1);
+    }
+
+    // The room's ghost is a Spirit, so the Onryo guess was wrong too.
+    let outbound = lobby.drain_outbound();
+    assert!(outbound.iter().any(|out| matches!(
+        out.message,
+        ServerMessage::RoundEnded(summary) if summary.outcome == RoundOutcome::WrongGhost
+    )));
+    assert!(!lobby.room_of(investigator).unwrap().in_round());
+}
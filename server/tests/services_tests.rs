@@ -1,5 +1,6 @@
 use server::services::ws::SocketAction;
 use server::services::{health, ws};
+use shared::codec::WireCodec;
 
 #[test]
 fn health_is_ok() {
@@ -8,12 +9,39 @@ fn health_is_ok() {
 }
 
 #[test]
-fn echoes_text() {
+fn rejects_unparsed_text_instead_of_echoing() {
     let action = ws::handle_message(axum::extract::ws::Message::Text("hello".into()));
-    match action {
-        SocketAction::Send(axum::extract::ws::Message::Text(text)) => assert_eq!(text, "hello"),
-        _ => panic!("expected echo text"),
-    }
+    assert!(matches!(
+        action,
+        SocketAction::Reject(shared::protocol::MessageRejection::Malformed)
+    ));
+    let action = ws::handle_message(axum::extract::ws::Message::Binary(vec![255, 2, 3]));
+    assert!(matches!(
+        action,
+        SocketAction::Reject(shared::protocol::MessageRejection::Malformed)
+    ));
+}
+
+#[test]
+fn binary_frames_need_the_binary_codec() {
+    let choose = shared::protocol::ClientMessage::ChooseRole {
+        role: shared::game::Role::Ghost,
+    };
+    let Some(shared::codec::Frame::Binary(bytes)) = WireCodec::Binary.encode(&choose) else {
+        panic!("expected a binary frame");
+    };
+    let frame = axum::extract::ws::Message::Binary(bytes);
+    let (action, kind) = ws::classify_frame(frame.clone(), 1024, WireCodec::Binary);
+    assert_eq!(kind, "ChooseRole");
+    assert!(matches!(
+        action,
+        SocketAction::Dispatch(shared::protocol::ClientMessage::ChooseRole { .. })
+    ));
+    let (action, _) = ws::classify_frame(frame, 1024, WireCodec::Json);
+    assert!(matches!(
+        action,
+        SocketAction::Reject(shared::protocol::MessageRejection::Unsupported)
+    ));
 }
 
 #[test]
@@ -32,3 +60,55 @@ fn closes_on_close_message() {
     let action = ws::handle_message(axum::extract::ws::Message::Close(None));
     assert!(matches!(action, SocketAction::Close));
 }
+
+#[test]
+fn dispatches_role_choice() {
+    let json = serde_json::to_string(&shared::protocol::ClientMessage::ChooseRole {
+        role: shared::game::Role::Ghost,
+    })
+    .expect("serialize join");
+    let action = ws::handle_message(axum::extract::ws::Message::Text(json));
+    assert!(matches!(
+        action,
+        SocketAction::Dispatch(shared::protocol::ClientMessage::ChooseRole { .. })
+    ));
+}
+
+#[test]
+fn join_assigns_a_player_id() {
+    let mut lobby = server::services::lobby::Lobby::new();
+    let room = lobby.create_room("Haunted").expect("create room");
+    let mut player = None;
+    let reply = ws::handle_client_message(
+        &mut lobby,
+        &mut player,
+        shared::protocol::ClientMessage::JoinLobby {
+            display_name: "Casper".to_string(),
+            room_code: room.code.to_lowercase(),
+        },
+    );
+    assert!(matches!(
+        reply,
+        Some(shared::protocol::ServerMessage::RoomJoined { ref room_code, .. }) if *room_code == room.code
+    ));
+    assert!(player.is_some());
+}
+
+#[test]
+fn role_choice_outside_a_room_is_rejected() {
+    let mut lobby = server::services::lobby::Lobby::new();
+    let mut player = None;
+    let reply = ws::handle_client_message(
+        &mut lobby,
+        &mut player,
+        shared::protocol::ClientMessage::ChooseRole {
+            role: shared::game::Role::Ghost,
+        },
+    );
+    assert!(matches!(
+        reply,
+        Some(shared::protocol::ServerMessage::LobbyRejected(
+            shared::protocol::LobbyRejection::NotInRoom
+        ))
+    ));
+}
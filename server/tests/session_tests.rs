@@ -0,0 +1,196 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use server::services::lobby::Lobby;
+use server::services::session::{SessionRegistry, RESUME_GRACE};
+use server::services::ws;
+use shared::prelude::{Role, ServerMessage, UpdateRequired, VersionRange, PROTOCOL_VERSION};
+
+#[test]
+fn tokens_are_unique() {
+    let mut sessions = SessionRegistry::new();
+    let first = sessions.open();
+    let second = sessions.open();
+    assert_ne!(first.token, second.token);
+    assert_eq!(first.token.len(), 32);
+}
+
+#[test]
+fn resume_within_grace_keeps_the_player() {
+    let mut sessions = SessionRegistry::new();
+    let handle = sessions.open();
+    sessions.bind_player(&handle.token, Some(4));
+    let dropped = Instant::now();
+    assert!(sessions.disconnect(&handle, dropped));
+
+    let resumed = sessions
+        .resume(&handle.token, dropped + Duration::from_secs(1))
+        .expect("resume within grace");
+    assert_eq!(resumed.player, Some(4));
+    assert_ne!(resumed.connection, handle.connection);
+    assert!(sessions.expire(dropped + RESUME_GRACE * 2).is_empty());
+}
+
+#[test]
+fn sessions_expire_after_grace() {
+    let mut sessions = SessionRegistry::new();
+    let handle = sessions.open();
+    sessions.bind_player(&handle.token, Some(9));
+    let dropped = Instant::now();
+    sessions.disconnect(&handle, dropped);
+
+    let late = dropped + RESUME_GRACE + Duration::from_secs(1);
+    assert!(sessions.resume(&handle.token, late).is_none());
+    assert_eq!(sessions.expire(late), vec![9]);
+    assert!(sessions.is_empty());
+}
+
+#[test]
+fn stale_connection_cannot_disconnect_a_resumed_session() {
+    let mut sessions = SessionRegistry::new();
+    let stale = sessions.open();
+    let now = Instant::now();
+    sessions.resume(&stale.token, now).expect("resume");
+    assert!(!sessions.disconnect(&stale, now));
+}
+
+#[tokio::test]
+async fn resuming_a_live_session_closes_the_old_connection() {
+    let mut sessions = SessionRegistry::new();
+    let old = sessions.open();
+    let taken_over = Arc::new(tokio::sync::Notify::new());
+    sessions.watch_takeover(&old, taken_over.clone());
+    assert!(sessions.is_current(&old));
+
+    let new = sessions.resume(&old.token, Instant::now()).expect("resume");
+    assert!(!sessions.is_current(&old));
+    assert!(sessions.is_current(&new));
+    tokio::time::timeout(Duration::from_secs(1), taken_over.notified())
+        .await
+        .expect("old connection told to close");
+}
+
+#[test]
+fn hello_with_wrong_version_is_refused() {
+    let mut sessions = SessionRegistry::new();
+    let lobby = Lobby::new();
+    let handshake = ws::handle_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION + 1,
+        None,
+        Instant::now(),
+    );
+    assert!(handshake.is_none());
+    assert!(sessions.is_empty());
+}
+
+#[test]
+fn hello_needs_the_exact_protocol_version() {
+    let mut sessions = SessionRegistry::new();
+    let lobby = Lobby::new();
+    let (_, welcome) = ws::negotiate_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION,
+        None,
+        &[],
+        Instant::now(),
+    )
+    .expect("same version accepted");
+    assert!(matches!(
+        welcome,
+        ServerMessage::Welcome { protocol_version, .. } if protocol_version == PROTOCOL_VERSION
+    ));
+
+    let opened = sessions.len();
+    let mismatch = ws::negotiate_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION + 1,
+        None,
+        &[],
+        Instant::now(),
+    )
+    .unwrap_err();
+    assert_eq!(mismatch.update, UpdateRequired::Server);
+    assert_eq!(mismatch.server, VersionRange::SUPPORTED);
+    assert_eq!(sessions.len(), opened);
+}
+
+#[test]
+fn older_clients_are_told_to_update() {
+    let mut sessions = SessionRegistry::new();
+    let lobby = Lobby::new();
+    let mismatch = ws::negotiate_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION - 1,
+        None,
+        &[],
+        Instant::now(),
+    )
+    .unwrap_err();
+    assert_eq!(mismatch.update, UpdateRequired::Client);
+    assert_eq!(mismatch.client, VersionRange::exact(PROTOCOL_VERSION - 1));
+    assert!(sessions.is_empty());
+}
+
+#[test]
+fn hello_with_resume_token_reattaches_to_the_room() {
+    let mut sessions = SessionRegistry::new();
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let player = lobby.join(&code, "Casper").unwrap();
+    lobby.choose_role(player, Role::Ghost).unwrap();
+
+    let (handle, _) = ws::handle_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION,
+        None,
+        Instant::now(),
+    )
+    .unwrap();
+    sessions.bind_player(&handle.token, Some(player));
+    sessions.disconnect(&handle, Instant::now());
+
+    let (resumed, welcome) = ws::handle_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION,
+        Some(&handle.token),
+        Instant::now(),
+    )
+    .unwrap();
+    assert_eq!(resumed.player, Some(player));
+    match welcome {
+        ServerMessage::Welcome {
+            resume_token,
+            player_id,
+            room,
+            ..
+        } => {
+            assert_eq!(resume_token, handle.token);
+            assert_eq!(player_id, Some(player));
+            assert_eq!(room.map(|room| room.code), Some(code));
+        }
+        other => panic!("expected welcome, got {other:?}"),
+    }
+}
+
+#[test]
+fn unknown_resume_token_starts_a_fresh_session() {
+    let mut sessions = SessionRegistry::new();
+    let lobby = Lobby::new();
+    let (handle, _) = ws::handle_hello(
+        &mut sessions,
+        &lobby,
+        PROTOCOL_VERSION,
+        Some("missing"),
+        Instant::now(),
+    )
+    .unwrap();
+    assert_ne!(handle.token, "missing");
+    assert_eq!(handle.player, None);
+}
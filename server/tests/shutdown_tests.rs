@@ -0,0 +1,128 @@
+use std::time::{Duration, Instant};
+
+use server::core::shutdown::{close_sockets, drain_matches, ShutdownPhase};
+use server::core::AppState;
+use server::services::lobby::Lobby;
+use shared::prelude::{LobbyRejection, Role, ServerMessage};
+
+fn state_with_round() -> (AppState, u32) {
+    let state = AppState::new();
+    let investigator = {
+        let mut lobby = state.lobby();
+        let code = lobby.create_room("Haunted").unwrap().code;
+        let ghost = lobby.join(&code, "Casper").unwrap();
+        let investigator = lobby.join(&code, "Ray").unwrap();
+        lobby.choose_role(ghost, Role::Ghost).unwrap();
+        lobby.choose_role(investigator, Role::Investigator).unwrap();
+        lobby.start_round(ghost).unwrap();
+        lobby.drain_outbound();
+        investigator
+    };
+    (state, investigator)
+}
+
+#[test]
+fn draining_lobby_refuses_new_rooms_and_joins() {
+    let (state, _) = state_with_round();
+    let mut lobby = state.lobby();
+    let idle_room = lobby.create_room("Idle").unwrap().code;
+    lobby.begin_shutdown();
+
+    assert!(lobby.drain_outbound().is_empty());
+    assert_eq!(lobby.create_room("Late"), Err(LobbyRejection::ShuttingDown));
+    assert_eq!(
+        lobby.join(&idle_room, "Egon"),
+        Err(LobbyRejection::ShuttingDown)
+    );
+    assert!(lobby.open_rooms().is_empty());
+    assert_eq!(lobby.active_rounds(), 1);
+}
+
+#[tokio::test]
+async fn every_socket_hears_the_drain_and_then_the_close() {
+    let state = AppState::new();
+    let mut socket = state.watch_shutdown();
+    assert_eq!(*socket.borrow(), ShutdownPhase::Running);
+    drain_matches(state.clone(), Duration::from_secs(10)).await;
+    assert!(socket.has_changed().unwrap());
+    let phase = *socket.borrow_and_update();
+    let ShutdownPhase::Draining { deadline } = phase else {
+        panic!("expected a drain, got {:?}", phase);
+    };
+    assert!(deadline <= tokio::time::Instant::now() + Duration::from_secs(10));
+    // Late sockets are told how long is left, not the whole grace period.
+    assert!(matches!(
+        phase.notice(deadline - Duration::from_secs(4)),
+        Some(ServerMessage::ShuttingDown {
+            remaining_ms: 4_000
+        })
+    ));
+    assert!(matches!(
+        phase.notice(deadline + Duration::from_secs(1)),
+        Some(ServerMessage::ShuttingDown { remaining_ms: 0 })
+    ));
+
+    // A socket leaves once it is told to close, and the server stops
+    // waiting as soon as the last one is gone.
+    state.metrics().connection_opened();
+    let closing = state.clone();
+    tokio::spawn(async move {
+        socket.changed().await.unwrap();
+        assert_eq!(*socket.borrow(), ShutdownPhase::Closing);
+        closing.metrics().connection_closed();
+    });
+    let started = Instant::now();
+    close_sockets(&state).await;
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert_eq!(state.metrics().connections_active(), 0);
+}
+
+#[tokio::test]
+async fn drain_waits_for_running_rounds_until_the_deadline() {
+    let (state, _) = state_with_round();
+    let started = Instant::now();
+    drain_matches(state.clone(), Duration::from_millis(100)).await;
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    assert!(state.lobby().is_draining());
+    assert_eq!(state.lobby().active_rounds(), 1);
+}
+
+// Carries each cursed object to its ritual spot, in order.
+fn contain_the_onryo(lobby: &mut Lobby, investigator: u32) {
+    let exorcism = lobby
+        .room_of(investigator)
+        .and_then(|room| room.game())
+        .map(|game| game.layout().exorcism.clone())
+        .unwrap();
+    for (object, spot) in exorcism
+        .onryo_cursed_positions
+        .iter()
+        .zip(&exorcism.onryo_ritual_positions)
+    {
+        assert!(lobby.place_player(investigator, *object));
+        lobby.interact(investigator).unwrap();
+        assert!(lobby.place_player(investigator, *spot));
+        lobby.interact(investigator).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn drain_finishes_early_once_rounds_end() {
+    let (state, investigator) = state_with_round();
+    let ending = state.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut lobby = ending.lobby();
+        lobby
+            .journal_guess(investigator, shared::prelude::GhostType::Onryo)
+            .unwrap();
+        lobby.journal_confirm(investigator).unwrap();
+        contain_the_onryo(&mut lobby, investigator);
+        lobby.claim_exorcism(investigator).unwrap();
+    });
+
+    let started = Instant::now();
+    drain_matches(state.clone(), Duration::from_secs(10)).await;
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(state.lobby().active_rounds(), 0);
+}
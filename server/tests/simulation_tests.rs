@@ -0,0 +1,94 @@
+use server::services::simulation::MatchSimulation;
+use shared::prelude::{HouseLayoutKind, InputCommand, MoveInput, Role, Vec3};
+
+#[test]
+fn players_join_and_appear_in_snapshot() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    let (ghost, investigator) = (1, 2);
+    game.add_player(ghost, Role::Ghost);
+    game.add_player(investigator, Role::Investigator);
+
+    let snapshot = game.snapshot();
+    assert_eq!(snapshot.players.len(), 2);
+    assert!(snapshot
+        .players
+        .iter()
+        .any(|player| player.id == ghost && player.role == Role::Ghost));
+    assert!(snapshot
+        .players
+        .iter()
+        .any(|player| player.id == investigator && player.role == Role::Investigator));
+}
+
+fn forward(seq: u32) -> InputCommand {
+    InputCommand {
+        seq,
+        dt: 0.05,
+        input: MoveInput {
+            forward: 1.0,
+            strafe: 0.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            sprint: false,
+        },
+    }
+}
+
+#[test]
+fn step_applies_queued_inputs_and_advances_tick() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    let id = 1;
+    let start = game.add_player(id, Role::Investigator);
+
+    assert!(game.apply_input(id, forward(1)));
+    assert!(game.apply_input(id, forward(2)));
+    game.step(0.05);
+    game.step(0.05);
+
+    let player = game.player(id).unwrap();
+    assert_eq!(game.tick(), 2);
+    assert!((game.snapshot().server_time - 0.1).abs() < 1e-6);
+    assert_eq!(player.last_input_seq, 2);
+    assert!(player.position.distance(start) > 0.0);
+}
+
+#[test]
+fn players_without_new_inputs_stay_put() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    let id = 1;
+    game.add_player(id, Role::Investigator);
+    game.apply_input(id, forward(1));
+    game.step(0.05);
+    let after_input = game.player(id).unwrap().position;
+
+    game.step(0.05);
+    assert_eq!(game.player(id).unwrap().position, after_input);
+}
+
+#[test]
+fn stale_input_sequences_are_rejected() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    let id = 1;
+    game.add_player(id, Role::Ghost);
+    assert!(game.apply_input(id, forward(5)));
+    assert!(!game.apply_input(id, forward(5)));
+    game.step(0.05);
+    assert!(!game.apply_input(id, forward(3)));
+    assert_eq!(game.snapshot().players[0].last_input_seq, 5);
+}
+
+#[test]
+fn input_for_unknown_player_is_rejected() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::TwoRoom);
+    assert!(!game.apply_input(42, InputCommand::default()));
+}
+
+#[test]
+fn removed_players_leave_the_snapshot() {
+    let mut game = MatchSimulation::new(HouseLayoutKind::ThreeRoom);
+    let id = 3;
+    game.add_player(id, Role::Ghost);
+    assert!(game.remove_player(id));
+    assert!(game.snapshot().players.is_empty());
+    assert_eq!(game.player(id).map(|player| player.position), None::<Vec3>);
+}
@@ -0,0 +1,177 @@
+use server::services::lobby::{Lobby, LobbySettings, Outbound, MAX_PLAYERS_PER_ROOM};
+use server::services::ws::handle_client_message;
+use shared::prelude::{
+    ClaimRejection, ClientMessage, EvidenceKind, GhostType, InputCommand, LobbyRejection, PlayerId,
+    Role, ServerMessage,
+};
+
+fn running_round(lobby: &mut Lobby) -> (String, PlayerId, PlayerId) {
+    let code = lobby.create_room("Haunted").expect("create room").code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Onryo).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.start_round(ghost).unwrap();
+    lobby.drain_outbound();
+    (code, ghost, investigator)
+}
+
+fn sent_to(outbound: &[Outbound], player: PlayerId) -> Vec<&ServerMessage> {
+    outbound
+        .iter()
+        .filter(|out| out.recipients.contains(&player))
+        .map(|out| &out.message)
+        .collect()
+}
+
+#[test]
+fn spectators_can_join_a_running_round() {
+    let mut lobby = Lobby::new();
+    let (code, _, _) = running_round(&mut lobby);
+
+    assert_eq!(
+        lobby.join(&code, "Late"),
+        Err(LobbyRejection::RoundInProgress)
+    );
+    let spectator = lobby.spectate(&code, "Late").unwrap();
+
+    let outbound = lobby.drain_outbound();
+    let messages = sent_to(&outbound, spectator);
+    assert!(messages
+        .iter()
+        .any(|message| matches!(message, ServerMessage::RoomUpdate(_))));
+    assert!(messages
+        .iter()
+        .any(|message| matches!(message, ServerMessage::RoundStarted { .. })));
+
+    let room = lobby.room(&code).unwrap();
+    assert_eq!(room.member(spectator).unwrap().role, Some(Role::Spectator));
+    assert!(room.game().unwrap().player(spectator).is_none());
+}
+
+#[test]
+fn only_spectators_see_the_ghost_type() {
+    let mut lobby = Lobby::new();
+    let (code, ghost, investigator) = running_round(&mut lobby);
+    let spectator = lobby.spectate(&code, "Stream").unwrap();
+    lobby.drain_outbound();
+
+    lobby.step(0.05);
+    let outbound = lobby.drain_outbound();
+
+    let snapshot_for = |player| {
+        sent_to(&outbound, player)
+            .into_iter()
+            .find_map(|message| match message {
+                ServerMessage::Snapshot(snapshot) => Some(snapshot.clone()),
+                _ => None,
+            })
+            .expect("snapshot")
+    };
+    let watched = snapshot_for(spectator);
+    assert_eq!(watched.ghost_type, Some(GhostType::Onryo));
+    assert_eq!(watched.players.len(), 2);
+    assert!(watched
+        .players
+        .iter()
+        .any(|player| player.id == ghost && player.role == Role::Ghost));
+    assert_eq!(snapshot_for(ghost).ghost_type, None);
+    assert_eq!(snapshot_for(investigator).ghost_type, None);
+}
+
+#[test]
+fn spectators_are_read_only() {
+    let mut lobby = Lobby::new();
+    let (code, _, _) = running_round(&mut lobby);
+    let spectator = lobby.spectate(&code, "Stream").unwrap();
+
+    assert!(!lobby.apply_input(spectator, InputCommand::default()));
+    assert!(matches!(
+        lobby.claim_evidence(spectator, EvidenceKind::EmfFive),
+        Err(ClaimRejection::NotInRound)
+    ));
+    assert_eq!(
+        lobby.choose_role(spectator, Role::Investigator),
+        Err(LobbyRejection::RoundInProgress)
+    );
+}
+
+#[test]
+fn spectators_have_their_own_seats() {
+    let mut lobby = Lobby::with_settings(LobbySettings {
+        max_spectators_per_room: 1,
+        ..LobbySettings::default()
+    });
+    let code = lobby.create_room("Haunted").unwrap().code;
+    for index in 0..MAX_PLAYERS_PER_ROOM {
+        lobby.join(&code, &format!("player {index}")).unwrap();
+    }
+    assert_eq!(lobby.join(&code, "late"), Err(LobbyRejection::RoomFull));
+
+    let spectator = lobby.spectate(&code, "Stream").unwrap();
+    assert_eq!(
+        lobby.spectate(&code, "Again"),
+        Err(LobbyRejection::RoomFull)
+    );
+    assert_eq!(
+        lobby.choose_role(spectator, Role::Investigator),
+        Err(LobbyRejection::RoomFull)
+    );
+
+    let info = lobby.room(&code).unwrap().info();
+    assert_eq!(info.players, MAX_PLAYERS_PER_ROOM);
+    assert_eq!(info.spectators, 1);
+    assert_eq!(lobby.stats().spectators, 1);
+}
+
+#[test]
+fn spectators_chosen_in_the_lobby_watch_the_round() {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    let investigator = lobby.join(&code, "Ray").unwrap();
+    let watcher = lobby.join(&code, "Stream").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_role(investigator, Role::Investigator).unwrap();
+    lobby.choose_role(watcher, Role::Spectator).unwrap();
+    lobby.start_round(ghost).unwrap();
+
+    let outbound = lobby.drain_outbound();
+    assert!(sent_to(&outbound, watcher)
+        .iter()
+        .any(|message| matches!(message, ServerMessage::RoundStarted { .. })));
+    let game = lobby.room(&code).unwrap().game().unwrap();
+    assert_eq!(game.snapshot().players.len(), 2);
+
+    assert!(matches!(
+        lobby.rejoin_round(watcher),
+        Some(ServerMessage::RoundStarted { .. })
+    ));
+}
+
+#[test]
+fn spectate_message_replies_with_room_joined() {
+    let mut lobby = Lobby::new();
+    let (code, _, _) = running_round(&mut lobby);
+    let mut player = None;
+
+    let reply = handle_client_message(
+        &mut lobby,
+        &mut player,
+        ClientMessage::SpectateRoom {
+            display_name: "Stream".to_string(),
+            room_code: code.to_lowercase(),
+        },
+    );
+
+    let Some(ServerMessage::RoomJoined {
+        player_id,
+        room_code,
+    }) = reply
+    else {
+        panic!("expected RoomJoined, got {reply:?}");
+    };
+    assert_eq!(player, Some(player_id));
+    assert_eq!(room_code, code);
+}
@@ -0,0 +1,80 @@
+use server::services::lobby::{Lobby, Outbound};
+use shared::prelude::{
+    EvidenceEvent, EvidenceKind, GhostType, PlayerId, Role, ServerMessage, Vec3, MAX_INVESTIGATORS,
+};
+
+fn full_team() -> (Lobby, PlayerId, Vec<PlayerId>) {
+    let mut lobby = Lobby::new();
+    let code = lobby.create_room("Haunted").unwrap().code;
+    let ghost = lobby.join(&code, "Casper").unwrap();
+    lobby.choose_role(ghost, Role::Ghost).unwrap();
+    lobby.choose_ghost_type(ghost, GhostType::Spirit).unwrap();
+    let team: Vec<PlayerId> = ["Ray", "Egon", "Peter", "Winston"]
+        .into_iter()
+        .map(|name| {
+            let id = lobby.join(&code, name).unwrap();
+            lobby.choose_role(id, Role::Investigator).unwrap();
+            id
+        })
+        .collect();
+    assert_eq!(team.len(), MAX_INVESTIGATORS);
+    lobby.start_round(ghost).unwrap();
+    (lobby, ghost, team)
+}
+
+fn spawn_of(outbound: &[Outbound], player: PlayerId) -> Vec3 {
+    outbound
+        .iter()
+        .filter(|out| out.recipients.contains(&player))
+        .find_map(|out| match out.message {
+            ServerMessage::RoundStarted { spawn, .. } => Some(spawn),
+            _ => None,
+        })
+        .expect("round start sent")
+}
+
+#[test]
+fn every_investigator_gets_a_spawn_of_their_own() {
+    let (mut lobby, ghost, team) = full_team();
+    let outbound = lobby.drain_outbound();
+
+    let ghost_spawn = spawn_of(&outbound, ghost);
+    let spawns: Vec<Vec3> = team.iter().map(|id| spawn_of(&outbound, *id)).collect();
+    for (index, spawn) in spawns.iter().enumerate() {
+        assert!(spawn.distance(ghost_spawn) >= 1.0);
+        for other in &spawns[index + 1..] {
+            assert!(spawn.distance(*other) >= 1.0);
+        }
+    }
+
+    let game = lobby.room_of(ghost).unwrap().game().unwrap();
+    assert_eq!(game.player(team[3]).unwrap().position, spawns[3]);
+}
+
+#[test]
+fn collected_evidence_reaches_the_whole_team() {
+    let (mut lobby, ghost, team) = full_team();
+    assert!(lobby.place_player(ghost, Vec3::new(0.0, 1.6, 0.0)));
+    assert!(lobby.place_player(team[0], Vec3::new(0.5, 0.9, 0.0)));
+    lobby.drain_outbound();
+
+    let reply = lobby
+        .claim_evidence(team[0], EvidenceKind::EmfFive)
+        .unwrap();
+    let collected = |message: &ServerMessage| {
+        matches!(
+            message,
+            ServerMessage::Evidence(EvidenceEvent::Collected(EvidenceKind::EmfFive))
+        )
+    };
+    assert!(collected(&reply));
+
+    let outbound = lobby.drain_outbound();
+    let shared: Vec<PlayerId> = outbound
+        .iter()
+        .filter(|out| collected(&out.message))
+        .flat_map(|out| out.recipients.clone())
+        .collect();
+    assert_eq!(shared, team[1..].to_vec());
+    assert!(!shared.contains(&ghost));
+}
@@ -0,0 +1,91 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Role;
+use crate::map::{room_id_in_house, HouseLayout};
+use crate::protocol::PlayerId;
+
+pub const MAX_CHAT_LEN: usize = 200;
+pub const DEFAULT_CHAT_RANGE: f32 = 6.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChatChannel {
+    Lobby,
+    Team,
+    // Delivery-only: team chat the ghost picked up in the room it haunts.
+    Overheard,
+}
+
+impl ChatChannel {
+    // The lobby channel closes when the round starts; after that only
+    // investigators can talk, on their team channel.
+    pub fn for_sender(role: Option<Role>, in_round: bool) -> Option<Self> {
+        match (in_round, role) {
+            (false, _) => Some(Self::Lobby),
+            (true, Some(Role::Investigator)) => Some(Self::Team),
+            (true, _) => None,
+        }
+    }
+
+    // Spectators may read the team channel; the ghost never can.
+    pub fn readable_by(self, role: Option<Role>) -> bool {
+        match self {
+            Self::Lobby => true,
+            Self::Team => matches!(role, Some(Role::Investigator | Role::Spectator)),
+            Self::Overheard => role == Some(Role::Ghost),
+        }
+    }
+}
+
+// Team chat carries to teammates in the same room or within `range`
+// metres; a speaker holding the radio reaches the whole team.
+pub fn within_earshot(
+    layout: &HouseLayout,
+    range: f32,
+    speaker: Vec3,
+    listener: Vec3,
+    radio: bool,
+) -> bool {
+    if radio || same_room(layout, speaker, listener) {
+        return true;
+    }
+    let dx = speaker.x - listener.x;
+    let dz = speaker.z - listener.z;
+    dx * dx + dz * dz <= range * range
+}
+
+// The ghost hears anything said in the room it is in, radio or not.
+pub fn overheard_by_ghost(layout: &HouseLayout, speaker: Vec3, ghost: Vec3) -> bool {
+    same_room(layout, speaker, ghost)
+}
+
+fn same_room(layout: &HouseLayout, a: Vec3, b: Vec3) -> bool {
+    room_id_in_house(layout, a).is_some_and(|room| room_id_in_house(layout, b) == Some(room))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub channel: ChatChannel,
+    pub from: PlayerId,
+    pub display_name: String,
+    pub text: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChatRejection {
+    NotInRoom,
+    Empty,
+    TooLong,
+    ChannelClosed,
+}
+
+pub fn validate_chat(text: &str) -> Result<String, ChatRejection> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ChatRejection::Empty);
+    }
+    if text.chars().count() > MAX_CHAT_LEN {
+        return Err(ChatRejection::TooLong);
+    }
+    Ok(text.to_string())
+}
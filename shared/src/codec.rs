@@ -0,0 +1,43 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+// JSON stays the default so frames can be read in browser devtools; clients
+// that offer `Binary` in their hello switch to bincode after the welcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WireCodec {
+    #[default]
+    Json,
+    Binary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl WireCodec {
+    pub const SUPPORTED: [WireCodec; 2] = [WireCodec::Binary, WireCodec::Json];
+
+    pub fn negotiate(offered: &[WireCodec]) -> WireCodec {
+        Self::SUPPORTED
+            .into_iter()
+            .find(|codec| offered.contains(codec))
+            .unwrap_or_default()
+    }
+
+    pub fn encode<T: Serialize>(self, message: &T) -> Option<Frame> {
+        match self {
+            WireCodec::Json => serde_json::to_string(message).ok().map(Frame::Text),
+            WireCodec::Binary => bincode::serialize(message).ok().map(Frame::Binary),
+        }
+    }
+}
+
+pub fn decode_text<T: DeserializeOwned>(text: &str) -> Option<T> {
+    serde_json::from_str(text).ok()
+}
+
+pub fn decode_binary<T: DeserializeOwned>(bytes: &[u8]) -> Option<T> {
+    bincode::deserialize(bytes).ok()
+}
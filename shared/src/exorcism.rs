@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{ExorcismState, GhostType};
+use crate::map::random_seed;
+
+pub fn spirit_progress(
+    mut progress: f32,
+    target_progress: f32,
+    dt: f32,
+    rate_up: f32,
+    rate_down: f32,
+) -> f32 {
+    let target_progress = target_progress.clamp(0.0, 1.0);
+    if progress < target_progress {
+        progress = (progress + rate_up * dt).min(target_progress);
+    } else if progress > target_progress {
+        progress = (progress - rate_down * dt).max(target_progress);
+    }
+    progress.clamp(0.0, 1.0)
+}
+
+pub fn banshee_advance(
+    stage: u8,
+    sequence_len: u8,
+    timing_ok: bool,
+    order_ok: bool,
+) -> ExorcismState {
+    if !order_ok || !timing_ok {
+        return ExorcismState::Failed;
+    }
+
+    let next = stage + 1;
+    if next >= sequence_len {
+        ExorcismState::Complete
+    } else {
+        ExorcismState::Stage(next)
+    }
+}
+
+// Node indices in the order they have to be pressed: a shuffle of the
+// layout's anchors, repeated if the sequence is longer than the layout.
+pub fn banshee_order(anchors: usize, len: u8, mut seed: u64) -> Vec<u8> {
+    let mut nodes: Vec<u8> = (0..anchors.clamp(1, usize::from(u8::MAX)) as u8).collect();
+    for index in (1..nodes.len()).rev() {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        nodes.swap(index, (seed % (index as u64 + 1)) as usize);
+    }
+    nodes
+        .iter()
+        .copied()
+        .cycle()
+        .take(usize::from(len))
+        .collect()
+}
+
+pub fn random_banshee_order(anchors: usize, len: u8) -> Vec<u8> {
+    banshee_order(anchors, len, random_seed(0xB45E_E5EE))
+}
+
+pub fn onryo_stack_tick(
+    stacks: f32,
+    dt: f32,
+    carrying: bool,
+    max_stacks: f32,
+    stack_rate: f32,
+) -> (f32, bool) {
+    if !carrying {
+        return (stacks, false);
+    }
+    let new_stacks = stacks + dt * stack_rate;
+    (new_stacks, new_stacks >= max_stacks)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpiritConfig {
+    pub watch_cos: f32,
+    pub watch_distance: f32,
+    pub grace_seconds: f32,
+    pub rate_up: f32,
+    pub rate_down: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BansheeConfig {
+    pub default_sequence_len: u8,
+    pub interact_distance: f32,
+    pub timing_min: f32,
+    pub timing_max: f32,
+    pub fail_reset_seconds: f32,
+}
+
+impl BansheeConfig {
+    pub fn sequence_len(&self) -> u8 {
+        self.default_sequence_len
+    }
+
+    // Every anchor is pressed at least once.
+    pub fn sequence_len_for(&self, anchors: usize) -> u8 {
+        self.sequence_len()
+            .max(anchors.min(usize::from(u8::MAX)) as u8)
+            .max(1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OnryoConfig {
+    pub default_ritual_count: u8,
+    pub interact_distance: f32,
+    pub carry_height: f32,
+    pub stack_rate: f32,
+    pub max_stacks: f32,
+    pub stack_penalty_wrong: f32,
+    pub stack_reward_correct: f32,
+}
+
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::system::Resource))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExorcismTables {
+    pub spirit: SpiritConfig,
+    pub banshee: BansheeConfig,
+    pub onryo: OnryoConfig,
+}
+
+impl Default for SpiritConfig {
+    fn default() -> Self {
+        Self {
+            watch_cos: 0.75,
+            watch_distance: 9.0,
+            grace_seconds: 4.5,
+            rate_up: 0.16,
+            rate_down: 0.1,
+        }
+    }
+}
+
+impl Default for BansheeConfig {
+    fn default() -> Self {
+        Self {
+            default_sequence_len: 3,
+            interact_distance: 1.6,
+            timing_min: 0.6,
+            timing_max: 3.5,
+            fail_reset_seconds: 2.5,
+        }
+    }
+}
+
+impl Default for OnryoConfig {
+    fn default() -> Self {
+        Self {
+            default_ritual_count: 3,
+            interact_distance: 1.8,
+            carry_height: 1.1,
+            stack_rate: 0.6,
+            max_stacks: 5.0,
+            stack_penalty_wrong: 1.0,
+            stack_reward_correct: 2.0,
+        }
+    }
+}
+
+pub fn puzzle_name(ghost_type: GhostType) -> &'static str {
+    match ghost_type {
+        GhostType::Spirit => "Spirit: The Vigil",
+        GhostType::Banshee => "Banshee: The Lament",
+        GhostType::Onryo => "Onryo: The Containment",
+    }
+}
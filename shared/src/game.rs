@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+pub const MAX_INVESTIGATORS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Role {
+    Ghost,
+    Investigator,
+    Spectator,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Equipment {
+    Emf,
+    Spiritbox,
+    Radio,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GhostType {
+    Spirit,
+    Banshee,
+    Onryo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RoundOutcome {
+    SuccessfulExorcism,
+    WrongGhost,
+    FailedExorcism,
+    InvestigatorsDead,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ExorcismState {
+    Inactive,
+    Stage(u8),
+    Progress(f32),
+    Failed,
+    Complete,
+}
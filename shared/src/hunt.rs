@@ -0,0 +1,83 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::system::Resource))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HuntTuning {
+    pub cooldown_seconds: f32,
+    pub duration_seconds: f32,
+    pub kill_radius: f32,
+}
+
+impl Default for HuntTuning {
+    fn default() -> Self {
+        Self {
+            cooldown_seconds: 45.0,
+            duration_seconds: 10.0,
+            kill_radius: 1.2,
+        }
+    }
+}
+
+// The ghost may hunt once the cooldown runs out; the first one is also
+// held back by a full cooldown from the round start.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HuntPhase {
+    Cooldown(f32),
+    Hunting(f32),
+}
+
+impl HuntPhase {
+    pub fn new(tuning: &HuntTuning) -> Self {
+        Self::Cooldown(tuning.cooldown_seconds)
+    }
+
+    pub fn is_hunting(self) -> bool {
+        matches!(self, Self::Hunting(_))
+    }
+
+    pub fn is_ready(self) -> bool {
+        matches!(self, Self::Cooldown(left) if left <= 0.0)
+    }
+
+    // Seconds left in the current phase.
+    pub fn remaining(self) -> f32 {
+        match self {
+            Self::Cooldown(left) | Self::Hunting(left) => left.max(0.0),
+        }
+    }
+
+    pub fn trigger(&mut self, tuning: &HuntTuning) -> bool {
+        if !self.is_ready() {
+            return false;
+        }
+        *self = Self::Hunting(tuning.duration_seconds);
+        true
+    }
+
+    // Returns true on the step a hunt runs out.
+    pub fn advance(&mut self, dt: f32, tuning: &HuntTuning) -> bool {
+        match *self {
+            Self::Cooldown(left) => {
+                *self = Self::Cooldown((left - dt).max(0.0));
+                false
+            }
+            Self::Hunting(left) if left - dt > 0.0 => {
+                *self = Self::Hunting(left - dt);
+                false
+            }
+            Self::Hunting(_) => {
+                *self = Self::Cooldown(tuning.cooldown_seconds);
+                true
+            }
+        }
+    }
+}
+
+// Height is ignored: the ghost floats above the investigators' heads.
+pub fn caught_by_ghost(ghost: Vec3, investigator: Vec3, tuning: &HuntTuning) -> bool {
+    let dx = ghost.x - investigator.x;
+    let dz = ghost.z - investigator.z;
+    dx * dx + dz * dz <= tuning.kill_radius * tuning.kill_radius
+}
@@ -1,3 +1,11 @@
+pub mod chat;
+pub mod codec;
+pub mod evidence;
+pub mod exorcism;
+pub mod game;
+pub mod hunt;
+pub mod map;
+pub mod movement;
 pub mod prelude;
 pub mod protocol;
 
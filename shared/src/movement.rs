@@ -0,0 +1,83 @@
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::game::Role;
+use crate::map::{move_with_collisions, CollisionWorld};
+
+pub const GHOST_SPEED: f32 = 5.2;
+pub const INVESTIGATOR_SPEED: f32 = 3.6;
+pub const SPECTATOR_SPEED: f32 = 6.0;
+pub const SPRINT_MULTIPLIER: f32 = 1.6;
+pub const BODY_RADIUS: f32 = 0.35;
+pub const MAX_INPUT_DT: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MoveInput {
+    pub forward: f32,
+    pub strafe: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub sprint: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputCommand {
+    pub seq: u32,
+    pub dt: f32,
+    pub input: MoveInput,
+}
+
+pub fn role_speed(role: Role) -> f32 {
+    match role {
+        Role::Ghost => GHOST_SPEED,
+        Role::Investigator => INVESTIGATOR_SPEED,
+        Role::Spectator => SPECTATOR_SPEED,
+    }
+}
+
+pub fn movement_delta(input: &MoveInput, role: Role, dt: f32) -> Vec3 {
+    let speed = role_speed(role);
+    let speed = if input.sprint {
+        speed * SPRINT_MULTIPLIER
+    } else {
+        speed
+    };
+
+    let forward = Vec3::new(input.yaw.sin(), 0.0, input.yaw.cos());
+    let right = Vec3::new(-forward.z, 0.0, forward.x);
+
+    let mut movement =
+        forward * input.forward.clamp(-1.0, 1.0) + right * input.strafe.clamp(-1.0, 1.0);
+    if movement.length_squared() > 0.0 {
+        movement = movement.normalize() * speed * dt;
+    }
+    movement
+}
+
+pub fn apply_command(
+    position: &mut Vec3,
+    command: &InputCommand,
+    role: Role,
+    world: &CollisionWorld,
+) {
+    let dt = command.dt.clamp(0.0, MAX_INPUT_DT);
+    step_position(position, &command.input, role, dt, world);
+}
+
+pub fn step_position(
+    position: &mut Vec3,
+    input: &MoveInput,
+    role: Role,
+    dt: f32,
+    world: &CollisionWorld,
+) {
+    let movement = movement_delta(input, role, dt);
+    move_with_collisions(
+        position,
+        movement,
+        BODY_RADIUS,
+        world.bounds,
+        &world.obstacles,
+        true,
+    );
+}
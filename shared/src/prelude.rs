@@ -1 +1,14 @@
-pub use crate::protocol::{ClientMessage, Health, ServerMessage};
+pub use crate::chat::{ChatChannel, ChatMessage, ChatRejection, MAX_CHAT_LEN};
+pub use crate::codec::WireCodec;
+pub use crate::game::{Equipment, ExorcismState, GhostType, Role, RoundOutcome, MAX_INVESTIGATORS};
+pub use crate::hunt::{HuntPhase, HuntTuning};
+pub use crate::map::{HouseLayout, HouseLayoutKind};
+pub use crate::movement::{InputCommand, MoveInput};
+pub use crate::protocol::{
+    ClaimRejection, ClientMessage, CreateRoomRequest, EvidenceEvent, EvidenceKind, ExorcismUpdate,
+    FastestExorcism, GhostTypeTally, GuestToken, GuestTokenRequest, Health, LobbyRejection,
+    MatchSnapshot, MessageRejection, OutcomeTally, PlayerId, PlayerProfile, PlayerSnapshot,
+    QueueStatus, Readiness, RoomInfo, RoomMember, RoomState, RoundSummary, ServerMessage,
+    UpdateRequired, VersionMismatch, VersionRange, PROTOCOL_VERSION,
+};
+pub use glam::Vec3;
@@ -1,16 +1,519 @@
+use glam::Vec3;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::chat::{ChatChannel, ChatMessage, ChatRejection};
+use crate::codec::WireCodec;
+use crate::game::{Equipment, ExorcismState, GhostType, Role, RoundOutcome};
+use crate::map::HouseLayoutKind;
+use crate::movement::InputCommand;
+
+pub type PlayerId = u32;
+
+// Client and server must speak exactly this version: messages are not
+// translated between versions, so there is no range to negotiate.
+pub const PROTOCOL_VERSION: u32 = 11;
+
+// Mismatch replies keep reporting ranges so every client build can read
+// them, but both ends of a range are always the one version a build speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpdateRequired {
+    #[default]
+    Client,
+    Server,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionMismatch {
+    pub server: VersionRange,
+    pub client: VersionRange,
+    pub update: UpdateRequired,
+}
+
+impl VersionRange {
+    pub const SUPPORTED: VersionRange = VersionRange::exact(PROTOCOL_VERSION);
+
+    pub const fn exact(version: u32) -> Self {
+        Self {
+            min: version,
+            max: version,
+        }
+    }
+
+    pub fn contains(&self, version: u32) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+}
+
+impl VersionMismatch {
+    // Accepts only this build's own version and says who is behind otherwise.
+    pub fn check(client_version: u32) -> Result<u32, VersionMismatch> {
+        if client_version == PROTOCOL_VERSION {
+            return Ok(client_version);
+        }
+        let update = if client_version < PROTOCOL_VERSION {
+            UpdateRequired::Client
+        } else {
+            UpdateRequired::Server
+        };
+        Err(VersionMismatch {
+            server: VersionRange::SUPPORTED,
+            client: VersionRange::exact(client_version),
+            update,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Health {
     pub status: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub build: String,
+    #[serde(default)]
+    pub protocol_version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub scheduler_on_time: bool,
+    pub last_tick_ms: Option<u64>,
+    pub tick_period_ms: Option<u64>,
+    pub rooms: usize,
+    pub rooms_at_capacity: usize,
+    pub draining: bool,
+    pub health: Health,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientMessage {
     Ping,
+    Hello {
+        version: u32,
+        resume_token: Option<String>,
+        #[serde(default)]
+        codecs: Vec<WireCodec>,
+    },
+    HeartbeatAck {
+        seq: u64,
+    },
+    JoinLobby {
+        display_name: String,
+        room_code: String,
+    },
+    ChooseRole {
+        role: Role,
+    },
+    ChooseGhostType {
+        ghost_type: GhostType,
+    },
+    ChooseLayout {
+        layout: HouseLayoutKind,
+    },
+    StartRound,
+    Input(InputCommand),
+    SelectEquipment {
+        equipment: Equipment,
+    },
+    UseEquipment,
+    ToggleRoomLight,
+    JournalGuess {
+        ghost_type: GhostType,
+    },
+    JournalConfirm,
+    Interact,
+    ClaimEvidence(EvidenceKind),
+    PressBansheeNode {
+        node: u8,
+    },
+    ClaimExorcism,
+    RequestProfile {
+        display_name: String,
+    },
+    SpectateRoom {
+        display_name: String,
+        room_code: String,
+    },
+    QuickPlay {
+        display_name: String,
+        role: Option<Role>,
+        layout: HouseLayoutKind,
+    },
+    LeaveQueue,
+    Chat {
+        channel: ChatChannel,
+        text: String,
+    },
+    StartHunt,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerMessage {
     Pong,
+    Welcome {
+        protocol_version: u32,
+        resume_token: String,
+        heartbeat_ms: u64,
+        player_id: Option<PlayerId>,
+        room: Option<RoomState>,
+        #[serde(default)]
+        codec: WireCodec,
+    },
+    Heartbeat {
+        seq: u64,
+    },
+    RoomJoined {
+        player_id: PlayerId,
+        room_code: String,
+    },
+    RoomUpdate(RoomState),
+    LobbyRejected(LobbyRejection),
+    RoundStarted {
+        layout: HouseLayoutKind,
+        spawn: Vec3,
+        // Banshee nodes in the order the server expects them pressed.
+        #[serde(default)]
+        banshee_order: Vec<u8>,
+    },
+    Snapshot(MatchSnapshot),
+    RoomLight {
+        room_id: u8,
+        enabled: bool,
+    },
+    Evidence(EvidenceEvent),
+    Investigation {
+        guess: Option<GhostType>,
+        confirmed: bool,
+    },
+    Exorcism(ExorcismUpdate),
+    RoundEnded(RoundSummary),
+    ClaimRejected(ClaimRejection),
+    ShuttingDown {
+        remaining_ms: u64,
+    },
+    ProtocolMismatch(VersionMismatch),
+    Profile(PlayerProfile),
+    MessageRejected(MessageRejection),
+    QueueStatus(QueueStatus),
+    RoundCountdown {
+        starts_in_ms: Option<u64>,
+    },
+    Chat(ChatMessage),
+    ChatRejected(ChatRejection),
+    // `remaining_ms` counts down the hunt while it runs and the cooldown
+    // before the next one otherwise.
+    Hunt {
+        active: bool,
+        remaining_ms: u64,
+    },
+    Eliminated {
+        player: PlayerId,
+    },
+}
+
+impl ClientMessage {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ClientMessage::Ping => "Ping",
+            ClientMessage::Hello { .. } => "Hello",
+            ClientMessage::HeartbeatAck { .. } => "HeartbeatAck",
+            ClientMessage::JoinLobby { .. } => "JoinLobby",
+            ClientMessage::ChooseRole { .. } => "ChooseRole",
+            ClientMessage::ChooseGhostType { .. } => "ChooseGhostType",
+            ClientMessage::ChooseLayout { .. } => "ChooseLayout",
+            ClientMessage::StartRound => "StartRound",
+            ClientMessage::Input(_) => "Input",
+            ClientMessage::SelectEquipment { .. } => "SelectEquipment",
+            ClientMessage::UseEquipment => "UseEquipment",
+            ClientMessage::ToggleRoomLight => "ToggleRoomLight",
+            ClientMessage::JournalGuess { .. } => "JournalGuess",
+            ClientMessage::JournalConfirm => "JournalConfirm",
+            ClientMessage::Interact => "Interact",
+            ClientMessage::ClaimEvidence(_) => "ClaimEvidence",
+            ClientMessage::PressBansheeNode { .. } => "PressBansheeNode",
+            ClientMessage::ClaimExorcism => "ClaimExorcism",
+            ClientMessage::RequestProfile { .. } => "RequestProfile",
+            ClientMessage::SpectateRoom { .. } => "SpectateRoom",
+            ClientMessage::QuickPlay { .. } => "QuickPlay",
+            ClientMessage::LeaveQueue => "LeaveQueue",
+            ClientMessage::Chat { .. } => "Chat",
+            ClientMessage::StartHunt => "StartHunt",
+        }
+    }
+}
+
+impl ServerMessage {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ServerMessage::Pong => "Pong",
+            ServerMessage::Welcome { .. } => "Welcome",
+            ServerMessage::Heartbeat { .. } => "Heartbeat",
+            ServerMessage::RoomJoined { .. } => "RoomJoined",
+            ServerMessage::RoomUpdate(_) => "RoomUpdate",
+            ServerMessage::LobbyRejected(_) => "LobbyRejected",
+            ServerMessage::RoundStarted { .. } => "RoundStarted",
+            ServerMessage::Snapshot(_) => "Snapshot",
+            ServerMessage::RoomLight { .. } => "RoomLight",
+            ServerMessage::Evidence(_) => "Evidence",
+            ServerMessage::Investigation { .. } => "Investigation",
+            ServerMessage::Exorcism(_) => "Exorcism",
+            ServerMessage::RoundEnded(_) => "RoundEnded",
+            ServerMessage::ClaimRejected(_) => "ClaimRejected",
+            ServerMessage::ShuttingDown { .. } => "ShuttingDown",
+            ServerMessage::ProtocolMismatch(_) => "ProtocolMismatch",
+            ServerMessage::Profile(_) => "Profile",
+            ServerMessage::MessageRejected(_) => "MessageRejected",
+            ServerMessage::QueueStatus(_) => "QueueStatus",
+            ServerMessage::RoundCountdown { .. } => "RoundCountdown",
+            ServerMessage::Chat(_) => "Chat",
+            ServerMessage::ChatRejected(_) => "ChatRejected",
+            ServerMessage::Hunt { .. } => "Hunt",
+            ServerMessage::Eliminated { .. } => "Eliminated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestTokenRequest {
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestToken {
+    pub token: String,
+    pub account_id: String,
+    pub display_name: String,
+    pub expires_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateRoomRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomInfo {
+    pub code: String,
+    pub name: String,
+    pub players: usize,
+    pub max_players: usize,
+    #[serde(default)]
+    pub spectators: usize,
+    pub has_ghost: bool,
+    pub in_round: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomMember {
+    pub id: PlayerId,
+    pub display_name: String,
+    pub role: Option<Role>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoomState {
+    pub code: String,
+    pub name: String,
+    pub layout: HouseLayoutKind,
+    pub members: Vec<RoomMember>,
+    pub in_round: bool,
+}
+
+// `position` counts from 1 among players waiting for the same layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStatus {
+    pub position: usize,
+    pub waiting: usize,
+    pub estimated_wait_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LobbyRejection {
+    InvalidName,
+    TooManyRooms,
+    RoomNotFound,
+    RoomFull,
+    NotInRoom,
+    GhostTaken,
+    NotGhost,
+    RoundInProgress,
+    RolesIncomplete,
+    ShuttingDown,
+    InvalidRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MessageRejection {
+    Malformed,
+    Unsupported,
+    RateLimited,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClaimRejection {
+    NotInRound,
+    WrongRole,
+    InvalidInput,
+    TooFast,
+    ImpossibleEvidence,
+    OutOfRange,
+    UnknownNode,
+    PuzzleNotStarted,
+    PuzzleIncomplete,
+    HuntNotReady,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    pub id: PlayerId,
+    pub role: Role,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub last_input_seq: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchSnapshot {
+    pub tick: u64,
+    pub server_time: f64,
+    pub players: Vec<PlayerSnapshot>,
+    // Only filled in for spectators; players have to find out for themselves.
+    #[serde(default)]
+    pub ghost_type: Option<GhostType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvidenceKind {
+    EmfFive,
+    SpiritboxResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EvidenceEvent {
+    EmfLevel { level: u8 },
+    SpiritboxReply { message: String, evidence: bool },
+    Collected(EvidenceKind),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExorcismUpdate {
+    pub state: ExorcismState,
+    pub progress: f32,
+    pub stage: u8,
+    pub stacks: f32,
+    pub max_stacks: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundSummary {
+    pub outcome: RoundOutcome,
+    pub ghost_type: GhostType,
+    pub guess: Option<GhostType>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutcomeTally {
+    pub successful_exorcism: u32,
+    pub wrong_ghost: u32,
+    pub failed_exorcism: u32,
+    #[serde(default)]
+    pub investigators_dead: u32,
+}
+
+impl OutcomeTally {
+    pub fn count(&self, outcome: RoundOutcome) -> u32 {
+        match outcome {
+            RoundOutcome::SuccessfulExorcism => self.successful_exorcism,
+            RoundOutcome::WrongGhost => self.wrong_ghost,
+            RoundOutcome::FailedExorcism => self.failed_exorcism,
+            RoundOutcome::InvestigatorsDead => self.investigators_dead,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.successful_exorcism + self.wrong_ghost + self.failed_exorcism + self.investigators_dead
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GhostTypeTally {
+    pub spirit: u32,
+    pub banshee: u32,
+    pub onryo: u32,
+}
+
+impl GhostTypeTally {
+    pub fn count(&self, ghost_type: GhostType) -> u32 {
+        match ghost_type {
+            GhostType::Spirit => self.spirit,
+            GhostType::Banshee => self.banshee,
+            GhostType::Onryo => self.onryo,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FastestExorcism {
+    pub puzzle: GhostType,
+    pub layout: HouseLayoutKind,
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub display_name: String,
+    pub games_as_ghost: u32,
+    pub games_as_investigator: u32,
+    // Outcomes are kept per role: a banished ghost is a loss for the
+    // ghost player and a win for the investigators.
+    #[serde(default)]
+    pub outcomes_as_ghost: OutcomeTally,
+    #[serde(default)]
+    pub outcomes_as_investigator: OutcomeTally,
+    pub ghost_types: GhostTypeTally,
+    pub favourite_ghost: Option<GhostType>,
+    pub fastest_exorcisms: Vec<FastestExorcism>,
+}
+
+impl PlayerProfile {
+    pub fn new(display_name: &str) -> Self {
+        Self {
+            display_name: display_name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn games_played(&self) -> u32 {
+        self.games_as_ghost + self.games_as_investigator
+    }
+
+    pub fn outcomes(&self, role: Role) -> OutcomeTally {
+        match role {
+            Role::Ghost => self.outcomes_as_ghost,
+            Role::Investigator => self.outcomes_as_investigator,
+            Role::Spectator => OutcomeTally::default(),
+        }
+    }
+
+    pub fn win_rate(&self, role: Role, outcome: RoundOutcome) -> f32 {
+        let outcomes = self.outcomes(role);
+        match outcomes.total() {
+            0 => 0.0,
+            total => outcomes.count(outcome) as f32 / total as f32,
+        }
+    }
+
+    pub fn fastest(&self, puzzle: GhostType, layout: HouseLayoutKind) -> Option<f64> {
+        self.fastest_exorcisms
+            .iter()
+            .find(|record| record.puzzle == puzzle && record.layout == layout)
+            .map(|record| record.seconds)
+    }
 }
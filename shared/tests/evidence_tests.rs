@@ -1,9 +1,9 @@
-use crate::core::GhostType;
-use crate::gameplay::evidence::{
+use shared::evidence::{
     emf_five_candidate, emf_level, overlap_distance, spiritbox_bearing, spiritbox_is_evidence,
     spiritbox_reply, EvidenceTuning, SpiritboxBearing, SpiritboxReply,
 };
-use bevy::prelude::Vec3;
+use shared::game::GhostType;
+use shared::prelude::Vec3;
 
 #[test]
 fn spirit_emf_levels_ramp_with_distance() {
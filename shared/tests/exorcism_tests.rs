@@ -1,6 +1,7 @@
-use crate::gameplay::exorcism::rules::{banshee_advance, onryo_stack_tick, spirit_progress};
-use crate::gameplay::exorcism::tables::ExorcismTables;
-use crate::gameplay::exorcism::ExorcismState;
+use shared::exorcism::{
+    banshee_advance, banshee_order, onryo_stack_tick, spirit_progress, ExorcismTables,
+};
+use shared::game::ExorcismState;
 
 #[test]
 fn spirit_progress_rises_toward_anchor_coverage() {
@@ -55,6 +56,22 @@ fn banshee_advance_completes_on_last_step() {
     assert!(matches!(state, ExorcismState::Complete));
 }
 
+#[test]
+fn banshee_order_shuffles_every_anchor_before_repeating() {
+    for seed in [1, 7, 0xDEAD_BEEF] {
+        let order = banshee_order(3, 5, seed);
+        assert_eq!(order.len(), 5);
+        let mut first = order[..3].to_vec();
+        first.sort();
+        assert_eq!(first, vec![0, 1, 2]);
+        assert_eq!(order[3..], order[..2]);
+        assert_eq!(banshee_order(3, 5, seed), order);
+    }
+    let tables = ExorcismTables::default();
+    assert_eq!(tables.banshee.sequence_len_for(2), 3);
+    assert_eq!(tables.banshee.sequence_len_for(4), 4);
+}
+
 #[test]
 fn onryo_stack_fails_at_max() {
     let tables = ExorcismTables::default();
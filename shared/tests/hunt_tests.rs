@@ -0,0 +1,40 @@
+use glam::Vec3;
+use shared::hunt::{caught_by_ghost, HuntPhase, HuntTuning};
+
+#[test]
+fn hunts_wait_for_the_cooldown_and_then_run_their_course() {
+    let tuning = HuntTuning {
+        cooldown_seconds: 2.0,
+        duration_seconds: 1.0,
+        kill_radius: 1.0,
+    };
+    let mut phase = HuntPhase::new(&tuning);
+    assert!(!phase.trigger(&tuning));
+
+    assert!(!phase.advance(2.0, &tuning));
+    assert!(phase.is_ready());
+    assert!(phase.trigger(&tuning));
+    assert!(phase.is_hunting());
+    assert!(!phase.trigger(&tuning));
+
+    assert!(!phase.advance(0.5, &tuning));
+    assert_eq!(phase.remaining(), 0.5);
+    assert!(phase.advance(0.5, &tuning));
+    assert_eq!(phase, HuntPhase::Cooldown(2.0));
+}
+
+#[test]
+fn the_kill_radius_ignores_height() {
+    let tuning = HuntTuning::default();
+    let ghost = Vec3::new(0.0, 1.6, 0.0);
+    assert!(caught_by_ghost(
+        ghost,
+        Vec3::new(tuning.kill_radius - 0.1, 0.9, 0.0),
+        &tuning
+    ));
+    assert!(!caught_by_ghost(
+        ghost,
+        Vec3::new(0.0, 0.9, tuning.kill_radius + 0.1),
+        &tuning
+    ));
+}
@@ -0,0 +1,65 @@
+use shared::game::Role;
+use shared::map::HouseLayout;
+use shared::movement::{
+    apply_command, movement_delta, step_position, InputCommand, MoveInput, GHOST_SPEED,
+    INVESTIGATOR_SPEED, MAX_INPUT_DT, SPRINT_MULTIPLIER,
+};
+use shared::prelude::Vec3;
+
+#[test]
+fn movement_is_normalized_for_diagonal_input() {
+    let input = MoveInput {
+        forward: 1.0,
+        strafe: 1.0,
+        yaw: 0.0,
+        pitch: 0.0,
+        sprint: false,
+    };
+    let delta = movement_delta(&input, Role::Ghost, 1.0);
+    assert!((delta.length() - GHOST_SPEED).abs() < 0.001);
+}
+
+#[test]
+fn sprint_scales_speed() {
+    let input = MoveInput {
+        forward: 1.0,
+        strafe: 0.0,
+        yaw: 0.0,
+        pitch: 0.0,
+        sprint: true,
+    };
+    let delta = movement_delta(&input, Role::Ghost, 1.0);
+    assert!((delta.length() - GHOST_SPEED * SPRINT_MULTIPLIER).abs() < 0.001);
+}
+
+#[test]
+fn step_position_stops_at_divider_wall() {
+    let world = HouseLayout::two_room().collision_world();
+    let mut position = Vec3::new(1.2, 0.9, 5.8);
+    let input = MoveInput {
+        forward: 1.0,
+        strafe: 0.0,
+        yaw: std::f32::consts::FRAC_PI_2,
+        pitch: 0.0,
+        sprint: false,
+    };
+    step_position(&mut position, &input, Role::Investigator, 0.2, &world);
+    assert!(position.x < 1.8);
+}
+
+#[test]
+fn command_dt_is_capped() {
+    let world = HouseLayout::two_room().collision_world();
+    let mut position = Vec3::new(-6.0, 0.9, -5.5);
+    let start = position;
+    let command = InputCommand {
+        seq: 1,
+        dt: 5.0,
+        input: MoveInput {
+            forward: 1.0,
+            ..MoveInput::default()
+        },
+    };
+    apply_command(&mut position, &command, Role::Investigator, &world);
+    assert!(position.distance(start) <= INVESTIGATOR_SPEED * MAX_INPUT_DT + 0.001);
+}
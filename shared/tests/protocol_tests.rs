@@ -1,12 +1,48 @@
-use shared::protocol::{ClientMessage, Health, ServerMessage};
+use serde::Serialize;
+use shared::chat::{
+    overheard_by_ghost, validate_chat, within_earshot, ChatChannel, ChatMessage, ChatRejection,
+    MAX_CHAT_LEN,
+};
+use shared::codec::{self, Frame, WireCodec};
+use shared::game::{Equipment, ExorcismState, GhostType, Role, RoundOutcome};
+use shared::map::{HouseLayout, HouseLayoutKind};
+use shared::movement::{InputCommand, MoveInput};
+use shared::prelude::Vec3;
+use shared::protocol::{
+    ClaimRejection, ClientMessage, CreateRoomRequest, EvidenceEvent, EvidenceKind, ExorcismUpdate,
+    FastestExorcism, GuestToken, GuestTokenRequest, Health, LobbyRejection, MatchSnapshot,
+    MessageRejection, OutcomeTally, PlayerProfile, PlayerSnapshot, QueueStatus, RoomInfo,
+    RoomMember, RoomState, RoundSummary, ServerMessage, UpdateRequired, VersionMismatch,
+    VersionRange, PROTOCOL_VERSION,
+};
 
 #[test]
 fn health_serializes() {
     let payload = Health {
         status: "ok".to_string(),
+        version: "0.1.0".to_string(),
+        build: "dev".to_string(),
+        protocol_version: PROTOCOL_VERSION,
     };
     let json = serde_json::to_string(&payload).expect("serialize health");
     assert!(json.contains("ok"));
+    let decoded: Health = serde_json::from_str(&json).expect("deserialize health");
+    assert_eq!(decoded, payload);
+}
+
+#[test]
+fn bare_health_payload_still_parses() {
+    let decoded: Health = serde_json::from_str(r#"{"status":"ok"}"#).expect("deserialize health");
+    assert_eq!(decoded.status, "ok");
+    assert_eq!(decoded.protocol_version, 0);
+}
+
+#[test]
+fn snapshots_without_a_ghost_type_still_parse() {
+    let decoded: MatchSnapshot =
+        serde_json::from_str(r#"{"tick":1,"server_time":0.05,"players":[]}"#)
+            .expect("deserialize snapshot");
+    assert_eq!(decoded.ghost_type, None);
 }
 
 #[test]
@@ -24,3 +60,532 @@ fn server_message_roundtrip() {
     let decoded: ServerMessage = serde_json::from_str(&json).expect("deserialize server message");
     assert!(matches!(decoded, ServerMessage::Pong));
 }
+
+fn all_client_messages() -> Vec<ClientMessage> {
+    vec![
+        ClientMessage::Ping,
+        ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+            resume_token: Some("token".to_string()),
+            codecs: vec![WireCodec::Binary, WireCodec::Json],
+        },
+        ClientMessage::HeartbeatAck { seq: 3 },
+        ClientMessage::JoinLobby {
+            display_name: "Casey".to_string(),
+            room_code: "ABCD".to_string(),
+        },
+        ClientMessage::ChooseRole {
+            role: Role::Investigator,
+        },
+        ClientMessage::ChooseRole {
+            role: Role::Spectator,
+        },
+        ClientMessage::ChooseGhostType {
+            ghost_type: GhostType::Banshee,
+        },
+        ClientMessage::ChooseLayout {
+            layout: HouseLayoutKind::ThreeRoom,
+        },
+        ClientMessage::StartRound,
+        ClientMessage::Input(InputCommand {
+            seq: 12,
+            dt: 0.016,
+            input: MoveInput {
+                forward: 1.0,
+                strafe: -1.0,
+                yaw: 0.5,
+                pitch: 0.1,
+                sprint: true,
+            },
+        }),
+        ClientMessage::SelectEquipment {
+            equipment: Equipment::Spiritbox,
+        },
+        ClientMessage::UseEquipment,
+        ClientMessage::ToggleRoomLight,
+        ClientMessage::JournalGuess {
+            ghost_type: GhostType::Onryo,
+        },
+        ClientMessage::JournalConfirm,
+        ClientMessage::Interact,
+        ClientMessage::ClaimEvidence(EvidenceKind::SpiritboxResponse),
+        ClientMessage::PressBansheeNode { node: 2 },
+        ClientMessage::ClaimExorcism,
+        ClientMessage::RequestProfile {
+            display_name: "Ray".to_string(),
+        },
+        ClientMessage::SpectateRoom {
+            display_name: "Quinn".to_string(),
+            room_code: "ABCD".to_string(),
+        },
+        ClientMessage::QuickPlay {
+            display_name: "Quinn".to_string(),
+            role: Some(Role::Ghost),
+            layout: HouseLayoutKind::TwoRoom,
+        },
+        ClientMessage::QuickPlay {
+            display_name: "Quinn".to_string(),
+            role: None,
+            layout: HouseLayoutKind::ThreeRoom,
+        },
+        ClientMessage::LeaveQueue,
+        ClientMessage::Chat {
+            channel: ChatChannel::Team,
+            text: "EMF 5 in the kitchen".to_string(),
+        },
+        ClientMessage::StartHunt,
+    ]
+}
+
+fn all_server_messages() -> Vec<ServerMessage> {
+    vec![
+        ServerMessage::Pong,
+        ServerMessage::Welcome {
+            protocol_version: PROTOCOL_VERSION,
+            resume_token: "token".to_string(),
+            heartbeat_ms: 5000,
+            player_id: Some(7),
+            room: None,
+            codec: WireCodec::Binary,
+        },
+        ServerMessage::Heartbeat { seq: 3 },
+        ServerMessage::RoomJoined {
+            player_id: 7,
+            room_code: "ABCD".to_string(),
+        },
+        ServerMessage::RoomUpdate(RoomState {
+            code: "ABCD".to_string(),
+            name: "Haunted".to_string(),
+            layout: HouseLayoutKind::TwoRoom,
+            members: vec![RoomMember {
+                id: 7,
+                display_name: "Casper".to_string(),
+                role: Some(Role::Ghost),
+            }],
+            in_round: false,
+        }),
+        ServerMessage::LobbyRejected(LobbyRejection::GhostTaken),
+        ServerMessage::RoundStarted {
+            layout: HouseLayoutKind::ThreeRoom,
+            spawn: Vec3::new(-6.0, 0.9, -5.5),
+            banshee_order: vec![2, 0, 1],
+        },
+        ServerMessage::Snapshot(MatchSnapshot {
+            tick: 42,
+            server_time: 2.1,
+            players: vec![PlayerSnapshot {
+                id: 7,
+                role: Role::Ghost,
+                position: Vec3::new(1.0, 1.6, -2.0),
+                yaw: 0.25,
+                last_input_seq: 12,
+            }],
+            ghost_type: None,
+        }),
+        ServerMessage::Snapshot(MatchSnapshot {
+            tick: 43,
+            server_time: 2.15,
+            players: Vec::new(),
+            ghost_type: Some(GhostType::Onryo),
+        }),
+        ServerMessage::RoomLight {
+            room_id: 1,
+            enabled: false,
+        },
+        ServerMessage::Evidence(EvidenceEvent::EmfLevel { level: 4 }),
+        ServerMessage::Evidence(EvidenceEvent::SpiritboxReply {
+            message: "Behind you...".to_string(),
+            evidence: true,
+        }),
+        ServerMessage::Evidence(EvidenceEvent::Collected(EvidenceKind::EmfFive)),
+        ServerMessage::Investigation {
+            guess: Some(GhostType::Spirit),
+            confirmed: true,
+        },
+        ServerMessage::Exorcism(ExorcismUpdate {
+            state: ExorcismState::Progress(0.5),
+            progress: 0.5,
+            stage: 1,
+            stacks: 0.0,
+            max_stacks: 2.0,
+        }),
+        ServerMessage::RoundEnded(RoundSummary {
+            outcome: RoundOutcome::WrongGhost,
+            ghost_type: GhostType::Banshee,
+            guess: Some(GhostType::Spirit),
+        }),
+        ServerMessage::ClaimRejected(ClaimRejection::OutOfRange),
+        ServerMessage::ShuttingDown {
+            remaining_ms: 90_000,
+        },
+        ServerMessage::LobbyRejected(LobbyRejection::ShuttingDown),
+        ServerMessage::ProtocolMismatch(VersionMismatch {
+            server: VersionRange::SUPPORTED,
+            client: VersionRange::exact(PROTOCOL_VERSION + 1),
+            update: UpdateRequired::Server,
+        }),
+        ServerMessage::Profile(PlayerProfile {
+            display_name: "Ray".to_string(),
+            games_as_investigator: 3,
+            outcomes_as_investigator: OutcomeTally {
+                successful_exorcism: 2,
+                wrong_ghost: 1,
+                failed_exorcism: 0,
+                investigators_dead: 0,
+            },
+            favourite_ghost: Some(GhostType::Banshee),
+            fastest_exorcisms: vec![FastestExorcism {
+                puzzle: GhostType::Banshee,
+                layout: HouseLayoutKind::ThreeRoom,
+                seconds: 95.5,
+            }],
+            ..PlayerProfile::default()
+        }),
+        ServerMessage::MessageRejected(MessageRejection::RateLimited),
+        ServerMessage::QueueStatus(QueueStatus {
+            position: 2,
+            waiting: 3,
+            estimated_wait_ms: Some(12_000),
+        }),
+        ServerMessage::RoundCountdown {
+            starts_in_ms: Some(10_000),
+        },
+        ServerMessage::RoundCountdown { starts_in_ms: None },
+        ServerMessage::LobbyRejected(LobbyRejection::InvalidRole),
+        ServerMessage::Chat(ChatMessage {
+            channel: ChatChannel::Lobby,
+            from: 2,
+            display_name: "Ray".to_string(),
+            text: "ready?".to_string(),
+        }),
+        ServerMessage::ChatRejected(ChatRejection::ChannelClosed),
+        ServerMessage::Hunt {
+            active: true,
+            remaining_ms: 10_000,
+        },
+        ServerMessage::Eliminated { player: 3 },
+        ServerMessage::ClaimRejected(ClaimRejection::HuntNotReady),
+        ServerMessage::RoundEnded(RoundSummary {
+            outcome: RoundOutcome::InvestigatorsDead,
+            ghost_type: GhostType::Onryo,
+            guess: None,
+        }),
+    ]
+}
+
+#[test]
+fn every_client_message_roundtrips() {
+    for msg in all_client_messages() {
+        let json = serde_json::to_string(&msg).expect("serialize client message");
+        let decoded: ClientMessage =
+            serde_json::from_str(&json).expect("deserialize client message");
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+    }
+}
+
+#[test]
+fn every_server_message_roundtrips() {
+    for msg in all_server_messages() {
+        let json = serde_json::to_string(&msg).expect("serialize server message");
+        let decoded: ServerMessage =
+            serde_json::from_str(&json).expect("deserialize server message");
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+    }
+}
+
+#[test]
+fn every_client_message_roundtrips_in_binary() {
+    for msg in all_client_messages() {
+        let Some(Frame::Binary(bytes)) = WireCodec::Binary.encode(&msg) else {
+            panic!("binary codec should produce a binary frame");
+        };
+        let decoded: ClientMessage = codec::decode_binary(&bytes).expect("decode client message");
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+    }
+}
+
+#[test]
+fn every_server_message_roundtrips_in_binary() {
+    for msg in all_server_messages() {
+        let Some(Frame::Binary(bytes)) = WireCodec::Binary.encode(&msg) else {
+            panic!("binary codec should produce a binary frame");
+        };
+        let decoded: ServerMessage = codec::decode_binary(&bytes).expect("decode server message");
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+    }
+}
+
+fn binary_tag<T: Serialize>(message: &T) -> u32 {
+    let Some(Frame::Binary(bytes)) = WireCodec::Binary.encode(message) else {
+        panic!("binary codec should produce a binary frame");
+    };
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+// Binary frames lead with the variant index, so new messages only ever go
+// at the end of their enum.
+#[test]
+fn existing_messages_keep_their_binary_tags() {
+    assert_eq!(
+        binary_tag(&ClientMessage::ChooseRole { role: Role::Ghost }),
+        4
+    );
+    assert_eq!(
+        binary_tag(&ClientMessage::RequestProfile {
+            display_name: "Ray".to_string(),
+        }),
+        18
+    );
+    assert_eq!(binary_tag(&ClientMessage::LeaveQueue), 21);
+    assert_eq!(
+        binary_tag(&ServerMessage::LobbyRejected(LobbyRejection::ShuttingDown)),
+        5
+    );
+    assert_eq!(binary_tag(&LobbyRejection::ShuttingDown), 9);
+    assert_eq!(
+        binary_tag(&ServerMessage::RoundStarted {
+            layout: HouseLayoutKind::TwoRoom,
+            spawn: Vec3::ZERO,
+            banshee_order: Vec::new(),
+        }),
+        6
+    );
+    assert_eq!(
+        binary_tag(&ServerMessage::MessageRejected(MessageRejection::Malformed)),
+        17
+    );
+    assert_eq!(
+        binary_tag(&ServerMessage::RoundCountdown { starts_in_ms: None }),
+        19
+    );
+    assert_eq!(
+        binary_tag(&ClientMessage::Chat {
+            channel: ChatChannel::Lobby,
+            text: "hi".to_string(),
+        }),
+        22
+    );
+    assert_eq!(
+        binary_tag(&ServerMessage::ChatRejected(ChatRejection::Empty)),
+        21
+    );
+    assert_eq!(binary_tag(&Equipment::Radio), 2);
+    assert_eq!(binary_tag(&ChatChannel::Overheard), 2);
+    assert_eq!(binary_tag(&ClientMessage::StartHunt), 23);
+    assert_eq!(binary_tag(&ServerMessage::Eliminated { player: 3 }), 23);
+    assert_eq!(binary_tag(&ClaimRejection::HuntNotReady), 9);
+    assert_eq!(binary_tag(&RoundOutcome::InvestigatorsDead), 3);
+}
+
+#[test]
+fn json_codec_matches_plain_serde_json() {
+    for msg in all_server_messages() {
+        let Some(Frame::Text(text)) = WireCodec::Json.encode(&msg) else {
+            panic!("json codec should produce a text frame");
+        };
+        assert_eq!(text, serde_json::to_string(&msg).unwrap());
+        let decoded: ServerMessage = codec::decode_text(&text).expect("decode server message");
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", msg));
+    }
+}
+
+#[test]
+fn binary_snapshots_are_smaller_than_json() {
+    let snapshot = all_server_messages()
+        .into_iter()
+        .find(|msg| matches!(msg, ServerMessage::Snapshot(_)))
+        .expect("snapshot in the sample set");
+    let Some(Frame::Binary(bytes)) = WireCodec::Binary.encode(&snapshot) else {
+        panic!("binary frame expected");
+    };
+    let Some(Frame::Text(text)) = WireCodec::Json.encode(&snapshot) else {
+        panic!("text frame expected");
+    };
+    assert!(bytes.len() * 2 < text.len());
+}
+
+#[test]
+fn codec_negotiation_prefers_binary_and_falls_back_to_json() {
+    assert_eq!(
+        WireCodec::negotiate(&[WireCodec::Json, WireCodec::Binary]),
+        WireCodec::Binary
+    );
+    assert_eq!(WireCodec::negotiate(&[WireCodec::Json]), WireCodec::Json);
+    assert_eq!(WireCodec::negotiate(&[]), WireCodec::Json);
+
+    let hello: ClientMessage =
+        serde_json::from_str(r#"{"Hello":{"version":1,"resume_token":null}}"#).unwrap();
+    assert!(matches!(hello, ClientMessage::Hello { ref codecs, .. } if codecs.is_empty()));
+}
+
+#[test]
+fn only_the_exact_protocol_version_is_accepted() {
+    assert_eq!(
+        VersionMismatch::check(PROTOCOL_VERSION),
+        Ok(PROTOCOL_VERSION)
+    );
+
+    let stale = VersionMismatch::check(PROTOCOL_VERSION - 1).unwrap_err();
+    assert_eq!(stale.update, UpdateRequired::Client);
+    assert_eq!(stale.server, VersionRange::exact(PROTOCOL_VERSION));
+    assert_eq!(stale.client, VersionRange::exact(PROTOCOL_VERSION - 1));
+    let ahead = VersionMismatch::check(PROTOCOL_VERSION + 1).unwrap_err();
+    assert_eq!(ahead.update, UpdateRequired::Server);
+
+    // Hellos from builds that still offered a range parse and get the
+    // mismatch reply.
+    let hello: ClientMessage =
+        serde_json::from_str(r#"{"Hello":{"version":9,"min_version":9,"resume_token":null}}"#)
+            .unwrap();
+    assert!(matches!(hello, ClientMessage::Hello { version: 9, .. }));
+}
+
+#[test]
+fn room_listing_round_trips() {
+    let request = CreateRoomRequest {
+        name: "Haunted".to_string(),
+    };
+    let json = serde_json::to_string(&request).expect("serialize request");
+    let decoded: CreateRoomRequest = serde_json::from_str(&json).expect("deserialize request");
+    assert_eq!(decoded, request);
+
+    let request = GuestTokenRequest {
+        display_name: "Ray".to_string(),
+    };
+    let json = serde_json::to_string(&request).expect("serialize token request");
+    let decoded: GuestTokenRequest = serde_json::from_str(&json).expect("deserialize request");
+    assert_eq!(decoded, request);
+
+    let issued = GuestToken {
+        token: "abcd.ef01".to_string(),
+        account_id: "guest-0123456789abcdef".to_string(),
+        display_name: "Ray".to_string(),
+        expires_ms: 1_700_000_000_000,
+    };
+    let json = serde_json::to_string(&issued).expect("serialize token");
+    let decoded: GuestToken = serde_json::from_str(&json).expect("deserialize token");
+    assert_eq!(decoded, issued);
+
+    let rooms = vec![RoomInfo {
+        code: "ABCD".to_string(),
+        name: "Haunted".to_string(),
+        players: 2,
+        max_players: 5,
+        spectators: 1,
+        has_ghost: true,
+        in_round: false,
+    }];
+    let json = serde_json::to_string(&rooms).expect("serialize rooms");
+    let decoded: Vec<RoomInfo> = serde_json::from_str(&json).expect("deserialize rooms");
+    assert_eq!(decoded, rooms);
+}
+
+#[test]
+fn profile_rates_and_records_read_back() {
+    let profile = PlayerProfile {
+        games_as_ghost: 1,
+        games_as_investigator: 3,
+        outcomes_as_ghost: OutcomeTally {
+            successful_exorcism: 1,
+            ..OutcomeTally::default()
+        },
+        outcomes_as_investigator: OutcomeTally {
+            successful_exorcism: 3,
+            wrong_ghost: 1,
+            failed_exorcism: 0,
+            investigators_dead: 0,
+        },
+        fastest_exorcisms: vec![FastestExorcism {
+            puzzle: GhostType::Spirit,
+            layout: HouseLayoutKind::TwoRoom,
+            seconds: 42.0,
+        }],
+        ..PlayerProfile::new("Ray")
+    };
+    assert_eq!(profile.games_played(), 4);
+    assert_eq!(
+        profile.win_rate(Role::Investigator, RoundOutcome::SuccessfulExorcism),
+        0.75
+    );
+    assert_eq!(
+        profile.win_rate(Role::Investigator, RoundOutcome::FailedExorcism),
+        0.0
+    );
+    assert_eq!(
+        profile.win_rate(Role::Ghost, RoundOutcome::SuccessfulExorcism),
+        1.0
+    );
+    assert_eq!(
+        profile.win_rate(Role::Spectator, RoundOutcome::SuccessfulExorcism),
+        0.0
+    );
+    assert_eq!(
+        profile.fastest(GhostType::Spirit, HouseLayoutKind::TwoRoom),
+        Some(42.0)
+    );
+    assert_eq!(
+        profile.fastest(GhostType::Spirit, HouseLayoutKind::ThreeRoom),
+        None
+    );
+    assert_eq!(
+        PlayerProfile::new("Egon").win_rate(Role::Investigator, RoundOutcome::WrongGhost),
+        0.0
+    );
+}
+
+#[test]
+fn chat_channels_follow_role_and_round() {
+    assert_eq!(
+        ChatChannel::for_sender(Some(Role::Ghost), false),
+        Some(ChatChannel::Lobby)
+    );
+    assert_eq!(
+        ChatChannel::for_sender(None, false),
+        Some(ChatChannel::Lobby)
+    );
+    assert_eq!(
+        ChatChannel::for_sender(Some(Role::Investigator), true),
+        Some(ChatChannel::Team)
+    );
+    assert_eq!(ChatChannel::for_sender(Some(Role::Ghost), true), None);
+    assert_eq!(ChatChannel::for_sender(Some(Role::Spectator), true), None);
+
+    assert!(ChatChannel::Team.readable_by(Some(Role::Spectator)));
+    assert!(!ChatChannel::Team.readable_by(Some(Role::Ghost)));
+    assert!(ChatChannel::Lobby.readable_by(Some(Role::Ghost)));
+    assert!(ChatChannel::Overheard.readable_by(Some(Role::Ghost)));
+    assert!(!ChatChannel::Overheard.readable_by(Some(Role::Spectator)));
+
+    assert_eq!(validate_chat("  hi  "), Ok("hi".to_string()));
+    assert_eq!(validate_chat("   "), Err(ChatRejection::Empty));
+    assert_eq!(
+        validate_chat(&"a".repeat(MAX_CHAT_LEN + 1)),
+        Err(ChatRejection::TooLong)
+    );
+}
+
+#[test]
+fn team_chat_range_follows_rooms_distance_and_radio() {
+    let layout = HouseLayout::two_room();
+    let speaker = Vec3::new(-6.0, 0.9, -6.0);
+    let same_room = Vec3::new(-6.0, 0.9, 6.0);
+    let nearby = Vec3::new(3.0, 0.9, -6.0);
+    let far = Vec3::new(6.0, 0.9, 6.0);
+
+    assert!(within_earshot(&layout, 0.0, speaker, same_room, false));
+    assert!(!within_earshot(&layout, 6.0, speaker, nearby, false));
+    assert!(within_earshot(&layout, 10.0, speaker, nearby, false));
+    assert!(!within_earshot(&layout, 6.0, speaker, far, false));
+    assert!(within_earshot(&layout, 6.0, speaker, far, true));
+
+    assert!(overheard_by_ghost(
+        &layout,
+        speaker,
+        Vec3::new(-1.0, 1.6, 8.0)
+    ));
+    assert!(!overheard_by_ghost(
+        &layout,
+        speaker,
+        Vec3::new(3.0, 1.6, -6.0)
+    ));
+}